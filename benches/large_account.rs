@@ -0,0 +1,87 @@
+//! Stress benchmarks against a generated 10,000-task account. The budgets in the README's
+//! Performance section are checked against these numbers.
+
+use chrono::Local;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::path::PathBuf;
+use todoist_cli::api::fixtures;
+use todoist_cli::controller::app::App;
+use todoist_cli::utils::filter::Filter;
+
+const TASKS: usize = 10_000;
+
+/// Tasks edited between syncs in the delta sync benchmark.
+const CHANGED: usize = 100;
+
+/// A filter exercising dates, priorities, projects, labels and negation.
+const FILTER: &str = "(today | overdue | 7 days) & !@waiting | p1 & #Work";
+
+/// Creates an app that syncs from a generated replay account in a scratch directory, which also
+/// holds its `tasks.db`.
+fn large_account() -> (App, tokio::runtime::Runtime, PathBuf) {
+    let dir = std::env::temp_dir().join("todoist-cli-bench");
+    let _ = std::fs::remove_dir_all(&dir);
+    fixtures::write_sample_account(&dir, TASKS, Local::now().date_naive()).unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+    // SAFETY: set before the runtime or any other thread starts
+    unsafe {
+        std::env::set_var("TODOIST_REPLAY", &dir);
+        std::env::set_var("TODOIST_CLI_CONFIG", dir.join("config.toml"));
+    }
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut app = App::new(String::new()).unwrap();
+    runtime.block_on(app.incremental_sync()).unwrap();
+    (app, runtime, dir)
+}
+
+fn benches(c: &mut Criterion) {
+    let (mut app, runtime, dir) = large_account();
+
+    // Dropping the sync token makes every sync a full one
+    c.bench_function("full sync 10k tasks", |b| {
+        b.iter(|| {
+            app.cache().clear_sync_token().unwrap();
+            runtime.block_on(app.incremental_sync()).unwrap()
+        })
+    });
+    fixtures::write_sample_changes(&dir, TASKS, CHANGED, Local::now().date_naive()).unwrap();
+    c.bench_function("delta sync 10k tasks", |b| {
+        b.iter(|| runtime.block_on(app.incremental_sync()).unwrap())
+    });
+    c.bench_function("cache save 10k tasks", |b| {
+        b.iter(|| app.cache().save_tasks(app.tasks()).unwrap())
+    });
+    c.bench_function("cache load 10k tasks", |b| {
+        b.iter(|| black_box(app.cache().load_tasks().unwrap()))
+    });
+
+    let today = Local::now().date_naive();
+    let filter = Filter::parse(FILTER, today).unwrap();
+    c.bench_function("filter 10k tasks", |b| {
+        b.iter(|| {
+            app.tasks()
+                .iter()
+                .filter(|task| filter.matches(task, app.projects(), today))
+                .count()
+        })
+    });
+
+    // A frame builds the rows in view; moving the selection scrolls through the whole list
+    c.bench_function("frame rows, 10k tasks", |b| {
+        b.iter(|| {
+            app.next();
+            black_box(app.visible_rows(50, 120, false).1.len())
+        })
+    });
+    app.set_filter(FILTER).unwrap();
+    c.bench_function("frame rows, 10k tasks filtered", |b| {
+        b.iter(|| {
+            app.next();
+            black_box(app.visible_rows(50, 120, false).1.len())
+        })
+    });
+}
+
+criterion_group!(large, benches);
+criterion_main!(large);
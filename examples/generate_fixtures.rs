@@ -0,0 +1,22 @@
+//! Writes replay fixtures for a large sample account, e.g. to try the TUI with 10,000 tasks:
+//!
+//! ```sh
+//! cargo run --example generate_fixtures -- 10000 target/large-account
+//! TODOIST_REPLAY=target/large-account cargo run
+//! ```
+
+use chrono::Local;
+use todoist_cli::api::fixtures;
+use todoist_cli::utils::error::AppResult;
+
+fn main() -> AppResult<()> {
+    let mut args = std::env::args().skip(1);
+    let count = match args.next() {
+        Some(count) => count.parse()?,
+        None => 10_000,
+    };
+    let dir = args.next().unwrap_or_else(|| "target/large-account".to_string());
+    fixtures::write_sample_account(dir.as_ref(), count, Local::now().date_naive())?;
+    println!("Wrote {} sample tasks to {}", count, dir);
+    Ok(())
+}
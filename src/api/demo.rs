@@ -0,0 +1,361 @@
+//! An in-memory Todoist account for `--demo`: answers API v1 requests from generated sample
+//! projects and tasks, and keeps the changes made to them until the app exits.
+
+use crate::api::client::task_from_json;
+use crate::api::fixtures;
+use crate::models::project::Project;
+use crate::utils::dates::{ParsedDue, parse_due};
+use crate::utils::error::AppResult;
+use crate::utils::filter::Filter;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use reqwest::{Request, Response, Url};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// Tasks in the demo account: enough to fill a screen and every view, few enough to read.
+const DEMO_TASKS: usize = 60;
+
+/// Sample projects, labels and tasks, as API v1 sends them.
+pub struct DemoAccount {
+    projects: Vec<Value>,
+    labels: Vec<Value>,
+    tasks: Vec<Value>,
+    completed: Vec<Value>,
+    /// Number used for the ID of the next added task or project.
+    next_id: usize,
+}
+
+impl DemoAccount {
+    /// Generates the sample account with tasks due around `today`.
+    pub fn new(today: NaiveDate) -> Self {
+        let results = |body: String| -> Vec<Value> {
+            let mut page: Value = serde_json::from_str(&body).unwrap_or_default();
+            serde_json::from_value(page["results"].take()).unwrap_or_default()
+        };
+        DemoAccount {
+            projects: results(fixtures::sample_projects_body()),
+            labels: results(fixtures::sample_labels_body()),
+            tasks: results(fixtures::sample_tasks_body(DEMO_TASKS, today)),
+            completed: Vec::new(),
+            next_id: DEMO_TASKS,
+        }
+    }
+
+    /// Answers a request as the Todoist API would, with a 404 for endpoints the demo lacks.
+    pub fn respond(&mut self, request: &Request) -> AppResult<Response> {
+        let url = request.url();
+        let path = url.path().trim_start_matches("/api/v1/");
+        let segments = path.split('/').collect::<Vec<_>>();
+        let query = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+        let raw = request.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+        let fields = serde_json::from_slice::<Value>(raw).unwrap_or_default();
+        let today = Local::now().date_naive();
+
+        let (status, body) = match (request.method().as_str(), segments.as_slice()) {
+            ("GET", ["user"]) => (200, json!({
+                "id": "demo",
+                "full_name": "Demo User",
+                "email": "demo@example.com",
+            })),
+            ("GET", ["projects"]) => (200, listing(self.projects_archived(false))),
+            ("GET", ["projects", "archived"]) => (200, listing(self.projects_archived(true))),
+            ("GET", ["labels"]) => (200, listing(self.labels.clone())),
+            // The demo projects aren't shared, so there's no one else's activity to report
+            ("GET", ["activities"]) => (200, listing(Vec::new())),
+            ("GET", ["projects", _, "collaborators"]) => (200, listing(Vec::new())),
+            ("POST", ["projects"]) => {
+                let project = json!({
+                    "id": self.new_id(),
+                    "name": fields["name"],
+                    "parent_id": fields["parent_id"],
+                    "is_favorite": false,
+                    "is_archived": false,
+                });
+                self.projects.push(project.clone());
+                (200, project)
+            }
+            ("POST", ["projects", id]) => match self.project_mut(id) {
+                Some(project) => {
+                    project["is_favorite"] = json!(fields["is_favorite"].as_bool().unwrap_or(false));
+                    (200, project.clone())
+                }
+                None => not_found(),
+            },
+            ("POST", ["projects", id, action @ ("archive" | "unarchive")]) => {
+                match self.project_mut(id) {
+                    Some(project) => {
+                        project["is_archived"] = json!(*action == "archive");
+                        (200, project.clone())
+                    }
+                    None => not_found(),
+                }
+            }
+            ("GET", ["tasks"]) => {
+                let tasks = self
+                    .tasks
+                    .iter()
+                    .filter(|t| query.get("project_id").is_none_or(|id| t["project_id"] == *id))
+                    .cloned()
+                    .collect();
+                (200, listing(tasks))
+            }
+            ("GET", ["tasks", "filter"]) => {
+                let query = query.get("query").map(String::as_str).unwrap_or_default();
+                match self.filter_tasks(query, today) {
+                    Ok(tasks) => (200, listing(tasks)),
+                    Err(e) => (400, json!({ "error": e.to_string() })),
+                }
+            }
+            ("GET", ["tasks", "completed", "by_completion_date"]) => {
+                (200, json!({ "items": self.completed }))
+            }
+            ("GET", ["tasks", "completed", "stats"]) => (200, self.stats(today)),
+            ("POST", ["tasks"]) => (200, self.add_task(&fields, today)),
+            // Quick Add's parsing is left to Todoist; the demo takes the text as the title
+            ("POST", ["tasks", "quick"]) => (200, self.add_task(&json!({ "content": fields["text"] }), today)),
+            ("POST" | "PATCH", ["tasks", id]) => match self.task_mut(id) {
+                Some(task) => {
+                    update_task(task, &fields, today);
+                    (200, task.clone())
+                }
+                None => not_found(),
+            },
+            ("POST", ["tasks", id, "close"]) => match self.close_task(id) {
+                Some(()) => (204, Value::Null),
+                None => not_found(),
+            },
+            ("POST", ["tasks", id, "reopen"]) => match self.reopen_task(id) {
+                Some(()) => (204, Value::Null),
+                None => not_found(),
+            },
+            ("POST", ["tasks", id, "move"]) => match self.task_mut(id) {
+                Some(task) => {
+                    task["project_id"] = fields["project_id"].clone();
+                    (200, task.clone())
+                }
+                None => not_found(),
+            },
+            ("DELETE", ["tasks", id]) => {
+                let before = self.tasks.len();
+                self.tasks.retain(|t| t["id"] != *id);
+                if self.tasks.len() < before { (204, Value::Null) } else { not_found() }
+            }
+            ("POST", ["comments"]) => (200, json!({ "id": self.new_id(), "content": fields["content"] })),
+            ("POST", ["sync"]) => (200, self.sync(raw, today)),
+            _ => not_found(),
+        };
+        let body = if body.is_null() { String::new() } else { body.to_string() };
+        Ok(http::Response::builder().status(status).body(body)?.into())
+    }
+
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        fixtures::sample_task_id(self.next_id)
+    }
+
+    fn projects_archived(&self, archived: bool) -> Vec<Value> {
+        self.projects
+            .iter()
+            .filter(|p| p["is_archived"].as_bool().unwrap_or(false) == archived)
+            .cloned()
+            .collect()
+    }
+
+    fn project_mut(&mut self, id: &str) -> Option<&mut Value> {
+        self.projects.iter_mut().find(|p| p["id"] == id)
+    }
+
+    fn task_mut(&mut self, id: &str) -> Option<&mut Value> {
+        self.tasks.iter_mut().find(|t| t["id"] == id)
+    }
+
+    /// Active tasks matching a filter query, evaluated locally like the TUI's `/` filter.
+    fn filter_tasks(&self, query: &str, today: NaiveDate) -> AppResult<Vec<Value>> {
+        let filter = Filter::parse(query, today)?;
+        let projects = serde_json::from_value::<Vec<Project>>(json!(self.projects))?;
+        Ok(self
+            .tasks
+            .iter()
+            .filter(|&t| {
+                task_from_json(t.clone()).is_some_and(|task| filter.matches(&task, &projects, today))
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Adds a task from the fields of a create request or an `item_add` command.
+    fn add_task(&mut self, fields: &Value, today: NaiveDate) -> Value {
+        let mut task = json!({
+            "id": self.new_id(),
+            "content": "",
+            "description": "",
+            "checked": false,
+            "priority": 1,
+            "project_id": self.projects[0]["id"],
+            "parent_id": null,
+            "labels": [],
+            "due": null,
+            "added_at": Utc::now().to_rfc3339(),
+        });
+        update_task(&mut task, fields, today);
+        self.tasks.push(task.clone());
+        task
+    }
+
+    /// Completes a task: recurring tasks move to their next date, others to the completed list.
+    fn close_task(&mut self, id: &str) -> Option<()> {
+        let index = self.tasks.iter().position(|t| t["id"] == id)?;
+        let due = &mut self.tasks[index]["due"];
+        if due["is_recurring"].as_bool().unwrap_or(false) {
+            let step = if due["string"].as_str().is_some_and(|s| s.contains("week")) { 7 } else { 1 };
+            let date = due["date"].as_str().unwrap_or_default();
+            let (day, time) = date.split_at(date.find('T').unwrap_or(date.len()));
+            if let Ok(day) = day.parse::<NaiveDate>() {
+                due["date"] = json!(format!("{}{}", day + Duration::days(step), time));
+            }
+            return Some(());
+        }
+        let mut task = self.tasks.remove(index);
+        task["checked"] = json!(true);
+        task["completed_at"] = json!(Utc::now().to_rfc3339());
+        self.completed.push(task);
+        Some(())
+    }
+
+    /// Moves a completed task back to the open ones; open tasks are left as they are.
+    fn reopen_task(&mut self, id: &str) -> Option<()> {
+        if self.tasks.iter().any(|t| t["id"] == id) {
+            return Some(());
+        }
+        let index = self.completed.iter().position(|t| t["id"] == id)?;
+        let mut task = self.completed.remove(index);
+        task["checked"] = json!(false);
+        task["completed_at"] = Value::Null;
+        self.tasks.push(task);
+        Some(())
+    }
+
+    /// Productivity stats with a daily goal of five and today's completions.
+    fn stats(&self, today: NaiveDate) -> Value {
+        let completed_today = self
+            .completed
+            .iter()
+            .filter_map(|t| t["completed_at"].as_str())
+            .filter_map(|at| DateTime::parse_from_rfc3339(at).ok())
+            .filter(|at| at.with_timezone(&Local).date_naive() == today)
+            .count();
+        json!({
+            "goals": { "daily_goal": 5 },
+            "days_items": [{ "date": today.format("%Y-%m-%d").to_string(), "total_completed": completed_today }],
+        })
+    }
+
+    /// Runs Sync API commands, of which the app sends `item_add` and `item_update`, and answers
+    /// reads with no location reminders and, when tasks are asked for, a full sync of the open
+    /// ones and the active projects: the demo keeps no history to send changes from. Saved
+    /// filters are the sample ones.
+    fn sync(&mut self, body: &[u8], today: NaiveDate) -> Value {
+        let form = Url::parse(&format!("demo:?{}", String::from_utf8_lossy(body)))
+            .map(|url| url.query_pairs().into_owned().collect::<HashMap<_, _>>())
+            .unwrap_or_default();
+        let Some(commands) = form.get("commands") else {
+            if form.get("resource_types").is_some_and(|types| types.contains("items")) {
+                return json!({
+                    "sync_token": "demo",
+                    "full_sync": true,
+                    "items": self.tasks,
+                    "projects": self.projects_archived(false),
+                    "reminders": [],
+                });
+            }
+            if form.get("resource_types").is_some_and(|types| types.contains("filters")) {
+                return json!({ "sync_token": "demo", "filters": fixtures::sample_filters() });
+            }
+            return json!({ "reminders": [] });
+        };
+        let commands = serde_json::from_str::<Vec<Value>>(commands).unwrap_or_default();
+        let mut sync_status = serde_json::Map::new();
+        let mut temp_id_mapping = serde_json::Map::new();
+        for command in commands {
+            let uuid = command["uuid"].as_str().unwrap_or_default().to_string();
+            let mut args = command["args"].clone();
+            if command["type"] == "item_update" {
+                let id = args["id"].as_str().unwrap_or_default().to_string();
+                let status = match self.task_mut(&id) {
+                    Some(task) => {
+                        update_task(task, &args, today);
+                        json!("ok")
+                    }
+                    None => json!({ "error": "Task not found" }),
+                };
+                sync_status.insert(uuid, status);
+                continue;
+            }
+            if command["type"] != "item_add" {
+                sync_status.insert(uuid, json!({ "error": "Not supported in demo mode" }));
+                continue;
+            }
+            // Commands nest the due date where the REST endpoints take flat fields
+            if let Some(due) = args["due"].as_object().cloned() {
+                for (key, value) in due {
+                    args[format!("due_{}", key)] = value;
+                }
+            }
+            let task = self.add_task(&args, today);
+            if let Some(temp_id) = command["temp_id"].as_str() {
+                temp_id_mapping.insert(temp_id.to_string(), task["id"].clone());
+            }
+            sync_status.insert(uuid, json!("ok"));
+        }
+        json!({ "sync_status": sync_status, "temp_id_mapping": temp_id_mapping })
+    }
+}
+
+/// Applies the fields of a create or update request to a task.
+fn update_task(task: &mut Value, fields: &Value, today: NaiveDate) {
+    for key in ["content", "description", "priority", "labels", "project_id", "parent_id"] {
+        if !fields[key].is_null() {
+            task[key] = fields[key].clone();
+        }
+    }
+    if fields.get("assignee_id").is_some() {
+        task["responsible_uid"] = fields["assignee_id"].clone();
+    }
+    if let Some(checked) = fields["checked"].as_bool() {
+        task["checked"] = json!(checked);
+    }
+    if let Some(date) = fields["due_date"].as_str() {
+        task["due"] = json!({ "date": date, "string": date, "is_recurring": false });
+    } else if let Some(datetime) = fields["due_datetime"].as_str() {
+        // API v1 reports timed dues as a UTC timestamp in `date`
+        let date = DateTime::parse_from_rfc3339(datetime)
+            .map(|at| at.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%SZ").to_string())
+            .unwrap_or_else(|_| datetime.to_string());
+        task["due"] = json!({ "date": date, "string": datetime, "is_recurring": false });
+    } else if let Some(string) = fields["due_string"].as_str() {
+        task["due"] = due_from_string(string, today);
+    }
+    task["updated_at"] = json!(Utc::now().to_rfc3339());
+}
+
+/// Resolves a natural-language due string; recurring ones start today.
+fn due_from_string(string: &str, today: NaiveDate) -> Value {
+    let date = match parse_due(string, today) {
+        Some(ParsedDue::Date(date)) => date.format("%Y-%m-%d").to_string(),
+        Some(ParsedDue::DateTime(at)) => at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        None if string.trim().is_empty() || string.eq_ignore_ascii_case("no date") => {
+            return Value::Null;
+        }
+        None => today.format("%Y-%m-%d").to_string(),
+    };
+    let is_recurring = string.trim_start().to_ascii_lowercase().starts_with("every");
+    json!({ "date": date, "string": string, "is_recurring": is_recurring })
+}
+
+fn listing(results: Vec<Value>) -> Value {
+    json!({ "results": results, "next_cursor": null })
+}
+
+fn not_found() -> (u16, Value) {
+    (404, json!({ "error": "Not found" }))
+}
@@ -0,0 +1,354 @@
+use crate::api::demo::DemoAccount;
+use crate::utils::error::AppResult;
+use chrono::{Duration, NaiveDate};
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// How the API client talks to the network.
+#[derive(Clone)]
+pub enum HttpMode {
+    Live,
+    /// Send requests and save each response under the directory.
+    Record(PathBuf),
+    /// Answer requests from saved responses without touching the network.
+    Replay(PathBuf),
+    /// Answer requests from an in-memory sample account, for `--demo`.
+    Demo(Arc<Mutex<DemoAccount>>),
+}
+
+impl HttpMode {
+    /// Reads the mode from `TODOIST_REPLAY` or `TODOIST_RECORD`, defaulting to live requests.
+    pub fn from_env() -> Self {
+        if let Some(dir) = std::env::var_os("TODOIST_REPLAY") {
+            HttpMode::Replay(PathBuf::from(dir))
+        } else if let Some(dir) = std::env::var_os("TODOIST_RECORD") {
+            HttpMode::Record(PathBuf::from(dir))
+        } else {
+            HttpMode::Live
+        }
+    }
+}
+
+/// A recorded HTTP response.
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    status: u16,
+    body: String,
+}
+
+/// Fixture file for a request, named after its method and path (the query is ignored).
+pub fn path_for(dir: &Path, request: &Request) -> PathBuf {
+    let name = format!("{}{}", request.method(), request.url().path())
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    dir.join(format!("{}.json", name))
+}
+
+/// Saves a live response to the fixture file and returns an equivalent response.
+pub async fn record(path: &Path, response: Response) -> AppResult<Response> {
+    let status = response.status();
+    let body = response.text().await?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let fixture = Fixture {
+        status: status.as_u16(),
+        body: body.clone(),
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&fixture)?)?;
+    Ok(http::Response::builder()
+        .status(status.as_u16())
+        .body(body)?
+        .into())
+}
+
+/// Builds a response from a previously recorded fixture file.
+pub fn replay(path: &Path) -> AppResult<Response> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("No fixture {}: {}", path.display(), e))?;
+    let fixture: Fixture = serde_json::from_str(&contents)?;
+    Ok(http::Response::builder()
+        .status(fixture.status)
+        .body(fixture.body)?
+        .into())
+}
+
+/// ID, name, Todoist color and parent project ID of each sample project.
+const SAMPLE_PROJECTS: &[(&str, &str, &str, Option<&str>)] = &[
+    ("6Jf8VQXxpwv56VQ7", "Inbox", "charcoal", None),
+    ("6Jf8VQXxpwv56VQ8", "Work", "blue", None),
+    ("6Jf8VQXxpwv56VQA", "Home", "green", None),
+    ("6Jf8VQXxpwv56VQB", "Errands", "orange", Some("6Jf8VQXxpwv56VQA")),
+    ("6Jf8VQXxpwv56VQC", "Side project", "grape", None),
+];
+
+/// Task titles by project, in the order of `SAMPLE_PROJECTS`.
+const SAMPLE_TITLES: &[&[&str]] = &[
+    &[
+        "Reply to Sam about the lease", "Renew passport", "Sort out photo backups",
+        "Look into a new phone plan", "Return library books", "Unsubscribe from newsletters",
+        "Find a plumber", "Write thank-you notes", "Back up laptop", "Cancel gym trial",
+        "Check car insurance quotes", "Plan weekend hike",
+    ],
+    &[
+        "Review quarterly report", "Prepare slides for all-hands", "Draft release notes",
+        "1:1 with Priya", "Review pull request #482", "Update onboarding docs",
+        "Book team offsite venue", "Send invoice to Acme", "Triage bug backlog",
+        "Write performance review", "Sync with design on the roadmap", "Submit expense report",
+    ],
+    &[
+        "Water the plants", "Clean the garage", "Fix the leaking tap", "Pay the water bill",
+        "Change bedsheets", "Descale the kettle", "Replace smoke alarm battery",
+        "Take out recycling", "Vacuum the stairs", "Defrost the freezer", "Mow the lawn",
+        "Wash the windows",
+    ],
+    &[
+        "Buy groceries", "Pick up dry cleaning", "Post the parcel", "Get bike tires replaced",
+        "Buy a birthday gift for Alex", "Drop off donations", "Refill prescription",
+        "Buy stamps", "Collect the repaired watch", "Get a spare key cut", "Buy printer ink",
+        "Pick up concert tickets",
+    ],
+    &[
+        "Sketch the landing page", "Write the README", "Set up CI", "Record a demo video",
+        "Answer GitHub issues", "Draft the launch post", "Add dark mode", "Profile startup time",
+        "Publish version 0.2", "Design a logo", "Collect beta feedback", "Write the changelog",
+    ],
+];
+
+/// Name and Todoist color of each sample label.
+const SAMPLE_LABELS: &[(&str, &str)] = &[
+    ("errands", "orange"),
+    ("calls", "sky_blue"),
+    ("deep-work", "violet"),
+    ("waiting", "grey"),
+    ("home", "lime_green"),
+    ("15min", "salmon"),
+];
+
+/// Name, query and Todoist color of each sample saved filter; the first is a favorite.
+const SAMPLE_FILTERS: &[(&str, &str, &str)] = &[
+    ("Work today", "today & #Work", "blue"),
+    ("Quick wins", "@15min", "salmon"),
+];
+
+/// The sample saved filters as the Sync API sends them.
+pub fn sample_filters() -> Vec<serde_json::Value> {
+    SAMPLE_FILTERS
+        .iter()
+        .enumerate()
+        .map(|(i, (name, query, color))| {
+            serde_json::json!({
+                "id": format!("4638{:03}", i),
+                "name": name,
+                "query": query,
+                "color": color,
+                "is_favorite": i == 0,
+            })
+        })
+        .collect()
+}
+
+/// Body of a projects response for the sample account.
+pub fn sample_projects_body() -> String {
+    serde_json::json!({ "results": sample_projects(), "next_cursor": null }).to_string()
+}
+
+/// The sample projects as API v1 sends them.
+fn sample_projects() -> Vec<serde_json::Value> {
+    SAMPLE_PROJECTS
+        .iter()
+        .enumerate()
+        .map(|(i, (id, name, color, parent_id))| {
+            serde_json::json!({
+                "id": id,
+                "name": name,
+                "color": color,
+                "parent_id": parent_id,
+                "is_favorite": i == 1,
+            })
+        })
+        .collect()
+}
+
+/// Body of a labels response for the sample account.
+pub fn sample_labels_body() -> String {
+    let labels = SAMPLE_LABELS
+        .iter()
+        .enumerate()
+        .map(|(i, (name, color))| {
+            serde_json::json!({
+                "id": format!("2156154{:03}", i),
+                "name": name,
+                "color": color,
+                "is_favorite": i == 2,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({ "results": labels, "next_cursor": null }).to_string()
+}
+
+/// Body of a single-page tasks response with `count` varied, deterministic tasks due around
+/// `today`: a mix of overdue, upcoming, timed, recurring, undated, labeled and subtasks.
+pub fn sample_tasks_body(count: usize, today: NaiveDate) -> String {
+    serde_json::json!({ "results": sample_tasks(count, today), "next_cursor": null }).to_string()
+}
+
+/// Body of a Sync API response holding the same tasks and projects as a full sync, without
+/// reminders.
+pub fn sample_sync_body(count: usize, today: NaiveDate) -> String {
+    serde_json::json!({
+        "sync_token": "sample",
+        "full_sync": true,
+        "items": sample_tasks(count, today),
+        "projects": sample_projects(),
+        "reminders": [],
+    })
+    .to_string()
+}
+
+/// Body of a delta Sync API response in which the first `changed` of `count` sample tasks were
+/// edited since the last sync.
+pub fn sample_sync_delta_body(count: usize, changed: usize, today: NaiveDate) -> String {
+    let items = sample_tasks(count, today)
+        .into_iter()
+        .take(changed)
+        .map(|mut task| {
+            task["content"] = format!("{} (edited)", task["content"].as_str().unwrap_or_default()).into();
+            task
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "sync_token": "sample",
+        "full_sync": false,
+        "items": items,
+        "projects": [],
+        "reminders": [],
+    })
+    .to_string()
+}
+
+/// The sample tasks as API v1 sends them.
+fn sample_tasks(count: usize, today: NaiveDate) -> Vec<serde_json::Value> {
+    (0..count)
+        .map(|i| {
+            let project = i % SAMPLE_PROJECTS.len();
+            let titles = SAMPLE_TITLES[project];
+            let title = titles[(i / SAMPLE_PROJECTS.len()) % titles.len()];
+            let date = today + Duration::days((i % 41) as i64 - 10);
+            let due = match i % 7 {
+                0 | 1 => serde_json::Value::Null,
+                2 => serde_json::json!({
+                    "date": format!("{}T{:02}:30:00", date.format("%Y-%m-%d"), 8 + i % 10),
+                    "string": "at a time",
+                    "is_recurring": false,
+                }),
+                3 => serde_json::json!({
+                    "date": date.format("%Y-%m-%d").to_string(),
+                    "string": "every week",
+                    "is_recurring": true,
+                }),
+                _ => serde_json::json!({
+                    "date": date.format("%Y-%m-%d").to_string(),
+                    "string": date.format("%b %-d").to_string(),
+                    "is_recurring": false,
+                }),
+            };
+            // Timed tasks take 30 to 90 minutes
+            let duration = (i % 7 == 2).then(|| serde_json::json!({ "amount": 30 + 30 * (i % 3), "unit": "minute" }));
+            let labels = match i % 5 {
+                0 => vec![],
+                n => vec![SAMPLE_LABELS[(i + n) % SAMPLE_LABELS.len()].0],
+            };
+            // Every tenth task is a subtask of the previous task in its project
+            let parent_id = (i % 10 == 9).then(|| sample_task_id(i - SAMPLE_PROJECTS.len()));
+            serde_json::json!({
+                "id": sample_task_id(i),
+                "content": title,
+                "description": if i % 6 == 0 { "Notes from the last meeting" } else { "" },
+                "checked": false,
+                "priority": 1 + (i % 4),
+                "project_id": SAMPLE_PROJECTS[project].0,
+                "parent_id": parent_id,
+                "labels": labels,
+                "due": due,
+                "duration": duration,
+                "added_at": format!("{}T09:00:00Z", (today - Duration::days((i % 90) as i64)).format("%Y-%m-%d")),
+            })
+        })
+        .collect()
+}
+
+/// Todoist-style ID of the `i`th sample task.
+pub(crate) fn sample_task_id(i: usize) -> String {
+    format!("6X7s{:012x}", i)
+}
+
+/// Writes replay fixtures for a sample account with `count` tasks into `dir`, for stress tests:
+/// `TODOIST_REPLAY=<dir>` then runs against it.
+pub fn write_sample_account(dir: &Path, count: usize, today: NaiveDate) -> AppResult<()> {
+    std::fs::create_dir_all(dir)?;
+    let fixtures = [
+        ("GET_api_v1_projects.json", sample_projects_body()),
+        ("GET_api_v1_projects_archived.json", r#"{"results": [], "next_cursor": null}"#.to_string()),
+        ("GET_api_v1_tasks.json", sample_tasks_body(count, today)),
+        ("GET_api_v1_labels.json", sample_labels_body()),
+        ("POST_api_v1_sync.json", sample_sync_body(count, today)),
+    ];
+    for (name, body) in fixtures {
+        let fixture = Fixture { status: 200, body };
+        std::fs::write(dir.join(name), serde_json::to_string_pretty(&fixture)?)?;
+    }
+    Ok(())
+}
+
+/// Replaces the Sync API fixture written by `write_sample_account` with a delta in which
+/// `changed` tasks were edited, so later syncs with a stored token replay a small change.
+pub fn write_sample_changes(dir: &Path, count: usize, changed: usize, today: NaiveDate) -> AppResult<()> {
+    let fixture = Fixture { status: 200, body: sample_sync_delta_body(count, changed, today) };
+    std::fs::write(dir.join("POST_api_v1_sync.json"), serde_json::to_string_pretty(&fixture)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temp dir; the caller removes it.
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("todoist-cli-fixtures-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn get(url: &str) -> Request {
+        reqwest::Client::new().get(url).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn recorded_response_replays_for_the_same_route() {
+        let dir = temp_dir();
+        let recorded = get("https://api.todoist.com/api/v1/tasks?limit=200");
+        let live: Response = http::Response::builder().status(201).body(r#"{"results": []}"#).unwrap().into();
+        let response = record(&path_for(&dir, &recorded), live).await.unwrap();
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.text().await.unwrap(), r#"{"results": []}"#);
+
+        // The query string doesn't pick the fixture
+        let path = path_for(&dir, &get("https://api.todoist.com/api/v1/tasks?cursor=abc"));
+        assert_eq!(path, dir.join("GET_api_v1_tasks.json"));
+        let replayed = replay(&path).unwrap();
+        assert_eq!(replayed.status(), 201);
+        assert_eq!(replayed.text().await.unwrap(), r#"{"results": []}"#);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unknown_route_names_the_missing_fixture() {
+        let dir = temp_dir();
+        let path = path_for(&dir, &get("https://api.todoist.com/api/v1/tasks/123/move"));
+        let error = replay(&path).unwrap_err().to_string();
+        assert!(error.starts_with("No fixture "), "{}", error);
+        assert!(error.contains("GET_api_v1_tasks_123_move.json"), "{}", error);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::utils::error::AppResult;
+use chrono::Local;
+use reqwest::{Request, Response};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Bodies longer than this many characters are cut in the log.
+const MAX_BODY_CHARS: usize = 2000;
+
+/// Appends sanitized request/response dumps to a file for bug reports, with the API token
+/// replaced wherever it appears.
+#[derive(Clone)]
+pub struct HttpLog {
+    file: Arc<Mutex<File>>,
+    token: String,
+}
+
+impl HttpLog {
+    /// Opens the log file for appending, creating it if needed.
+    pub fn create(path: &Path, token: &str) -> AppResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow::anyhow!("Cannot open HTTP log {}: {}", path.display(), e))?;
+        Ok(HttpLog {
+            file: Arc::new(Mutex::new(file)),
+            token: token.to_string(),
+        })
+    }
+
+    /// Logs the method, URL, and body of a request about to be sent.
+    pub fn request(&self, request: &Request) {
+        let mut entry = format!(
+            "{} {} {}\n",
+            Local::now().to_rfc3339(),
+            request.method(),
+            request.url()
+        );
+        if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+            entry.push_str(&format!(
+                "> {}\n",
+                self.body(&String::from_utf8_lossy(body))
+            ));
+        }
+        self.write(&entry);
+    }
+
+    /// Logs the outcome of a request sent at `sent`, returning an equivalent response since
+    /// reading the body consumes it.
+    pub async fn response(
+        &self,
+        result: AppResult<Response>,
+        sent: Instant,
+    ) -> AppResult<Response> {
+        let elapsed = sent.elapsed().as_millis();
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                self.write(&format!(
+                    "< error after {} ms: {}\n\n",
+                    elapsed,
+                    self.redact(&e.to_string())
+                ));
+                return Err(e);
+            }
+        };
+        let status = response.status();
+        let body = response.text().await?;
+        self.write(&format!(
+            "< {} ({} ms)\n< {}\n\n",
+            status,
+            elapsed,
+            self.body(&body)
+        ));
+        Ok(http::Response::builder()
+            .status(status.as_u16())
+            .body(body)?
+            .into())
+    }
+
+    /// Redacts and truncates a body for the log.
+    fn body(&self, body: &str) -> String {
+        let body = self.redact(body);
+        let chars = body.chars().count();
+        if chars <= MAX_BODY_CHARS {
+            return body;
+        }
+        let cut: String = body.chars().take(MAX_BODY_CHARS).collect();
+        format!("{}… ({} more characters)", cut, chars - MAX_BODY_CHARS)
+    }
+
+    fn redact(&self, text: &str) -> String {
+        if self.token.is_empty() {
+            return text.to_string();
+        }
+        text.replace(&self.token, "[REDACTED]")
+    }
+
+    /// Writes an entry; logging failures never fail the request itself.
+    fn write(&self, entry: &str) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(entry.as_bytes());
+        }
+    }
+}
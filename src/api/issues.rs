@@ -0,0 +1,112 @@
+use crate::utils::error::AppResult;
+use reqwest::Client;
+use serde::Deserialize;
+
+/// A GitHub or GitLab issue referenced by its web URL.
+pub enum IssueRef {
+    GitHub {
+        owner: String,
+        repo: String,
+        number: u64,
+    },
+    GitLab {
+        host: String,
+        project: String,
+        number: u64,
+    },
+}
+
+/// The issue fields needed to create and track a task.
+pub struct Issue {
+    pub title: String,
+    pub closed: bool,
+}
+
+#[derive(Deserialize)]
+struct IssueResponse {
+    title: String,
+    state: String,
+}
+
+impl IssueRef {
+    /// Parses a GitHub (`/owner/repo/issues/N`) or GitLab (`/group/project/-/issues/N`) issue URL.
+    pub fn parse(url: &str) -> AppResult<Self> {
+        let invalid = || anyhow::anyhow!("Not a GitHub or GitLab issue URL: {}", url);
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .ok_or_else(invalid)?;
+        let (host, path) = rest.split_once('/').ok_or_else(invalid)?;
+        let path = path.trim_end_matches('/');
+
+        if let Some((project, number)) = path.split_once("/-/issues/") {
+            return Ok(IssueRef::GitLab {
+                host: host.to_string(),
+                project: project.to_string(),
+                number: number.parse().map_err(|_| invalid())?,
+            });
+        }
+        if host == "github.com" {
+            let parts = path.split('/').collect::<Vec<_>>();
+            if let [owner, repo, "issues" | "pull", number] = parts.as_slice() {
+                return Ok(IssueRef::GitHub {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    number: number.parse().map_err(|_| invalid())?,
+                });
+            }
+        }
+        Err(invalid())
+    }
+
+    /// Fetches the issue title and state, using `GITHUB_TOKEN`/`GITLAB_TOKEN` when set.
+    pub async fn fetch(&self) -> AppResult<Issue> {
+        let client = Client::new();
+        let request = match self {
+            IssueRef::GitHub {
+                owner,
+                repo,
+                number,
+            } => {
+                let request = client
+                    .get(format!(
+                        "https://api.github.com/repos/{}/{}/issues/{}",
+                        owner, repo, number
+                    ))
+                    .header("User-Agent", "todoist-cli")
+                    .header("Accept", "application/vnd.github+json");
+                match std::env::var("GITHUB_TOKEN") {
+                    Ok(token) => request.bearer_auth(token),
+                    Err(_) => request,
+                }
+            }
+            IssueRef::GitLab {
+                host,
+                project,
+                number,
+            } => {
+                let request = client.get(format!(
+                    "https://{}/api/v4/projects/{}/issues/{}",
+                    host,
+                    project.replace('/', "%2F"),
+                    number
+                ));
+                match std::env::var("GITLAB_TOKEN") {
+                    Ok(token) => request.header("PRIVATE-TOKEN", token),
+                    Err(_) => request,
+                }
+            }
+        };
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("Failed to fetch issue: status {}", status));
+        }
+        let issue: IssueResponse = response.json().await?;
+        Ok(Issue {
+            title: issue.title,
+            closed: issue.state == "closed",
+        })
+    }
+}
@@ -0,0 +1,97 @@
+use reqwest::Method;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Request totals for one endpoint.
+#[derive(Clone, Copy, Default)]
+pub struct EndpointStats {
+    pub requests: u64,
+    /// Failed requests: network errors and non-success statuses, including 429 rate limits.
+    pub errors: u64,
+    /// Cumulative latency in milliseconds.
+    pub total_ms: u64,
+}
+
+impl EndpointStats {
+    /// Average latency in milliseconds.
+    pub fn average_ms(&self) -> u64 {
+        self.total_ms.checked_div(self.requests).unwrap_or(0)
+    }
+}
+
+/// Totals by endpoint name.
+pub type Usage = Vec<(String, EndpointStats)>;
+
+/// API usage of a session by endpoint, shared between clones of the API client.
+#[derive(Clone, Default)]
+pub struct ApiMetrics {
+    endpoints: Arc<Mutex<BTreeMap<String, EndpointStats>>>,
+}
+
+impl ApiMetrics {
+    /// Counts a request to `path` that took `elapsed`.
+    pub fn record(&self, method: &Method, path: &str, failed: bool, elapsed: Duration) {
+        let Ok(mut endpoints) = self.endpoints.lock() else {
+            return;
+        };
+        let stats = endpoints.entry(endpoint(method, path)).or_default();
+        stats.requests += 1;
+        stats.errors += failed as u64;
+        stats.total_ms += elapsed.as_millis() as u64;
+    }
+
+    /// Returns the totals so far, sorted by endpoint.
+    pub fn snapshot(&self) -> Usage {
+        self.endpoints
+            .lock()
+            .map(|endpoints| endpoints.iter().map(|(k, v)| (k.clone(), *v)).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Names an endpoint by method and path, with task and project IDs replaced by `{id}` so
+/// requests for different tasks add up, e.g. `POST /api/v1/tasks/{id}/close`.
+fn endpoint(method: &Method, path: &str) -> String {
+    let path = path
+        .split('/')
+        .map(|segment| if is_id(segment) { "{id}" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{} {}", method, path)
+}
+
+/// Todoist IDs mix letters and digits, unlike version segments such as `v1`.
+fn is_id(segment: &str) -> bool {
+    let version = segment
+        .strip_prefix('v')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    !version && segment.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Formats totals as aligned rows, busiest endpoint first.
+pub fn format_table(stats: &[(String, EndpointStats)]) -> Vec<String> {
+    let mut stats = stats.to_vec();
+    stats.sort_by(|a, b| b.1.requests.cmp(&a.1.requests).then(a.0.cmp(&b.0)));
+    let width = stats
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let mut lines = vec![format!(
+        "{:<width$}  {:>8}  {:>6}  {:>9}  {:>8}",
+        "ENDPOINT", "REQUESTS", "ERRORS", "TOTAL MS", "AVG MS"
+    )];
+    for (name, s) in &stats {
+        lines.push(format!(
+            "{:<width$}  {:>8}  {:>6}  {:>9}  {:>8}",
+            name,
+            s.requests,
+            s.errors,
+            s.total_ms,
+            s.average_ms()
+        ));
+    }
+    lines
+}
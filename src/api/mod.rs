@@ -1 +1,7 @@
-pub mod client;
\ No newline at end of file
+pub mod client;
+pub mod demo;
+pub mod fixtures;
+pub mod http_log;
+pub mod issues;
+pub mod metrics;
+pub mod sync;
\ No newline at end of file
@@ -0,0 +1,164 @@
+use crate::models::task::{NewTask, TaskUpdate};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// Maximum number of commands the Sync API accepts per request.
+pub const MAX_COMMANDS: usize = 100;
+
+/// Returns true for the temporary IDs given to objects created offline, which are UUIDs until
+/// Todoist maps them to real IDs.
+pub fn is_temp_id(id: &str) -> bool {
+    uuid::Uuid::parse_str(id).is_ok()
+}
+
+/// A single write command for the Todoist Sync API.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SyncCommand {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_id: Option<String>,
+    pub args: Value,
+}
+
+impl SyncCommand {
+    /// Builds an `item_add` command creating the given task.
+    pub fn item_add(new_task: &NewTask) -> Self {
+        let mut args = json!({ "content": new_task.content });
+        if let Some(description) = &new_task.description {
+            args["description"] = json!(description);
+        }
+        if let Some(project_id) = &new_task.project_id {
+            args["project_id"] = json!(project_id);
+        }
+        if let Some(parent_id) = &new_task.parent_id {
+            args["parent_id"] = json!(parent_id);
+        }
+        if let Some(priority) = new_task.priority {
+            args["priority"] = json!(priority);
+        }
+        if !new_task.labels.is_empty() {
+            args["labels"] = json!(new_task.labels);
+        }
+        if let Some(datetime) = &new_task.due_datetime {
+            args["due"] = json!({ "date": datetime });
+        } else if let Some(date) = &new_task.due_date {
+            args["due"] = json!({ "date": date });
+        } else if let Some(string) = &new_task.due_string {
+            args["due"] = json!({ "string": string });
+        }
+        if let Some(date) = &new_task.deadline_date {
+            args["deadline"] = json!({ "date": date });
+        }
+        SyncCommand {
+            kind: "item_add".to_string(),
+            uuid: uuid::Uuid::new_v4().to_string(),
+            temp_id: Some(uuid::Uuid::new_v4().to_string()),
+            args,
+        }
+    }
+
+    /// Builds an `item_update` command changing the given fields of a task.
+    pub fn item_update(todoist_id: &str, mut args: Value) -> Self {
+        args["id"] = json!(todoist_id);
+        Self::for_task("item_update", args)
+    }
+
+    /// Builds the commands applying a partial task update: an `item_update` for its fields,
+    /// then `item_close` or `item_uncomplete` when it completes or reopens the task.
+    pub fn task_update(todoist_id: &str, update: &TaskUpdate) -> Vec<Self> {
+        let mut args = json!({});
+        if let Some(content) = &update.content {
+            args["content"] = json!(content);
+        }
+        if let Some(description) = &update.description {
+            args["description"] = json!(description);
+        }
+        if let Some(priority) = update.priority {
+            args["priority"] = json!(priority);
+        }
+        if let Some(datetime) = &update.due_datetime {
+            args["due"] = json!({ "date": datetime });
+        } else if let Some(date) = &update.due_date {
+            args["due"] = json!({ "date": date });
+        } else if let Some(string) = &update.due_string {
+            args["due"] = json!({ "string": string });
+        }
+        if let Some(assignee) = &update.assignee_id {
+            args["responsible_uid"] = json!(assignee);
+        }
+        let mut commands = Vec::new();
+        if args.as_object().is_some_and(|args| !args.is_empty()) {
+            commands.push(Self::item_update(todoist_id, args));
+        }
+        match update.checked {
+            Some(true) => commands.push(Self::item_close(todoist_id)),
+            Some(false) => commands.push(Self::item_uncomplete(todoist_id)),
+            None => {}
+        }
+        commands
+    }
+
+    /// Builds an `item_close` command completing a task.
+    pub fn item_close(todoist_id: &str) -> Self {
+        Self::for_task("item_close", json!({ "id": todoist_id }))
+    }
+
+    /// Builds an `item_uncomplete` command reopening a task.
+    pub fn item_uncomplete(todoist_id: &str) -> Self {
+        Self::for_task("item_uncomplete", json!({ "id": todoist_id }))
+    }
+
+    /// Builds an `item_delete` command deleting a task.
+    pub fn item_delete(todoist_id: &str) -> Self {
+        Self::for_task("item_delete", json!({ "id": todoist_id }))
+    }
+
+    fn for_task(kind: &str, args: Value) -> Self {
+        SyncCommand {
+            kind: kind.to_string(),
+            uuid: uuid::Uuid::new_v4().to_string(),
+            temp_id: None,
+            args,
+        }
+    }
+
+    /// Todoist ID of the task the command changes, unless it adds one.
+    pub fn task_id(&self) -> Option<&str> {
+        self.args["id"].as_str()
+    }
+
+    /// The command with task IDs that were temporary when it was queued replaced by the real
+    /// ones from `temp_id_mapping`.
+    pub fn with_ids(&self, temp_id_mapping: &HashMap<String, String>) -> Self {
+        let mut command = self.clone();
+        for key in ["id", "parent_id"] {
+            if let Some(id) = command.args[key].as_str().and_then(|id| temp_id_mapping.get(id)) {
+                command.args[key] = json!(id);
+            }
+        }
+        command
+    }
+}
+
+/// Response to a batch of Sync API commands.
+#[derive(Deserialize)]
+pub struct SyncResponse {
+    #[serde(default)]
+    pub sync_status: HashMap<String, Value>,
+    #[serde(default)]
+    pub temp_id_mapping: HashMap<String, String>,
+}
+
+impl SyncResponse {
+    /// Returns the error reported for a command, if it failed.
+    pub fn error_for(&self, command: &SyncCommand) -> Option<String> {
+        match self.sync_status.get(&command.uuid) {
+            Some(Value::String(status)) if status == "ok" => None,
+            Some(status) => Some(status.to_string()),
+            None => Some("missing from sync status".to_string()),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::cli::output::{self, OutputMode};
+use crate::config::settings::Settings;
+use crate::controller::app::SHORT_ID_LEN;
+use crate::db::archive;
+use crate::models::project::Project;
+use crate::utils::error::AppResult;
+use crate::utils::text::unique_prefix_len;
+use chrono::{DateTime, Local};
+use clap::Subcommand;
+
+/// Project archive actions.
+#[derive(Subcommand)]
+pub enum ArchiveAction {
+    /// Lists the saved projects, oldest first
+    List,
+    /// Prints the tasks a project had when it went away
+    Show {
+        /// Archive file from `archive list`, or a project name or ID for its newest archive
+        project: String,
+    },
+}
+
+/// Runs an archive action on the files in `archive_dir`.
+pub fn run(action: &ArchiveAction, color: bool) -> AppResult<()> {
+    let settings = Settings::load()?;
+    let dir = &settings.archive_dir;
+    match action {
+        ArchiveAction::List => {
+            let archives = archive::list(dir)?;
+            if archives.is_empty() {
+                println!("No archived projects in {}", dir.display());
+            }
+            for path in archives {
+                let archive = archive::load(&path)?;
+                let plural = if archive.tasks.len() == 1 { "" } else { "s" };
+                println!(
+                    "{} {} ({} task{}) {}",
+                    local_time(&archive.archived_at),
+                    archive.project,
+                    archive.tasks.len(),
+                    plural,
+                    path.display()
+                );
+            }
+        }
+        ArchiveAction::Show { project } => {
+            let (path, archive) = archive::find(dir, project)?;
+            println!("{} as of {} ({})", archive.project, local_time(&archive.archived_at), path.display());
+            // Name the project in each row, though it no longer exists
+            let projects = [Project {
+                id: archive.project_id.clone(),
+                name: archive.project.clone(),
+                is_archived: true,
+                is_favorite: false,
+                is_shared: false,
+                color: String::new(),
+                parent_id: None,
+            }];
+            let tasks = archive.tasks.iter().collect::<Vec<_>>();
+            let id_len = unique_prefix_len(tasks.iter().map(|t| t.todoist_id.as_str()), SHORT_ID_LEN);
+            let today = Local::now().date_naive();
+            output::print_tasks(&tasks, &projects, today, settings.relative_dates, color, OutputMode::Plain, id_len);
+        }
+    }
+    Ok(())
+}
+
+/// Formats an RFC 3339 time as local "YYYY-MM-DD HH:MM", or leaves it as it is.
+fn local_time(at: &str) -> String {
+    DateTime::parse_from_rfc3339(at)
+        .map(|at| at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or(at.to_string())
+}
@@ -0,0 +1,48 @@
+use crate::config::settings::Settings;
+use crate::db::backup;
+use crate::db::cache::Cache;
+use crate::utils::error::AppResult;
+use clap::Subcommand;
+use std::path::PathBuf;
+
+/// Backup actions.
+#[derive(Subcommand)]
+pub enum BackupAction {
+    /// Snapshots the cache now, whatever the configured schedule
+    Now,
+    /// Lists snapshots, oldest first
+    List,
+    /// Replaces the cache with a snapshot, first saving the current cache as a new snapshot
+    Restore {
+        /// Snapshot file, e.g. from `backup list`
+        file: PathBuf,
+    },
+}
+
+/// Runs a backup action with the `[backups]` settings, or their defaults when unset.
+pub fn run(action: &BackupAction) -> AppResult<()> {
+    let settings = Settings::load()?.backups.unwrap_or_default();
+    match action {
+        BackupAction::Now => {
+            if let Some(path) = backup::snapshot(&Cache::new()?, &settings, true)? {
+                println!("Saved {}", path.display());
+            }
+        }
+        BackupAction::List => {
+            let snapshots = backup::list(&settings.dir)?;
+            if snapshots.is_empty() {
+                println!("No backups in {}", settings.dir.display());
+            }
+            for path in snapshots {
+                println!("{}", path.display());
+            }
+        }
+        BackupAction::Restore { file } => {
+            if let Some(path) = backup::restore(file, &settings)? {
+                println!("Saved the current cache as {}", path.display());
+            }
+            println!("Restored {}; the next sync refreshes tasks from Todoist", file.display());
+        }
+    }
+    Ok(())
+}
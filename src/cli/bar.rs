@@ -0,0 +1,66 @@
+use crate::cli::statusline::Summary;
+use crate::models::task::Task;
+use chrono::Local;
+use clap::ValueEnum;
+use serde_json::json;
+
+const OVERDUE_COLOR: &str = "#ff5555";
+const DUE_COLOR: &str = "#f1fa8c";
+
+/// Status bar protocols supported by `bar`.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum BarFormat {
+    Waybar,
+    I3blocks,
+    Polybar,
+}
+
+/// Renders the count of tasks due today or overdue for the given status bar.
+pub fn render(tasks: &[Task], format: BarFormat) -> String {
+    let today = Local::now().date_naive();
+    let summary = Summary::from_tasks(tasks, today);
+    let count = summary.overdue + summary.due_today;
+    let class = if summary.overdue > 0 {
+        "overdue"
+    } else if summary.due_today > 0 {
+        "due"
+    } else {
+        "clear"
+    };
+    let color = match class {
+        "overdue" => Some(OVERDUE_COLOR),
+        "due" => Some(DUE_COLOR),
+        _ => None,
+    };
+    let text = format!("✓{}", count);
+
+    match format {
+        BarFormat::Waybar => {
+            let tooltip = tasks
+                .iter()
+                .filter(|t| {
+                    !t.is_done()
+                        && t.due
+                            .as_ref()
+                            .and_then(|d| d.naive_date())
+                            .is_some_and(|d| d <= today)
+                })
+                .map(|t| t.title.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            json!({
+                "text": text,
+                "tooltip": tooltip,
+                "class": class,
+                "alt": class,
+            })
+            .to_string()
+        }
+        // i3blocks reads full text, short text, and color on separate lines
+        BarFormat::I3blocks => format!("{}\n{}\n{}", text, count, color.unwrap_or("")),
+        BarFormat::Polybar => match color {
+            Some(color) => format!("%{{F{}}}{}%{{F-}}", color, text),
+            None => text,
+        },
+    }
+}
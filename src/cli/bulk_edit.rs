@@ -0,0 +1,234 @@
+use crate::controller::app::App;
+use crate::formats::ParsedTask;
+use crate::formats::todotxt;
+use crate::models::task::Task;
+use crate::utils::error::AppResult;
+use crate::utils::filter::Filter;
+use serde_json::{Map, Value, json};
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use std::process::Command;
+
+const HEADER: &str = "\
+# One task per todo.txt line; save and quit to apply the changes.
+# (A)-(C) set the priority, +Project moves the task, @label and due:YYYY-MM-DD set
+# labels and the due date, and an `x ` prefix completes the task.
+# Delete a line to delete its task; lines without an id: tag create new tasks.
+";
+
+/// A task line read back from the editor.
+struct EditedLine {
+    /// Local ID from the `id:` tag; `None` for new tasks.
+    id: Option<usize>,
+    checked: bool,
+    parsed: ParsedTask,
+}
+
+/// Opens the tasks matching `filter` in `$VISUAL` or `$EDITOR` as todo.txt lines and applies
+/// the edits as updates, completions, deletions and new tasks.
+pub async fn run(app: &mut App, filter: Option<&Filter>) -> AppResult<()> {
+    app.sync_projects().await?;
+    let today = chrono::Local::now().date_naive();
+    let tasks = app
+        .tasks()
+        .iter()
+        .filter(|task| filter.is_none_or(|f| f.matches(task, app.projects(), today)))
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut text = HEADER.to_string();
+    for task in &tasks {
+        text.push_str(&format!("{} id:{}\n", todotxt::export_line(task, app.projects()), task.id));
+    }
+
+    let edited = edit_in_editor(&text)?;
+    if edited == text {
+        println!("No changes");
+        return Ok(());
+    }
+    // Parse everything before changing anything, so a typo doesn't leave a half-applied edit
+    let lines = edited
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_edited)
+        .collect::<AppResult<Vec<_>>>()?;
+    let mut seen = HashSet::new();
+    for id in lines.iter().filter_map(|line| line.id) {
+        if !tasks.iter().any(|t| t.id == id) {
+            return Err(anyhow::anyhow!("Unknown task id:{}", id));
+        }
+        if !seen.insert(id) {
+            return Err(anyhow::anyhow!("Task id:{} appears more than once", id));
+        }
+    }
+
+    let deleted = tasks
+        .iter()
+        .filter(|t| !seen.contains(&t.id))
+        .collect::<Vec<_>>();
+    // An editor that saved an empty or cut-off file would otherwise delete every task
+    if !deleted.is_empty() && lines.iter().all(|line| line.id.is_none()) {
+        return Err(anyhow::anyhow!(
+            "Every task line was removed; refusing to delete all {} tasks. Nothing was changed",
+            deleted.len()
+        ));
+    }
+    if !deleted.is_empty() && !confirm_deletions(&deleted)? {
+        println!("Nothing was changed");
+        return Ok(());
+    }
+    let deleted = deleted.iter().map(|t| t.id).collect::<Vec<_>>();
+    let (mut updated, mut completed, mut created) = (0, Vec::new(), 0);
+    for line in lines {
+        let Some(id) = line.id else {
+            if !line.checked
+                && app
+                    .add_to_project(line.parsed.task, line.parsed.project.as_deref())
+                    .await?
+                    .is_some()
+            {
+                created += 1;
+            }
+            continue;
+        };
+        let Some(original) = tasks.iter().find(|t| t.id == id) else {
+            continue;
+        };
+        let fields = changed_fields(original, &line.parsed);
+        let current_project = app
+            .projects()
+            .iter()
+            .find(|p| p.id == original.project_id)
+            .map(|p| p.name.clone());
+        let project_id = match &line.parsed.project {
+            Some(name) if current_project.as_ref().is_none_or(|c| !c.eq_ignore_ascii_case(name)) => {
+                Some(app.resolve_project(name).await?)
+            }
+            _ => None,
+        };
+        if !fields.is_empty() || project_id.is_some() {
+            app.edit_task(id, &Value::Object(fields), project_id.as_deref())
+                .await?;
+            updated += 1;
+        }
+        if line.checked && !original.is_done() {
+            completed.push(original.todoist_id.clone());
+        } else if !line.checked && original.is_done() {
+            eprintln!("Reopening tasks isn't supported; '{}' stays completed", original.title);
+        }
+    }
+    app.close_tasks_by_todoist_id(&completed).await?;
+    app.delete_tasks(&deleted).await?;
+    println!(
+        "Updated {}, completed {}, deleted {} and created {} tasks",
+        updated,
+        completed.len(),
+        deleted.len(),
+        created
+    );
+    Ok(())
+}
+
+/// Parses an edited line: an optional `x ` completion prefix, then a todo.txt task with an optional `id:` tag.
+fn parse_edited(line: &str) -> AppResult<EditedLine> {
+    let (checked, rest) = match line.strip_prefix("x ") {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let mut id = None;
+    let mut words = Vec::new();
+    for word in rest.split_whitespace() {
+        match word.strip_prefix("id:") {
+            Some(value) => {
+                id = Some(
+                    value
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid id tag '{}'", word))?,
+                )
+            }
+            None => words.push(word),
+        }
+    }
+    Ok(EditedLine {
+        id,
+        checked,
+        parsed: todotxt::parse_line(&words.join(" "))?,
+    })
+}
+
+/// Builds the API fields that differ between a task and its edited line.
+fn changed_fields(original: &Task, edited: &ParsedTask) -> Map<String, Value> {
+    let mut fields = Map::new();
+    let task = &edited.task;
+    if task.content != original.title {
+        fields.insert("content".to_string(), json!(task.content));
+    }
+    let priority = task.priority.unwrap_or(1);
+    if priority != original.priority {
+        fields.insert("priority".to_string(), json!(priority));
+    }
+    // Labels are written with spaces as underscores
+    let labels = original
+        .labels
+        .iter()
+        .map(|label| label.replace(' ', "_"))
+        .collect::<Vec<_>>();
+    if task.labels != labels {
+        fields.insert("labels".to_string(), json!(task.labels));
+    }
+    // Untouched dates keep their time and recurrence
+    if task.due_date.as_deref() != original.due.as_ref().map(|due| due.date.as_str()) {
+        match &task.due_date {
+            Some(date) => fields.insert("due_date".to_string(), json!(date)),
+            None => fields.insert("due_string".to_string(), json!("no date")),
+        };
+    }
+    fields
+}
+
+/// Lists the tasks whose lines were removed and asks whether to delete them.
+fn confirm_deletions(deleted: &[&Task]) -> AppResult<bool> {
+    println!("Lines removed for {} tasks, which will be deleted:", deleted.len());
+    for task in deleted {
+        println!("  {}", task.title);
+    }
+    print!("Delete them? [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Writes `text` to a temporary file, opens it in the user's editor and returns the saved contents.
+fn edit_in_editor(text: &str) -> AppResult<String> {
+    // Created with an unpredictable name, readable only by the user, and removed when dropped
+    let mut file = tempfile::Builder::new()
+        .prefix("todoist-cli-edit-")
+        .suffix(".txt")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    // Closed so editors on Windows can write to it
+    let path = file.into_temp_path();
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    // Run through the shell so editors with arguments, like `code --wait`, work
+    let status = if cfg!(windows) {
+        Command::new("cmd")
+            .arg("/C")
+            .arg(format!("{} \"{}\"", editor, path.display()))
+            .status()
+    } else {
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("sh")
+            .arg(&path)
+            .status()
+    };
+    match status {
+        Ok(status) if status.success() => Ok(std::fs::read_to_string(&path)?),
+        Ok(status) => Err(anyhow::anyhow!("Editor '{}' exited with {}", editor, status)),
+        Err(e) => Err(anyhow::anyhow!("Couldn't run editor '{}': {}", editor, e)),
+    }
+}
@@ -0,0 +1,92 @@
+use crate::controller::app::App;
+use crate::models::task::Task;
+use crate::utils::error::AppResult;
+use crate::utils::text::width;
+use crossterm::event::{Event, EventStream, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use crossterm::tty::IsTty;
+use futures::StreamExt;
+use ratatui::backend::CrosstermBackend;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{Frame, Terminal, TerminalOptions, Viewport};
+use std::io::{self, Stdout};
+
+/// Height of the prompt: one line of input between borders.
+const PROMPT_HEIGHT: u16 = 3;
+
+/// Asks for one line in a small prompt below the cursor, adds it with Quick Add and exits;
+/// Esc cancels. Failed adds keep the text so it can be retried.
+pub async fn run(app: &mut App) -> AppResult<()> {
+    if !io::stdin().is_tty() {
+        anyhow::bail!("capture needs a terminal; bind it to e.g. `alacritty -e todoist-cli capture`");
+    }
+    enable_raw_mode()?;
+    let options = TerminalOptions { viewport: Viewport::Inline(PROMPT_HEIGHT) };
+    let mut terminal = Terminal::with_options(CrosstermBackend::new(io::stdout()), options)?;
+    let result = prompt(&mut terminal, app).await;
+    // Leave the shell prompt where the capture prompt was
+    terminal.clear()?;
+    disable_raw_mode()?;
+    if let Some(task) = result? {
+        println!("Added task {}: {}", task.todoist_id, task.title);
+    }
+    Ok(())
+}
+
+/// Reads keys until a task is added or the prompt is cancelled.
+async fn prompt(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+) -> AppResult<Option<Task>> {
+    let mut text = String::new();
+    let mut hint = "Enter: add, Esc: cancel".to_string();
+    let mut events = EventStream::new();
+    loop {
+        terminal.draw(|f| draw(f, &text, &hint))?;
+        let Some(event) = events.next().await else {
+            return Ok(None);
+        };
+        let Event::Key(key) = event? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if ctrl => return Ok(None),
+            KeyCode::Char('u') if ctrl => text.clear(),
+            KeyCode::Char(c) if !ctrl => text.push(c),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Enter if !text.trim().is_empty() => {
+                terminal.draw(|f| draw(f, &text, "Adding…"))?;
+                match app.quick_add(&text).await {
+                    Ok(task) => return Ok(Some(task)),
+                    Err(e) => hint = format!("Failed: {} (Enter: retry, Esc: cancel)", e),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draws the input box, scrolled so the end of long text stays in view.
+fn draw(f: &mut Frame, text: &str, hint: &str) {
+    let area = f.area();
+    let inner = area.width.saturating_sub(2) as usize;
+    // Keep one column free for the cursor
+    let mut shown = text;
+    while width(shown) + 1 > inner
+        && let Some(c) = shown.chars().next()
+    {
+        shown = &shown[c.len_utf8()..];
+    }
+    let block = Block::bordered()
+        .title(" Quick add ")
+        .title_bottom(format!(" {} ", hint));
+    f.render_widget(Paragraph::new(shown).block(block), area);
+    f.set_cursor_position((area.x + 1 + width(shown) as u16, area.y + 1));
+}
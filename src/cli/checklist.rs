@@ -0,0 +1,36 @@
+use crate::cli::template;
+use crate::controller::app::App;
+use crate::utils::error::AppResult;
+use clap::Subcommand;
+
+/// Checklist actions.
+#[derive(Subcommand)]
+pub enum ChecklistAction {
+    /// Creates every task of a checklist defined in the config file
+    Run {
+        /// Checklist name
+        name: String,
+        /// Project name to create the tasks in; created if it doesn't exist
+        #[arg(long)]
+        project: Option<String>,
+    },
+}
+
+/// Runs a checklist action.
+pub async fn run(app: &mut App, action: &ChecklistAction) -> AppResult<()> {
+    match action {
+        ChecklistAction::Run { name, project } => {
+            let tasks = app
+                .settings()
+                .checklists
+                .get(name)
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow::anyhow!("No checklist named '{}' in the config file", name)
+                })?;
+            let created = template::create_tree(app, &tasks, project.as_deref()).await?;
+            println!("Created {} tasks from checklist '{}'", created, name);
+            Ok(())
+        }
+    }
+}
@@ -1,6 +1,33 @@
-use clap::{Parser, Subcommand};
+use chrono::{Duration, Local, Utc};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use crate::utils::error::AppResult;
+use crate::config::settings::ActivitySettings;
 use crate::controller::app::App;
+use crate::controller::groups::{self, GroupBy};
+use crate::controller::rows;
+use crate::cli::archive::{self, ArchiveAction};
+use crate::cli::backup::{self, BackupAction};
+use crate::cli::bar::{self, BarFormat};
+use crate::cli::bulk_edit;
+use crate::cli::checklist::{self, ChecklistAction};
+use crate::cli::git::{self, GitAction};
+use crate::cli::labels::{self, LabelAction};
+use crate::cli::output::{self, OutputMode};
+use crate::cli::projects::{self, ProjectsAction};
+use crate::cli::queue::{self, QueueAction};
+use crate::cli::template::{self, TemplateAction};
+use crate::cli::{capture, deep_link, digest, doctor, due_preview, followups, history, issues, man, md_sync, notify, prefetch, review, self_update, stats, statusline, watch};
+use crate::formats::{self, ParsedTask};
+use crate::models::project;
+use crate::models::task::{NewTask, Task, TaskUpdate};
+use crate::utils::browser;
+use crate::utils::color::ColorChoice;
+use crate::utils::dates::parse_age;
+use crate::utils::filter::Filter;
+use crate::utils::random;
 
 /// CLI arguments for the Todoist CLI.
 #[derive(Parser)]
@@ -9,6 +36,35 @@ use crate::controller::app::App;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+    /// Use cached tasks only and never sync
+    #[arg(long, global = true)]
+    pub offline: bool,
+    /// Skip the startup sync; the TUI syncs in the background instead
+    #[arg(long, global = true)]
+    pub no_sync: bool,
+    /// Color CLI output; `auto` colors terminals unless NO_COLOR is set, and a bare `--color` means always
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto,
+        num_args = 0..=1,
+        default_missing_value = "always"
+    )]
+    pub color: ColorChoice,
+    /// Render the TUI for screen readers: no box drawing, high-contrast selection, and a status line announcing changes
+    #[arg(long, global = true)]
+    pub plain_ui: bool,
+    /// Draw the TUI in the normal terminal buffer instead of the alternate screen
+    #[arg(long, global = true)]
+    pub no_alt_screen: bool,
+    /// Append API requests and responses, with the token redacted, to FILE for bug reports
+    #[arg(long, global = true, value_name = "FILE")]
+    pub debug_http: Option<PathBuf>,
+    /// Try the app on generated sample projects and tasks, kept in memory; no Todoist account needed
+    #[arg(long, global = true)]
+    pub demo: bool,
 }
 
 /// Available CLI commands.
@@ -17,30 +73,936 @@ pub enum Commands {
     /// Adds a new task
     Add {
         /// Task title
-        title: String,
+        #[arg(required_unless_present_any = ["from_url", "from_clipboard"])]
+        title: Option<String>,
+        /// Create the task from a GitHub or GitLab issue URL
+        #[arg(long, conflicts_with_all = ["title", "from_clipboard"])]
+        from_url: Option<String>,
+        /// Use the clipboard: the first line is the title, the rest the description
+        #[arg(long, conflicts_with = "title")]
+        from_clipboard: bool,
+        /// Close the task when the linked issue is closed (see `sync-issues`)
+        #[arg(long, requires = "from_url")]
+        track: bool,
+        /// Due date in natural language, e.g. "tomorrow" or "next fri 3pm"
+        #[arg(long)]
+        due: Option<String>,
+        /// Priority, from 1 (p1, urgent) to 4 (normal)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=4))]
+        priority: Option<u8>,
+        /// Project to add the task to, by name or ID; a name is created if it doesn't exist
+        #[arg(long)]
+        project: Option<String>,
+        /// Add the task even if a similar open task already exists
+        #[arg(long)]
+        force: bool,
     },
-    /// Updates an existing task
+    /// Adds a task from a one-line prompt
+    ///
+    /// Opens a one-line prompt, adds what's typed with Todoist's Quick Add ("Call Bo tomorrow
+    /// #Work p1") and exits; meant for a global hotkey.
+    Capture,
+    /// Adds a task from a todoist-cli:// link
+    ///
+    /// Adds a task from a deep link, e.g. "todoist-cli://add?content=Buy%20milk&due=tomorrow",
+    /// for apps and browser extensions opening the registered URL scheme.
+    HandleUrl {
+        /// The URL; read from stdin when omitted or `-`
+        url: Option<String>,
+    },
+    /// Updates an existing task; only the given fields change
+    #[command(group(ArgGroup::new("changes").required(true).multiple(true)))]
     Update {
-        /// Task ID
-        id: usize,
+        /// Todoist task ID, or enough of its start to be unambiguous
+        id: String,
         /// New task title
-        title: String,
-        /// Task completion status
+        #[arg(long, group = "changes")]
+        title: Option<String>,
+        /// Mark the task completed
+        #[arg(long, group = "changes", conflicts_with = "unchecked")]
         checked: bool,
+        /// Mark the task not completed
+        #[arg(long, group = "changes")]
+        unchecked: bool,
+        /// New due date in natural language, e.g. "tomorrow" or "no date"
+        #[arg(long, group = "changes")]
+        due: Option<String>,
+        /// New priority, from 1 (p1, urgent) to 4 (normal)
+        #[arg(long, group = "changes", value_parser = clap::value_parser!(u8).range(1..=4))]
+        priority: Option<u8>,
+        /// New description
+        #[arg(long, group = "changes")]
+        description: Option<String>,
+    },
+    /// Marks a task completed
+    Complete {
+        /// Todoist task ID, or enough of its start to be unambiguous
+        id: String,
+    },
+    /// Reopens a completed task, including ones archived by `cleanup`
+    Reopen {
+        /// Todoist task ID, or enough of its start to be unambiguous
+        id: String,
+    },
+    /// Marks a task as blocked by another
+    ///
+    /// Records that a task can't start until another is done; labels it `blocked` and comments
+    /// in Todoist, dims it in the TUI, and surfaces it once the blocker is completed.
+    Block {
+        /// The blocked task: its Todoist ID, or enough of its start to be unambiguous
+        id: String,
+        /// The task it waits on: its Todoist ID, or enough of its start to be unambiguous
+        #[arg(long)]
+        by: String,
+    },
+    /// Removes blocked-by links recorded with `block`
+    Unblock {
+        /// The blocked task: its Todoist ID, or enough of its start to be unambiguous
+        id: String,
+        /// Only remove the link to this blocker; all of the task's links by default
+        #[arg(long)]
+        by: Option<String>,
+    },
+    /// Lists waiting tasks due for a follow-up
+    ///
+    /// Lists tasks waiting on someone else (labeled `waiting`, or e.g. `waiting-7d` for a 7-day
+    /// interval) whose follow-up day has come; the TUI marks them with `!`.
+    Followups {
+        /// List every waiting task with its follow-up day
+        #[arg(long)]
+        all: bool,
+        /// Record that you followed up on this task, restarting its interval: its Todoist ID, or
+        /// enough of its start to be unambiguous
+        #[arg(long, value_name = "ID")]
+        nudged: Option<String>,
+    },
+    /// Edits many tasks at once in $VISUAL or $EDITOR, one todo.txt line per task
+    Edit {
+        /// Edit all matching tasks in one file
+        #[arg(long, required = true)]
+        bulk: bool,
+        /// Only edit tasks matching this filter query or saved filter
+        #[arg(long)]
+        filter: Option<String>,
     },
-    /// Deletes a task
+    /// Opens a task in the Todoist web app, e.g. for attachments
+    Open {
+        /// Todoist task ID, or enough of its start to be unambiguous
+        id: String,
+    },
+    /// Prints the web URL of a task, for pasting into chat or commit messages
+    Url {
+        /// Todoist task ID, or enough of its start to be unambiguous
+        id: String,
+        /// Print the full Todoist ID instead of the URL
+        #[arg(long)]
+        id_only: bool,
+    },
+    /// Deletes one or more tasks
     Delete {
-        /// Task ID
-        id: usize,
+        /// Todoist task IDs, or enough of their starts to be unambiguous
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Exports tasks to stdout or a file
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// File to write instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Imports tasks from a file into Todoist
+    Import {
+        /// File to import, or `-` for stdin
+        path: PathBuf,
+        /// Input format, inferred from the file extension if omitted
+        #[arg(long = "from", alias = "format", value_enum)]
+        from: Option<ImportFormat>,
+    },
+    /// Prints a markdown report of recent progress
+    Report {
+        /// Cover the last 7 days
+        #[arg(long, conflicts_with = "days")]
+        week: bool,
+        /// Number of days to cover
+        #[arg(long, default_value_t = 7)]
+        days: i64,
+    },
+    /// Sends desktop notifications for due and overdue tasks
+    Notify {
+        /// Notify about tasks due within this many minutes
+        #[arg(long, default_value_t = 15)]
+        within: i64,
+        /// Keep running and check again every interval
+        #[arg(long)]
+        daemonize: bool,
+        /// Seconds between checks when daemonized
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+    },
+    /// Prints a one-line summary for tmux or shell prompts
+    Statusline {
+        /// Maximum width of the output in terminal columns
+        #[arg(long, default_value_t = 40)]
+        max_len: usize,
+    },
+    /// Prints task counts for waybar, i3blocks, or polybar
+    Bar {
+        /// Status bar protocol
+        #[arg(long, value_enum, default_value_t = BarFormat::Waybar)]
+        format: BarFormat,
+    },
+    /// Closes tasks whose tracked issues have been closed
+    SyncIssues,
+    /// Syncs `- [ ] task (todoist:ID)` checkboxes in a markdown vault with Todoist
+    MdSync {
+        /// Directory to scan for markdown files
+        dir: PathBuf,
+    },
+    /// Prints or emails today's agenda
+    Digest {
+        /// Send the digest through the SMTP server in the config file
+        #[arg(long)]
+        email: bool,
+        /// Print HTML instead of plain text
+        #[arg(long, conflicts_with = "email")]
+        html: bool,
+    },
+    /// Generates roff man pages
+    Man {
+        /// Directory to write one page per subcommand into; prints the main page if omitted
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+    /// Shows the next dates a recurring due string like "every 2nd wed" falls on
+    DuePreview {
+        due: String,
+        /// Number of dates to show
+        #[arg(long, default_value_t = 5)]
+        count: usize,
+    },
+    /// Diagnoses token, cache, config, terminal, and network problems
+    Doctor,
+    /// Snapshots the local cache, or lists and restores snapshots
+    Backup {
+        #[command(subcommand)]
+        action: BackupAction,
+    },
+    /// Shows tasks of removed projects
+    ///
+    /// Lists and shows the tasks of projects archived or deleted in Todoist, saved before a
+    /// sync dropped them from the cache.
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
+    /// Lists, resends, drops or resolves changes queued while Todoist couldn't be reached
+    Queue {
+        #[command(subcommand)]
+        action: QueueAction,
+    },
+    /// Replaces this binary with the latest GitHub release after verifying its checksum
+    SelfUpdate {
+        /// Only report whether a newer release is available
+        #[arg(long)]
+        check: bool,
+    },
+    /// Refreshes the cache quietly, for cron
+    ///
+    /// Refreshes tasks, projects, labels, the daily goal and recent completed tasks in the cache,
+    /// so startup with `sync_on_start = false` is instant and up to date.
+    Prefetch {
+        /// Days of completed tasks to fetch into the history
+        #[arg(long, default_value_t = 56)]
+        days: i64,
+    },
+    /// Shows changes made to tasks from this app, with the values they replaced
+    History {
+        /// Only changes to this task: its Todoist ID, or enough of its start to be unambiguous
+        #[arg(long)]
+        task: Option<String>,
+        /// Show at most this many of the newest changes
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Shows tasks added, completed, rescheduled or edited elsewhere since you last looked
+    Changes {
+        /// Also show changes already seen
+        #[arg(long)]
+        all: bool,
+        /// Show at most this many of the newest changes
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
     },
+    /// Prints what collaborators do in shared projects, e.g. "Ana completed Ship it", as it happens
+    Watch {
+        /// Only this shared project; repeat for more. Defaults to the `[activity]` projects, or all
+        #[arg(long = "project")]
+        projects: Vec<String>,
+        /// Seconds between checks of the activity log; defaults to the `[activity]` interval
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+    /// Shows task counts
+    Stats {
+        /// Show request counts, errors, and latency per API endpoint of the last session instead
+        #[arg(long)]
+        api: bool,
+        /// Print tasks completed per week and open at each week's end, per project, as CSV instead
+        #[arg(long, conflicts_with = "api")]
+        csv: bool,
+    },
+    /// Closes tasks referenced in git commit messages
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
+    /// Moves old completed tasks from the live cache into the local history table
+    Cleanup {
+        /// Minimum age since completion, e.g. 30d, 2w or 12h
+        #[arg(long, default_value = "30d", value_parser = parse_age_arg)]
+        completed_older_than: Duration,
+    },
+    /// Lists tasks, optionally narrowed by a filter query or saved filter view
+    List {
+        /// Todoist filter query, e.g. "(today | overdue) & #Work", or the name of a saved filter
+        #[arg(long)]
+        filter: Option<String>,
+        /// Only tasks in this project or its sub-projects, by name or ID
+        #[arg(long)]
+        project: Option<String>,
+        /// Only completed tasks, including those archived by `cleanup`
+        #[arg(long)]
+        completed: bool,
+        /// Only tasks created longer ago than this, e.g. 30d or 2w
+        #[arg(long, value_parser = parse_age_arg)]
+        older_than: Option<Duration>,
+        /// Order of the listed tasks
+        #[arg(long, value_enum)]
+        sort: Option<ListSort>,
+        /// Reverse the sort order
+        #[arg(long, requires = "sort")]
+        reverse: bool,
+        /// Print at most this many tasks
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Output layout
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputMode,
+        /// Print the tasks in sections with headers and counts
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+        /// Show subtasks indented under their parents
+        #[arg(long, conflicts_with_all = ["output", "group_by"])]
+        tree: bool,
+    },
+    /// Picks a random open task, optionally among those matching a filter, to get you started
+    Roulette {
+        /// Todoist filter query, e.g. "today", or the name of a saved filter
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Searches task titles, optionally including the completed-task history
+    Search {
+        /// Text to look for, ignoring case
+        query: String,
+        /// Also search completed tasks archived by `cleanup`, showing when they were finished
+        #[arg(long)]
+        include_completed: bool,
+        /// Order of the open tasks found
+        #[arg(long, value_enum)]
+        sort: Option<ListSort>,
+        /// Reverse the sort order
+        #[arg(long, requires = "sort")]
+        reverse: bool,
+        /// Print at most this many open tasks
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Output layout of the open tasks found
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputMode,
+    },
+    /// Saves and applies reusable task templates
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+    /// Creates named checklists of tasks defined in the config file
+    Checklist {
+        #[command(subcommand)]
+        action: ChecklistAction,
+    },
+    /// Goes through tasks untouched for a while, one at a time, to keep, reschedule, delete or deprioritize
+    Review {
+        /// Days without changes that make a task stale; defaults to `stale_days` from the config
+        #[arg(long)]
+        days: Option<i64>,
+        /// Go through a GTD-style weekly review instead: empty the Inbox, check overdue tasks and
+        /// ones labeled `waiting_label`, then preview next week; resumes where it was left off
+        #[arg(long, conflicts_with = "days")]
+        weekly: bool,
+        /// Start the weekly review over instead of resuming it
+        #[arg(long, requires = "weekly")]
+        restart: bool,
+    },
+    /// Lists, archives and unarchives projects
+    Projects {
+        #[command(subcommand)]
+        action: ProjectsAction,
+    },
+    /// Adds or removes a label across all tasks matching a filter
+    Label {
+        #[command(subcommand)]
+        action: LabelAction,
+    },
+}
+
+impl Commands {
+    /// Returns true for commands that don't need fresh tasks and should skip the startup sync.
+    pub fn skips_sync(&self) -> bool {
+        matches!(
+            self,
+            Commands::Statusline { .. }
+                | Commands::Bar { .. }
+                // Syncing would replace the previous session's API stats with its own
+                | Commands::Stats { .. }
+                // The audit log is local
+                | Commands::History { .. }
+                // Activity comes from the activity log, not the task list
+                | Commands::Watch { .. }
+                // A sync would drop the locally completed tasks it archives
+                | Commands::Cleanup { .. }
+                // Git actions talk to the API directly, without the cache
+                | Commands::Git { .. }
+                // Capture should open instantly; the task comes back with Todoist's parsing
+                | Commands::Capture
+                // Prefetch syncs itself, even with `sync_on_start = false`
+                | Commands::Prefetch { .. }
+                // A sync would send the queue before it can be looked at
+                | Commands::Queue { .. }
+                // md-sync fetches every task itself before reconciling
+                | Commands::MdSync { .. }
+        )
+    }
+}
+
+fn parse_age_arg(value: &str) -> Result<Duration, String> {
+    parse_age(value).ok_or_else(|| format!("invalid age '{}', expected e.g. 30d, 2w or 12h", value))
+}
+
+/// Orders supported by `list --sort`.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ListSort {
+    /// Soonest due first
+    Due,
+    /// p1 first
+    Priority,
+    /// Oldest first, by creation time
+    #[value(alias = "age")]
+    Added,
+    /// By title, ignoring case
+    Alpha,
+}
+
+impl ListSort {
+    /// Orders tasks, or the reverse with `reverse`; tasks without a due date or creation time go
+    /// last either way.
+    fn sort(self, tasks: &mut [&Task], reverse: bool) {
+        let order = |ordering: Ordering| if reverse { ordering.reverse() } else { ordering };
+        match self {
+            ListSort::Due => tasks.sort_by(|a, b| {
+                let key = |t: &Task| t.due.as_ref().and_then(|d| d.sort_key());
+                compare_present(key(a), key(b), order)
+            }),
+            ListSort::Priority => tasks.sort_by(|a, b| order(b.priority.cmp(&a.priority))),
+            ListSort::Added => tasks.sort_by(|a, b| compare_present(a.added(), b.added(), order)),
+            ListSort::Alpha => {
+                tasks.sort_by(|a, b| order(a.title.to_lowercase().cmp(&b.title.to_lowercase())))
+            }
+        }
+    }
+}
+
+/// Which tasks `list` prints.
+#[derive(Clone, Copy)]
+struct TaskSelection<'a> {
+    /// Filter query or saved filter name.
+    filter: Option<&'a str>,
+    /// Project name or ID; sub-projects count too.
+    project: Option<&'a str>,
+    /// Only completed tasks, including the archived history.
+    completed: bool,
+    /// Minimum age since creation.
+    older_than: Option<Duration>,
+}
+
+/// Sort order and limit of listed tasks.
+#[derive(Clone, Copy)]
+struct TaskOrder {
+    sort: Option<ListSort>,
+    reverse: bool,
+    limit: Option<usize>,
+}
+
+impl TaskOrder {
+    /// Sorts the tasks and keeps at most `limit` of them.
+    fn apply(self, tasks: &mut Vec<&Task>) {
+        if let Some(sort) = self.sort {
+            sort.sort(tasks, self.reverse);
+        }
+        if let Some(limit) = self.limit {
+            tasks.truncate(limit);
+        }
+    }
+}
+
+/// Compares optional keys with `order`, always putting missing keys last.
+fn compare_present<T: Ord>(a: Option<T>, b: Option<T>, order: impl Fn(Ordering) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => order(a.cmp(&b)),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    }
+}
+
+/// How `list` lays out the tasks.
+#[derive(Clone, Copy)]
+enum ListLayout {
+    /// One row per task in the given mode, optionally grouped.
+    Flat(OutputMode, Option<GroupBy>),
+    /// Subtasks indented under their parents.
+    Tree,
+}
+
+/// Formats supported by `export`.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    /// Todoist's project import template, for re-importing into Todoist
+    Csv,
+    Org,
+    Todotxt,
+}
+
+/// Formats supported by `import`.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportFormat {
+    Org,
+    Todotxt,
+    /// JSON produced by `task export`
+    Taskwarrior,
 }
 
-// todo: Add Close Task command?
-/// Processes CLI commands and updates the app state.
-pub async fn process_command(app: &mut App, command: &Commands) -> AppResult<()> {
+impl ImportFormat {
+    /// Guesses the import format from a file extension.
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "org" => Some(ImportFormat::Org),
+            "txt" => Some(ImportFormat::Todotxt),
+            "json" => Some(ImportFormat::Taskwarrior),
+            _ => None,
+        }
+    }
+}
+
+/// Processes CLI commands and updates the app state, printing ANSI colors when `color` is set.
+pub async fn process_command(app: &mut App, command: &Commands, color: bool) -> AppResult<()> {
     match command {
-        Commands::Add { title } => app.add_task(title).await,
-        Commands::Update { id, title, checked } => app.update_task(*id, title, *checked).await,
-        Commands::Delete { id } => app.delete_task(*id).await,
+        Commands::Add { title, from_url, from_clipboard, track, due, priority, project, force } => match (title, from_url) {
+            (_, Some(url)) => {
+                issues::add_from_url(app, url, *track, due.as_deref(), *priority, project.as_deref()).await
+            }
+            _ if *from_clipboard => {
+                add_from_clipboard(app, due.as_deref(), *priority, project.as_deref(), *force).await
+            }
+            (Some(title), None) => {
+                if !force && warn_duplicate(app, title) {
+                    return Ok(());
+                }
+                let mut new_task = NewTask::new(title);
+                if let Some(due) = due {
+                    new_task.set_due(due);
+                }
+                new_task.priority = priority.map(|p| 5 - p);
+                app.add_to_project(new_task, project.as_deref()).await?;
+                Ok(())
+            }
+            (None, None) => Ok(()),
+        },
+        Commands::Capture => capture::run(app).await,
+        Commands::HandleUrl { url } => deep_link::run(app, url.as_deref()).await,
+        Commands::Update { id, title, checked, unchecked, due, priority, description } => {
+            let mut update = TaskUpdate {
+                content: title.clone(),
+                checked: (*checked || *unchecked).then_some(*checked),
+                description: description.clone(),
+                priority: priority.map(|p| 5 - p),
+                ..Default::default()
+            };
+            if let Some(due) = due {
+                update.set_due(due);
+            }
+            let id = app.find_task(id)?.id;
+            app.update_task(id, update).await
+        }
+        Commands::Complete { id } => {
+            let id = app.find_task(id)?.id;
+            app.set_task_done(id, true).await
+        }
+        Commands::Reopen { id } => app.reopen_task(id).await,
+        Commands::Block { id, by } => {
+            let (id, blocker) = (app.find_task(id)?.id, app.find_task(by)?.id);
+            app.block_task(id, blocker).await
+        }
+        Commands::Unblock { id, by } => {
+            let id = app.find_task(id)?.id;
+            let blocker = by.as_deref().map(|by| app.find_task(by).map(|t| t.id)).transpose()?;
+            app.unblock_task(id, blocker).await
+        }
+        Commands::Followups { all, nudged } => followups::run(app, *all, nudged.as_deref()),
+        Commands::Edit { filter, .. } => {
+            let filter = parse_filter(app, filter.as_deref()).await?;
+            bulk_edit::run(app, filter.as_ref()).await
+        }
+        Commands::Open { id } => browser::open(&app.find_task(id)?.web_url()),
+        Commands::Url { id, id_only } => {
+            let task = app.find_task(id)?;
+            println!("{}", if *id_only { task.todoist_id.clone() } else { task.web_url() });
+            Ok(())
+        }
+        Commands::Delete { ids } => {
+            let ids = ids
+                .iter()
+                .map(|id| app.find_task(id).map(|t| t.id))
+                .collect::<AppResult<Vec<_>>>()?;
+            app.delete_tasks(&ids).await
+        }
+        Commands::Export { format, output } => export_tasks(app, *format, output.as_deref()).await,
+        Commands::Import { path, from } => import_tasks(app, path, *from).await,
+        Commands::Report { week, days } => print_report(app, if *week { 7 } else { *days }).await,
+        Commands::Notify { within, daemonize, interval } => notify::run(app, *within, *daemonize, *interval).await,
+        Commands::Statusline { max_len } => {
+            println!("{}", statusline::render(app.tasks(), *max_len, color));
+            Ok(())
+        }
+        Commands::Bar { format } => {
+            println!("{}", bar::render(app.tasks(), *format));
+            Ok(())
+        }
+        Commands::SyncIssues => issues::sync_closed(app).await,
+        Commands::MdSync { dir } => md_sync::run(app, dir).await,
+        Commands::Digest { email, html } => digest::run(app, *email, *html).await,
+        Commands::Git { action } => git::run(action).await,
+        Commands::Cleanup { completed_older_than } => {
+            let archived = app.archive_completed(Utc::now() - *completed_older_than)?;
+            println!("Archived {} completed tasks", archived);
+            Ok(())
+        }
+        Commands::List { filter, project, completed, older_than, sort, reverse, limit, output, group_by, tree } => {
+            let selection = TaskSelection {
+                filter: filter.as_deref(),
+                project: project.as_deref(),
+                completed: *completed,
+                older_than: *older_than,
+            };
+            let order = TaskOrder { sort: *sort, reverse: *reverse, limit: *limit };
+            let layout = if *tree { ListLayout::Tree } else { ListLayout::Flat(*output, *group_by) };
+            list_tasks(app, selection, order, color, layout).await
+        }
+        Commands::Roulette { filter } => roulette(app, filter.as_deref(), color).await,
+        Commands::Search { query, include_completed, sort, reverse, limit, output } => {
+            let order = TaskOrder { sort: *sort, reverse: *reverse, limit: *limit };
+            search_tasks(app, query, *include_completed, order, color, *output)
+        }
+        Commands::Template { action } => template::run(app, action).await,
+        Commands::Checklist { action } => checklist::run(app, action).await,
+        Commands::Projects { action } => projects::run(app, action, color).await,
+        Commands::Label { action } => match parse_filter(app, Some(action.filter())).await? {
+            Some(filter) => labels::run(app, action, &filter).await,
+            None => Ok(()),
+        },
+        Commands::Review { weekly: true, restart, .. } => review::run_weekly(app, *restart).await,
+        Commands::Review { days, .. } => review::run(app, *days).await,
+        Commands::Man { out_dir } => man::generate(out_dir.as_deref()),
+        Commands::DuePreview { due, count } => due_preview::run(due, *count),
+        Commands::Doctor => doctor::run().await,
+        Commands::Backup { action } => backup::run(action),
+        Commands::Archive { action } => archive::run(action, color),
+        Commands::Queue { action } => queue::run(app, action).await,
+        Commands::SelfUpdate { check } => self_update::run(*check).await,
+        Commands::Stats { api, csv } => stats::run(app, *api, *csv).await,
+        Commands::Prefetch { days } => prefetch::run(app, *days).await,
+        Commands::History { task, limit } => history::run(app, task.as_deref(), *limit),
+        Commands::Changes { all, limit } => history::changes(app, *all, *limit),
+        Commands::Watch { projects, interval } => {
+            let settings = app.settings().activity.as_ref();
+            let projects = match settings {
+                Some(activity) if projects.is_empty() => activity.projects.clone(),
+                _ => projects.clone(),
+            };
+            let interval = interval.or(settings.map(|a| a.interval)).unwrap_or(ActivitySettings::default().interval);
+            watch::run(app, &projects, interval).await
+        }
+    }
+}
+
+/// Creates a task from the clipboard text.
+async fn add_from_clipboard(
+    app: &mut App,
+    due: Option<&str>,
+    priority: Option<u8>,
+    project: Option<&str>,
+    force: bool,
+) -> AppResult<()> {
+    let text = arboard::Clipboard::new()?.get_text()?;
+    let text = text.trim();
+    let (title, description) = match text.split_once('\n') {
+        Some((title, rest)) => (title.trim(), Some(rest.trim().to_string())),
+        None => (text, None),
+    };
+    if !force && warn_duplicate(app, title) {
+        return Ok(());
+    }
+    let mut new_task = NewTask {
+        description: description.filter(|d| !d.is_empty()),
+        priority: priority.map(|p| 5 - p),
+        ..NewTask::new(title)
+    };
+    if let Some(due) = due {
+        new_task.set_due(due);
+    }
+    match app.add_to_project(new_task, project).await? {
+        Some(task) => println!("Added task {}: {}", task.todoist_id, task.title),
+        None => println!("Clipboard is empty; no task added"),
+    }
+    Ok(())
+}
+
+/// Warns and returns true when an open task closely matches `title`.
+fn warn_duplicate(app: &App, title: &str) -> bool {
+    let Some(existing) = app.find_duplicate(title) else {
+        return false;
+    };
+    eprintln!(
+        "Not added: task {} \"{}\" looks like the same task. Pass --force to add it anyway.",
+        existing.short_id(app.short_id_len()),
+        existing.title
+    );
+    true
+}
+
+/// Writes the cached tasks in the requested format.
+async fn export_tasks(app: &mut App, format: ExportFormat, output: Option<&Path>) -> AppResult<()> {
+    let rendered = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(app.tasks())?,
+        ExportFormat::Csv => formats::csv::export(app.tasks()),
+        ExportFormat::Org => formats::org::export(app.tasks()),
+        ExportFormat::Todotxt => {
+            app.sync_projects().await?;
+            formats::todotxt::export(app.tasks(), app.projects())
+        }
+    };
+    match output {
+        Some(path) => std::fs::write(path, rendered)?,
+        None if rendered.ends_with('\n') => print!("{}", rendered),
+        None => println!("{}", rendered),
     }
-}
\ No newline at end of file
+    Ok(())
+}
+
+/// Parses a file and creates the tasks it contains in Todoist, printing a mapping report.
+async fn import_tasks(app: &mut App, path: &Path, from: Option<ImportFormat>) -> AppResult<()> {
+    let format = from
+        .or_else(|| ImportFormat::from_path(path))
+        .ok_or_else(|| anyhow::anyhow!("Cannot infer import format for {}; pass --from", path.display()))?;
+    let contents = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    let parsed: Vec<ParsedTask> = match format {
+        ImportFormat::Org => formats::org::parse(&contents).into_iter().map(ParsedTask::from).collect(),
+        ImportFormat::Todotxt => formats::todotxt::parse(&contents)?,
+        ImportFormat::Taskwarrior => formats::taskwarrior::parse(&contents)?,
+    };
+    if parsed.iter().any(|p| p.project.is_some()) {
+        app.sync_projects().await?;
+    }
+
+    let mut new_tasks = Vec::with_capacity(parsed.len());
+    let mut sources = Vec::with_capacity(parsed.len());
+    for ParsedTask { mut task, project, source } in parsed {
+        if let Some(name) = project {
+            task.project_id = Some(app.resolve_project(&name).await?);
+        }
+        sources.push(source.unwrap_or_else(|| task.content.clone()));
+        new_tasks.push(task);
+    }
+
+    let results = app.create_tasks_batch(&new_tasks).await?;
+    let mut failed = 0;
+    for (source, result) in sources.iter().zip(&results) {
+        match result {
+            Ok(todoist_id) => println!("{} -> {}", source, todoist_id),
+            Err(error) => {
+                failed += 1;
+                println!("{} -> FAILED ({})", source, error);
+            }
+        }
+    }
+    println!(
+        "Imported {} of {} tasks from {}",
+        results.len() - failed,
+        results.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Parses a filter query or saved filter name, fetching projects when it refers to any.
+async fn parse_filter(app: &mut App, query: Option<&str>) -> AppResult<Option<Filter>> {
+    let Some(query) = query else {
+        return Ok(None);
+    };
+    let query = app.settings().resolve_filter(query).to_string();
+    if query.contains('#') && app.sync_projects().await.is_err() {
+        eprintln!("Couldn't fetch projects; #Project terms won't match anything");
+    }
+    Ok(Some(Filter::parse(&query, Local::now().date_naive())?))
+}
+
+/// Prints the selected tasks, evaluated locally so it also works offline. Tasks of unknown age
+/// never count as older than anything.
+async fn list_tasks(
+    app: &mut App,
+    selection: TaskSelection<'_>,
+    order: TaskOrder,
+    color: bool,
+    layout: ListLayout,
+) -> AppResult<()> {
+    let today = Local::now().date_naive();
+    let filter = parse_filter(app, selection.filter).await?;
+    load_project_names(app).await;
+    let project_id = match selection.project {
+        Some(name) => match app.find_project(name).await? {
+            Some(id) => Some(id),
+            None => anyhow::bail!("No project named \"{}\"", name),
+        },
+        None => None,
+    };
+    // Completed tasks archived by `cleanup` are no longer in the task list
+    let archived = if selection.completed {
+        let listed = app.tasks().iter().map(|t| t.todoist_id.as_str()).collect::<HashSet<_>>();
+        let history = app.cache().load_history()?;
+        history.into_iter().filter(|t| !listed.contains(t.todoist_id.as_str())).collect()
+    } else {
+        Vec::new()
+    };
+    let relative_dates = app.settings().relative_dates;
+    let cutoff = selection.older_than.map(|age| Utc::now() - age);
+    let mut tasks = app
+        .tasks()
+        .iter()
+        .chain(&archived)
+        .filter(|task| !selection.completed || task.is_done())
+        .filter(|task| {
+            project_id.as_ref().is_none_or(|id| project::is_within(app.projects(), &task.project_id, id))
+        })
+        .filter(|task| filter.as_ref().is_none_or(|f| f.matches(task, app.projects(), today)))
+        .filter(|task| cutoff.is_none_or(|cutoff| task.added().is_some_and(|added| added < cutoff)))
+        .collect::<Vec<_>>();
+    order.apply(&mut tasks);
+    let id_len = app.short_id_len();
+    match layout {
+        ListLayout::Flat(output, Some(by)) => {
+            let groups = groups::group(&tasks, by, app.projects(), today);
+            output::print_groups(&groups, app.projects(), today, relative_dates, color, output, id_len);
+        }
+        ListLayout::Flat(output, None) => {
+            output::print_tasks(&tasks, app.projects(), today, relative_dates, color, output, id_len)
+        }
+        ListLayout::Tree => output::print_tree(&tasks, app.projects(), today, relative_dates, color, id_len),
+    }
+    Ok(())
+}
+
+/// Fetches projects for showing their names in task rows; offline the rows just go without them.
+async fn load_project_names(app: &mut App) {
+    if app.projects().is_empty() {
+        let _ = app.sync_projects().await;
+    }
+}
+
+/// Prints a random open task matching an optional filter query.
+async fn roulette(app: &mut App, filter: Option<&str>, color: bool) -> AppResult<()> {
+    let today = Local::now().date_naive();
+    let filter = parse_filter(app, filter).await?;
+    load_project_names(app).await;
+    let tasks = app
+        .tasks()
+        .iter()
+        .filter(|task| !task.is_done())
+        .filter(|task| filter.as_ref().is_none_or(|f| f.matches(task, app.projects(), today)))
+        .collect::<Vec<_>>();
+    match random::index(tasks.len()).map(|i| tasks[i]) {
+        Some(task) => {
+            let row = rows::format_row(task, app.projects(), today, app.settings().relative_dates, color);
+            println!("{} {}", task.short_id(app.short_id_len()), row)
+        }
+        None => println!("No open tasks to pick from"),
+    }
+    Ok(())
+}
+
+/// Prints tasks whose titles contain `query`, then matching tasks from the completed history
+/// that aren't among them, e.g. completed but not yet archived.
+fn search_tasks(
+    app: &App,
+    query: &str,
+    include_completed: bool,
+    order: TaskOrder,
+    color: bool,
+    output: OutputMode,
+) -> AppResult<()> {
+    let today = Local::now().date_naive();
+    let relative_dates = app.settings().relative_dates;
+    let query = query.to_lowercase();
+    let mut found = app
+        .tasks()
+        .iter()
+        .filter(|task| task.title.to_lowercase().contains(&query))
+        .collect::<Vec<_>>();
+    order.apply(&mut found);
+    let id_len = app.short_id_len();
+    output::print_tasks(&found, app.projects(), today, relative_dates, color, output, id_len);
+    if include_completed {
+        for task in app.cache().load_history()? {
+            if task.title.to_lowercase().contains(&query) && !found.iter().any(|t| t.todoist_id == task.todoist_id) {
+                match task.completed_on() {
+                    Some(date) => println!("- [x] {} (completed {})", task.title, date),
+                    None => println!("- [x] {}", task.title),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints a markdown report covering the last `days` days.
+async fn print_report(app: &mut App, days: i64) -> AppResult<()> {
+    let now = Local::now();
+    let since = now - Duration::days(days);
+    let completed = app.completed_tasks(since.to_utc(), now.to_utc()).await?;
+    app.sync_projects().await?;
+    let report = formats::markdown::report(
+        &completed,
+        app.tasks(),
+        app.projects(),
+        since.date_naive(),
+        now.date_naive(),
+        app.settings().relative_dates,
+    );
+    print!("{}", report);
+    Ok(())
+}
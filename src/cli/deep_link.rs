@@ -0,0 +1,79 @@
+use crate::controller::app::App;
+use crate::models::task::NewTask;
+use crate::utils::error::AppResult;
+use reqwest::Url;
+use std::io::{self, Read};
+
+/// URL schemes accepted by `handle-url`.
+const SCHEMES: [&str; 2] = ["todoist-cli", "todoist"];
+
+/// Handles a deep link such as `todoist-cli://add?content=Buy%20milk&due=tomorrow`, reading the
+/// URL from stdin when none is given or it is `-`.
+pub async fn run(app: &mut App, url: Option<&str>) -> AppResult<()> {
+    let url = match url {
+        Some(url) if url != "-" => url.to_string(),
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+    let url = Url::parse(url.trim())
+        .map_err(|e| anyhow::anyhow!("Invalid URL '{}': {}", url.trim(), e))?;
+    if !SCHEMES.contains(&url.scheme()) {
+        return Err(anyhow::anyhow!(
+            "Unsupported URL scheme '{}', expected todoist-cli://",
+            url.scheme()
+        ));
+    }
+    match url.host_str() {
+        Some("add") => add(app, &url).await,
+        action => Err(anyhow::anyhow!(
+            "Unsupported action '{}', expected todoist-cli://add",
+            action.unwrap_or_default()
+        )),
+    }
+}
+
+/// Adds the task described by the query parameters: `content` (required), `description`, `due`,
+/// `project`, `priority` (1 for p1 to 4) and comma-separated `labels`.
+async fn add(app: &mut App, url: &Url) -> AppResult<()> {
+    let mut new_task = NewTask::default();
+    let mut project = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "content" => new_task.content = value.trim().to_string(),
+            "description" => new_task.description = Some(value.into_owned()),
+            "due" => new_task.set_due(&value),
+            "project" => project = Some(value.into_owned()),
+            "priority" => {
+                let priority = value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|p| (1..=4).contains(p))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Invalid priority '{}', expected 1 to 4", value)
+                    })?;
+                new_task.priority = Some(5 - priority);
+            }
+            "labels" => {
+                new_task.labels = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|label| !label.is_empty())
+                    .map(String::from)
+                    .collect()
+            }
+            other => eprintln!("Ignoring unknown parameter '{}'", other),
+        }
+    }
+    if new_task.content.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Missing task content, e.g. todoist-cli://add?content=Buy%20milk"
+        ));
+    }
+    if let Some(task) = app.add_to_project(new_task, project.as_deref()).await? {
+        println!("Added task {}: {}", task.todoist_id, task.title);
+    }
+    Ok(())
+}
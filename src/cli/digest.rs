@@ -0,0 +1,52 @@
+use crate::config::settings::SmtpSettings;
+use crate::controller::app::App;
+use crate::formats::digest;
+use crate::utils::error::AppResult;
+use chrono::Local;
+use lettre::message::MultiPart;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+/// Prints today's agenda, or emails it through the configured SMTP server.
+pub async fn run(app: &mut App, email: bool, html: bool) -> AppResult<()> {
+    app.sync_projects().await?;
+    let today = Local::now().date_naive();
+    let agenda = digest::agenda(app.tasks(), today);
+    let text = digest::plaintext(&agenda, app.projects(), today);
+    let html_body = digest::html(&agenda, app.projects(), today);
+
+    if !email {
+        print!("{}", if html { html_body } else { text });
+        return Ok(());
+    }
+
+    let smtp = app.settings().smtp.as_ref().ok_or_else(|| {
+        anyhow::anyhow!("No [smtp] section in the config file; cannot send the digest")
+    })?;
+    let subject = format!("Todoist agenda for {}", today.format("%Y-%m-%d"));
+    send(smtp, &subject, text, html_body).await?;
+    println!("Sent digest to {}", smtp.to);
+    Ok(())
+}
+
+/// Sends a multipart plaintext/HTML message over SMTP with STARTTLS.
+async fn send(smtp: &SmtpSettings, subject: &str, text: String, html: String) -> AppResult<()> {
+    let message = Message::builder()
+        .from(smtp.from.parse()?)
+        .to(smtp.to.parse()?)
+        .subject(subject)
+        .multipart(MultiPart::alternative_plain_html(text, html))?;
+
+    let mut transport =
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host)?.port(smtp.port);
+    if let Some(username) = &smtp.username {
+        let password = smtp
+            .password
+            .clone()
+            .or_else(|| std::env::var("TODOIST_SMTP_PASSWORD").ok())
+            .unwrap_or_default();
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+    transport.build().send(message).await?;
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use crate::api::client::ApiClient;
+use crate::config::settings::Settings;
+use crate::db::cache::{Cache, SCHEMA_VERSION};
+use crate::utils::error::AppResult;
+use crossterm::tty::IsTty;
+
+/// Prints a check result with an optional suggested fix.
+fn report(ok: bool, message: &str, fix: Option<&str>) {
+    println!("[{}] {}", if ok { " ok " } else { "FAIL" }, message);
+    if let (false, Some(fix)) = (ok, fix) {
+        println!("       fix: {}", fix);
+    }
+}
+
+/// Diagnoses the token, cache, config, terminal, and network, printing actionable fixes.
+pub async fn run() -> AppResult<()> {
+    check_config();
+    check_cache();
+    check_terminal();
+    let reachable = check_network().await;
+    check_token(reachable).await;
+    Ok(())
+}
+
+fn check_config() {
+    let path = Settings::path();
+    let shown = path
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "<no config directory>".to_string());
+    match Settings::load() {
+        Ok(_) if path.as_ref().is_some_and(|p| p.exists()) => {
+            report(true, &format!("Config parsed: {}", shown), None)
+        }
+        Ok(_) => report(
+            true,
+            &format!("No config file at {} (using defaults)", shown),
+            None,
+        ),
+        Err(e) => report(
+            false,
+            &format!("{}", e),
+            Some("Fix the TOML syntax or remove the file to use defaults"),
+        ),
+    }
+}
+
+fn check_cache() {
+    // Read-only, so diagnosing neither creates the cache nor migrates it
+    let cache = match Cache::read_only() {
+        Ok(Some(cache)) => cache,
+        Ok(None) => {
+            report(true, "No cache tasks.db yet (created on first sync)", None);
+            return;
+        }
+        Err(e) => {
+            report(
+                false,
+                &format!("Cannot open cache tasks.db: {}", e),
+                Some("Check file permissions, or delete tasks.db to rebuild it on next sync"),
+            );
+            return;
+        }
+    };
+    match cache.integrity_problems() {
+        Ok(problems) if problems.is_empty() => report(true, "Cache integrity check passed", None),
+        Ok(problems) => report(
+            false,
+            &format!("Cache integrity problems: {}", problems.join("; ")),
+            Some("Delete tasks.db; it is rebuilt from Todoist on the next sync"),
+        ),
+        Err(e) => report(false, &format!("Cache integrity check failed: {}", e), None),
+    }
+    match cache.schema_version() {
+        Ok(version) => report(
+            version == SCHEMA_VERSION,
+            &format!(
+                "Cache schema version {} (expected {})",
+                version, SCHEMA_VERSION
+            ),
+            Some(if version < SCHEMA_VERSION {
+                "Run any todoist-cli command to migrate it, or delete tasks.db to recreate it"
+            } else {
+                "The cache was written by a newer todoist-cli; upgrade, or delete tasks.db"
+            }),
+        ),
+        Err(e) => report(false, &format!("Cannot read schema version: {}", e), None),
+    }
+}
+
+fn check_terminal() {
+    let tty = std::io::stdout().is_tty();
+    report(
+        tty,
+        "Standard output is a terminal",
+        Some("The TUI needs an interactive terminal; CLI subcommands work when piped"),
+    );
+    let term = std::env::var("TERM").unwrap_or_default();
+    report(
+        !term.is_empty() && term != "dumb",
+        &format!("TERM={}", if term.is_empty() { "<unset>" } else { &term }),
+        Some("Set TERM to a capable terminal type such as xterm-256color"),
+    );
+    if let Ok((cols, rows)) = crossterm::terminal::size() {
+        report(
+            cols >= 60 && rows >= 15,
+            &format!("Terminal size {}x{}", cols, rows),
+            Some("Enlarge the terminal to at least 60x15 for the TUI"),
+        );
+    }
+    if std::env::var_os("NO_COLOR").is_some() {
+        report(true, "NO_COLOR is set; colors are disabled", None);
+    }
+}
+
+async fn check_network() -> bool {
+    match reqwest::get("https://api.todoist.com").await {
+        Ok(_) => {
+            report(true, "api.todoist.com is reachable", None);
+            true
+        }
+        Err(e) => {
+            report(
+                false,
+                &format!("Cannot reach api.todoist.com: {}", e),
+                Some("Check your connection, proxy settings (HTTPS_PROXY), or firewall"),
+            );
+            false
+        }
+    }
+}
+
+async fn check_token(reachable: bool) {
+    let Ok(token) = std::env::var("TODOIST_TOKEN") else {
+        report(
+            false,
+            "TODOIST_TOKEN is not set",
+            Some(
+                "Export TODOIST_TOKEN with the token from Todoist → Settings → Integrations → Developer",
+            ),
+        );
+        return;
+    };
+    if !reachable {
+        report(false, "Token not verified (network unreachable)", None);
+        return;
+    }
+    let version = Settings::load().map(|s| s.api).unwrap_or_default();
+    match ApiClient::new(token).with_version(version).fetch_user().await {
+        Ok(user) => report(
+            true,
+            &format!("Token valid for {} <{}>", user.full_name, user.email),
+            None,
+        ),
+        Err(e) => report(
+            false,
+            &format!("Token rejected: {}", e),
+            Some("Copy a fresh API token from Todoist → Settings → Integrations → Developer"),
+        ),
+    }
+}
@@ -0,0 +1,28 @@
+use crate::utils::dates::parse_due;
+use crate::utils::error::AppResult;
+use crate::utils::recurrence::parse_recurrence;
+use chrono::Local;
+
+/// Prints the next `count` dates a recurring due string falls on, or the single date of a
+/// one-off one, to check how it'll be read before saving a task with it.
+pub fn run(due: &str, count: usize) -> AppResult<()> {
+    let today = Local::now().date_naive();
+    if let Some(recurrence) = parse_recurrence(due, today) {
+        let dates = recurrence.occurrences(count);
+        if dates.is_empty() {
+            println!("'{}' has no dates left", due);
+        }
+        for date in dates {
+            println!("{}", date.format("%a %Y-%m-%d"));
+        }
+        if recurrence.from_completion {
+            println!("(every! counts from each completion; dates assume completing on time)");
+        }
+        return Ok(());
+    }
+    match parse_due(due, today) {
+        Some(date) => println!("{} (doesn't repeat)", date.format("%a %Y-%m-%d")),
+        None => anyhow::bail!("Can't preview '{}'; Todoist may still understand it", due),
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::controller::app::App;
+use crate::utils::dates::relative_date;
+use crate::utils::error::AppResult;
+use chrono::Local;
+
+/// Lists waiting tasks whose follow-up day has come, or all waiting tasks with `all`, soonest
+/// first. With `nudged`, records a follow-up on that task instead, restarting its interval.
+pub fn run(app: &mut App, all: bool, nudged: Option<&str>) -> AppResult<()> {
+    let today = Local::now().date_naive();
+    if let Some(id) = nudged {
+        let task = app.find_task(id)?;
+        let (id, title) = (task.id, task.title.clone());
+        let next = app.record_followup(id)?;
+        println!("Followed up on \"{}\"; next follow-up {}", title, relative_date(next, today));
+        return Ok(());
+    }
+    let followups = app.followups(all);
+    if followups.is_empty() {
+        println!("{}", if all { "Nothing is waiting on others" } else { "No follow-ups due" });
+    }
+    let id_len = app.short_id_len();
+    for (task, date) in followups {
+        println!("{} {} (follow-up {})", task.short_id(id_len), task.title, relative_date(date, today));
+    }
+    Ok(())
+}
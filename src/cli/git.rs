@@ -0,0 +1,109 @@
+use crate::api::client::ApiClient;
+use crate::config::settings::Settings;
+use crate::utils::error::AppResult;
+use clap::Subcommand;
+use std::path::PathBuf;
+use std::process::Command;
+
+const HOOK_NAME: &str = "post-commit";
+const HOOK_SCRIPT: &str = "#!/bin/sh\n# Installed by todoist-cli: closes tasks referenced in commit messages\nexec todoist-cli git post-commit\n";
+
+/// Git integration actions.
+#[derive(Subcommand)]
+pub enum GitAction {
+    /// Installs a post-commit hook in the current repository
+    InstallHook {
+        /// Overwrite an existing post-commit hook
+        #[arg(long)]
+        force: bool,
+    },
+    /// Closes tasks referenced by the latest commit (run by the hook)
+    PostCommit,
+}
+
+/// Runs a git integration action. Neither needs the cache, so no `tasks.db` is left in the
+/// repository the hook runs in.
+pub async fn run(action: &GitAction) -> AppResult<()> {
+    match action {
+        GitAction::InstallHook { force } => install_hook(*force),
+        GitAction::PostCommit => post_commit().await,
+    }
+}
+
+/// Writes the post-commit hook into the repository's hooks directory.
+fn install_hook(force: bool) -> AppResult<()> {
+    let hooks_dir = PathBuf::from(git(&["rev-parse", "--git-path", "hooks"])?.trim());
+    std::fs::create_dir_all(&hooks_dir)?;
+    let hook = hooks_dir.join(HOOK_NAME);
+    if hook.exists() && !force {
+        return Err(anyhow::anyhow!(
+            "{} already exists; pass --force to overwrite",
+            hook.display()
+        ));
+    }
+    std::fs::write(&hook, HOOK_SCRIPT)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))?;
+    }
+    println!("Installed {}", hook.display());
+    Ok(())
+}
+
+/// Closes each task referenced in the last commit message and comments with the commit hash.
+/// Without a token it does nothing, so the hook never gets in the way of committing; a task
+/// that can't be closed is reported and the others are still closed.
+async fn post_commit() -> AppResult<()> {
+    let Ok(token) = std::env::var("TODOIST_TOKEN") else {
+        return Ok(());
+    };
+    let hash = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    let message = git(&["log", "-1", "--format=%B"])?;
+    let version = Settings::load().map(|s| s.api).unwrap_or_default();
+    let api_client = ApiClient::new(token).with_version(version);
+    for todoist_id in referenced_tasks(&message) {
+        let closed = async {
+            api_client.close_task(&todoist_id).await?;
+            api_client
+                .add_comment(&todoist_id, &format!("Closed by commit {}", hash))
+                .await
+        };
+        match closed.await {
+            Ok(()) => println!("Closed task {}", todoist_id),
+            Err(e) => eprintln!("Couldn't close task {}: {}", todoist_id, e),
+        }
+    }
+    Ok(())
+}
+
+/// Extracts task IDs from `todoist:<id>` and `closes t-<id>` references.
+fn referenced_tasks(message: &str) -> Vec<String> {
+    let lower = message.to_ascii_lowercase();
+    let mut ids = Vec::new();
+    for marker in ["todoist:", "closes t-"] {
+        for (start, _) in lower.match_indices(marker) {
+            let id = message[start + marker.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect::<String>();
+            if !id.is_empty() && !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// Runs a git command and returns its stdout.
+fn git(args: &[&str]) -> AppResult<String> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
@@ -0,0 +1,47 @@
+use crate::controller::app::App;
+use crate::models::event::AuditEntry;
+use crate::utils::error::AppResult;
+use chrono::{DateTime, Local};
+
+/// Prints the newest `limit` changes made to tasks from this app, oldest first, with the
+/// previous and new value of edited fields. `task` narrows them to one task by Todoist ID
+/// or a prefix of it, which also finds deleted tasks.
+pub fn run(app: &App, task: Option<&str>, limit: usize) -> AppResult<()> {
+    let entries = app.events(task, limit)?;
+    if entries.is_empty() {
+        println!("No changes recorded");
+    }
+    print_entries(app, &entries, true);
+    Ok(())
+}
+
+/// Prints the tasks added, closed, rescheduled or edited elsewhere, as found by syncs since the
+/// last time this ran, or the newest `limit` of them with `all`.
+pub fn changes(app: &App, all: bool, limit: usize) -> AppResult<()> {
+    let entries = app.remote_changes(all, limit)?;
+    if entries.is_empty() {
+        println!("No changes from elsewhere since you last looked");
+    }
+    print_entries(app, &entries, false);
+    Ok(())
+}
+
+/// Prints one line per change, followed by the old and new values of edited fields. The time
+/// is when the change was made or, for changes found by a sync, when the sync found it.
+fn print_entries(app: &App, entries: &[AuditEntry], show_actor: bool) {
+    let id_len = app.short_id_len();
+    for entry in entries {
+        let at = DateTime::parse_from_rfc3339(&entry.at)
+            .map(|at| at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or(entry.at.clone());
+        let short_id = entry.todoist_id.chars().take(id_len).collect::<String>();
+        let mut line = format!("{} {} {} \"{}\"", at, entry.event.verb(), short_id, entry.title());
+        if show_actor {
+            line.push_str(&format!(" ({})", entry.actor));
+        }
+        println!("{}", line);
+        for change in entry.changes() {
+            println!("    {}", change);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::api::issues::IssueRef;
+use crate::controller::app::App;
+use crate::models::task::NewTask;
+use crate::utils::error::AppResult;
+
+/// Creates a task from an issue URL, linking back to it and optionally tracking its state.
+pub async fn add_from_url(
+    app: &mut App,
+    url: &str,
+    track: bool,
+    due: Option<&str>,
+    priority: Option<u8>,
+    project: Option<&str>,
+) -> AppResult<()> {
+    let issue = IssueRef::parse(url)?.fetch().await?;
+    let mut new_task = NewTask {
+        priority: priority.map(|p| 5 - p),
+        ..NewTask::new(&format!("[{}]({})", issue.title, url))
+    };
+    if let Some(due) = due {
+        new_task.set_due(due);
+    }
+    if let Some(task) = app.add_to_project(new_task, project).await? {
+        if track {
+            app.cache().link_issue(&task.todoist_id, url)?;
+        }
+        println!("Added task {}: {}", task.todoist_id, issue.title);
+    }
+    Ok(())
+}
+
+/// Closes tracked tasks whose linked issues have been closed.
+pub async fn sync_closed(app: &mut App) -> AppResult<()> {
+    let mut closed = Vec::new();
+    for (todoist_id, url) in app.cache().linked_issues()? {
+        if IssueRef::parse(&url)?.fetch().await?.closed {
+            closed.push((todoist_id, url));
+        }
+    }
+    let todoist_ids = closed.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>();
+    app.close_tasks_by_todoist_id(&todoist_ids).await?;
+    for (todoist_id, url) in closed {
+        app.cache().unlink_issue(&todoist_id)?;
+        println!("Closed task {} ({} was closed)", todoist_id, url);
+    }
+    Ok(())
+}
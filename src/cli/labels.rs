@@ -0,0 +1,88 @@
+use crate::controller::app::App;
+use crate::utils::error::AppResult;
+use crate::utils::filter::Filter;
+use chrono::Local;
+use clap::Subcommand;
+
+/// Label actions.
+#[derive(Subcommand)]
+pub enum LabelAction {
+    /// Adds a label to every open task matching a filter
+    Apply {
+        /// Label name, with or without the leading @
+        label: String,
+        /// Todoist filter query, e.g. "#Work & p1", or the name of a saved filter
+        #[arg(long)]
+        filter: String,
+    },
+    /// Removes a label from every open task matching a filter
+    Remove {
+        /// Label name, with or without the leading @
+        label: String,
+        /// Todoist filter query, e.g. "#Work & p1", or the name of a saved filter
+        #[arg(long)]
+        filter: String,
+    },
+}
+
+impl LabelAction {
+    /// The filter query selecting the tasks to change.
+    pub fn filter(&self) -> &str {
+        match self {
+            LabelAction::Apply { filter, .. } | LabelAction::Remove { filter, .. } => filter,
+        }
+    }
+}
+
+/// Adds or strips a label across the open tasks matching `filter`, in batched requests, and
+/// reports the tasks Todoist refused.
+pub async fn run(app: &mut App, action: &LabelAction, filter: &Filter) -> AppResult<()> {
+    let (label, apply) = match action {
+        LabelAction::Apply { label, .. } => (label.trim_start_matches('@'), true),
+        LabelAction::Remove { label, .. } => (label.trim_start_matches('@'), false),
+    };
+    if label.is_empty() {
+        anyhow::bail!("Label name can't be empty");
+    }
+    let today = Local::now().date_naive();
+    let changes = app
+        .tasks()
+        .iter()
+        .filter(|task| !task.is_done() && filter.matches(task, app.projects(), today))
+        .filter(|task| task.labels.iter().any(|l| l == label) != apply)
+        .map(|task| {
+            let mut labels = task.labels.clone();
+            if apply {
+                labels.push(label.to_string());
+            } else {
+                labels.retain(|l| l != label);
+            }
+            (task.id, labels)
+        })
+        .collect::<Vec<_>>();
+    if changes.is_empty() {
+        println!("No matching tasks {} @{}", if apply { "without" } else { "with" }, label);
+        return Ok(());
+    }
+
+    let results = app.set_labels_batch(&changes).await?;
+    let id_len = app.short_id_len();
+    let mut failed = 0;
+    for ((id, _), result) in changes.iter().zip(&results) {
+        let Err(e) = result else {
+            continue;
+        };
+        match app.tasks().iter().find(|t| t.id == *id) {
+            Some(task) => eprintln!("{} {}: {}", task.short_id(id_len), task.title, e),
+            None => eprintln!("{}: {}", id, e),
+        }
+        failed += 1;
+    }
+    let updated = changes.len() - failed;
+    let plural = if updated == 1 { "" } else { "s" };
+    match apply {
+        true => println!("Added @{} to {} task{}", label, updated, plural),
+        false => println!("Removed @{} from {} task{}", label, updated, plural),
+    }
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::cli::commands::Cli;
+use crate::utils::error::AppResult;
+use clap::CommandFactory;
+use clap_mangen::Man;
+use std::path::Path;
+
+/// Writes roff man pages for the CLI and each subcommand, or prints the main page to stdout.
+pub fn generate(out_dir: Option<&Path>) -> AppResult<()> {
+    let command = Cli::command();
+    let Some(out_dir) = out_dir else {
+        Man::new(command).render(&mut std::io::stdout())?;
+        return Ok(());
+    };
+
+    std::fs::create_dir_all(out_dir)?;
+    let name = command.get_name().to_string();
+    for subcommand in command.get_subcommands() {
+        let page_name = format!("{}-{}", name, subcommand.get_name());
+        let subcommand = subcommand
+            .clone()
+            .display_name(page_name.clone())
+            .bin_name(format!("{} {}", name, subcommand.get_name()));
+        write_page(Man::new(subcommand), out_dir, &page_name)?;
+    }
+    write_page(Man::new(command), out_dir, &name)?;
+    println!("Wrote man pages to {}", out_dir.display());
+    Ok(())
+}
+
+fn write_page(man: Man, out_dir: &Path, name: &str) -> AppResult<()> {
+    let mut buffer = Vec::new();
+    man.render(&mut buffer)?;
+    std::fs::write(out_dir.join(format!("{}.1", name)), buffer)?;
+    Ok(())
+}
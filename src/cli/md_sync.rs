@@ -0,0 +1,148 @@
+use crate::controller::app::App;
+use crate::models::task::NewTask;
+use crate::utils::error::AppResult;
+use std::path::{Path, PathBuf};
+
+/// A markdown checkbox line, split into its parts.
+struct Checkbox<'a> {
+    indent: &'a str,
+    checked: bool,
+    title: &'a str,
+    todoist_id: Option<&'a str>,
+}
+
+impl<'a> Checkbox<'a> {
+    /// Parses `- [ ] title (todoist:ID)`, where the ID suffix is optional.
+    fn parse(line: &'a str) -> Option<Self> {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        let (checked, rest) = if let Some(rest) = trimmed.strip_prefix("- [ ] ") {
+            (false, rest)
+        } else if let Some(rest) = trimmed
+            .strip_prefix("- [x] ")
+            .or_else(|| trimmed.strip_prefix("- [X] "))
+        {
+            (true, rest)
+        } else {
+            return None;
+        };
+        let rest = rest.trim_end();
+        let (title, todoist_id) = match rest
+            .strip_suffix(')')
+            .and_then(|r| r.rsplit_once(" (todoist:"))
+        {
+            Some((title, id)) => (title, Some(id)),
+            None => (rest, None),
+        };
+        Some(Checkbox {
+            indent,
+            checked,
+            title,
+            todoist_id,
+        })
+    }
+
+    fn render(&self, checked: bool, todoist_id: &str) -> String {
+        let mark = if checked { "x" } else { " " };
+        format!(
+            "{}- [{}] {} (todoist:{})",
+            self.indent, mark, self.title, todoist_id
+        )
+    }
+}
+
+/// Syncs markdown checkboxes under `dir` with Todoist in both directions.
+pub async fn run(app: &mut App, dir: &Path) -> AppResult<()> {
+    // Tagged lines missing from the task list get checked off, so reconcile only against every
+    // open task fetched just now; offline or with lazy loading the cached list is partial
+    app.sync_tasks().await?;
+    let mut files = Vec::new();
+    collect_markdown(dir, &mut files)?;
+    let (mut created, mut checked_off) = (0, 0);
+    let mut to_close = Vec::new();
+    // A failed create stops further ones, but files are still written so created IDs aren't lost
+    let mut failed = None;
+
+    for file in files {
+        let contents = std::fs::read_to_string(&file)?;
+        let mut changed = false;
+        let mut lines = Vec::new();
+        for line in contents.lines() {
+            let Some(checkbox) = Checkbox::parse(line) else {
+                lines.push(line.to_string());
+                continue;
+            };
+            match (checkbox.checked, checkbox.todoist_id) {
+                (false, None) if failed.is_some() || checkbox.title.trim().is_empty() => {
+                    lines.push(line.to_string())
+                }
+                // New unchecked line: create a task and tag the line with its ID. Queued offline
+                // it would only have a temporary ID, which never matches again
+                (false, None) => {
+                    match app.create_task_online(&NewTask::new(checkbox.title)).await {
+                        Ok(task) => {
+                            lines.push(checkbox.render(false, &task.todoist_id));
+                            changed = true;
+                            created += 1;
+                        }
+                        Err(e) => {
+                            failed = Some(e);
+                            lines.push(line.to_string());
+                        }
+                    }
+                }
+                (checked, Some(id)) => {
+                    let open_remotely =
+                        app.tasks().iter().any(|t| t.todoist_id == id && !t.is_done());
+                    if checked && open_remotely {
+                        if !to_close.iter().any(|c| c == id) {
+                            to_close.push(id.to_string());
+                        }
+                        lines.push(line.to_string());
+                    } else if !checked && !open_remotely {
+                        // Completed or deleted elsewhere
+                        lines.push(checkbox.render(true, id));
+                        changed = true;
+                        checked_off += 1;
+                    } else {
+                        lines.push(line.to_string());
+                    }
+                }
+                (true, None) => lines.push(line.to_string()),
+            }
+        }
+        if changed {
+            let mut updated = lines.join("\n");
+            if contents.ends_with('\n') {
+                updated.push('\n');
+            }
+            std::fs::write(&file, updated)?;
+        }
+    }
+
+    app.close_tasks_by_todoist_id(&to_close).await?;
+    println!(
+        "Created {} tasks, closed {} tasks, checked off {} lines",
+        created,
+        to_close.len(),
+        checked_off
+    );
+    failed.map_or(Ok(()), Err)
+}
+
+/// Recursively collects `.md` files, skipping hidden directories such as `.obsidian`.
+fn collect_markdown(dir: &Path, files: &mut Vec<PathBuf>) -> AppResult<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with('.'));
+        if path.is_dir() && !hidden {
+            collect_markdown(&path, files)?;
+        } else if path.extension().is_some_and(|e| e == "md") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -1 +1,29 @@
-pub mod commands;
\ No newline at end of file
+pub mod archive;
+pub mod backup;
+pub mod bar;
+pub mod bulk_edit;
+pub mod capture;
+pub mod checklist;
+pub mod commands;
+pub mod deep_link;
+pub mod digest;
+pub mod due_preview;
+pub mod followups;
+pub mod doctor;
+pub mod git;
+pub mod history;
+pub mod issues;
+pub mod labels;
+pub mod man;
+pub mod md_sync;
+pub mod notify;
+pub mod output;
+pub mod prefetch;
+pub mod projects;
+pub mod queue;
+pub mod review;
+pub mod self_update;
+pub mod stats;
+pub mod statusline;
+pub mod template;
+pub mod watch;
\ No newline at end of file
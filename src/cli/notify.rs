@@ -0,0 +1,76 @@
+use crate::controller::app::App;
+use crate::models::task::Task;
+use crate::utils::error::AppResult;
+use chrono::{Duration, Local, NaiveDateTime};
+use notify_rust::Notification;
+
+/// Sends desktop notifications for due and overdue tasks, optionally polling until interrupted.
+pub async fn run(app: &mut App, within: i64, daemonize: bool, interval: u64) -> AppResult<()> {
+    loop {
+        let sent = notify_due(app, within)?;
+        if !daemonize {
+            println!("Sent {} notifications", sent);
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+        if let Err(e) = app.incremental_sync().await {
+            eprintln!("Sync failed, using cached tasks: {}", e);
+        }
+        app.save_api_stats()?;
+    }
+}
+
+/// Notifies each task that is due soon or overdue and hasn't been notified for its current due date.
+fn notify_due(app: &App, within: i64) -> AppResult<usize> {
+    let now = Local::now().naive_local();
+    let mut sent = 0;
+    for task in app.tasks() {
+        let (Some(due), Some(message)) = (&task.due, alert_message(task, now, within)) else {
+            continue;
+        };
+        let key = due.datetime.as_deref().unwrap_or(&due.date);
+        if app.cache().was_notified(&task.todoist_id, key)? {
+            continue;
+        }
+        send(task, &message)?;
+        app.cache().mark_notified(&task.todoist_id, key)?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+/// Shows a desktop notification for a task.
+pub fn send(task: &Task, message: &str) -> AppResult<()> {
+    Notification::new()
+        .appname("todoist-cli")
+        .summary(&task.title)
+        .body(message)
+        .show()?;
+    Ok(())
+}
+
+/// Describes why a task needs a notification, if it does.
+fn alert_message(task: &Task, now: NaiveDateTime, within: i64) -> Option<String> {
+    if task.is_done() {
+        return None;
+    }
+    let due = task.due.as_ref()?;
+    match due.naive_datetime() {
+        Some(at) if at <= now => Some(format!("Overdue since {}", at.format("%Y-%m-%d %H:%M"))),
+        Some(at) if at <= now + Duration::minutes(within) => {
+            Some(format!("Due at {}", at.format("%H:%M")))
+        }
+        Some(_) => None,
+        None => {
+            let date = due.naive_date()?;
+            let today = now.date();
+            if date < today {
+                Some(format!("Overdue since {}", date.format("%Y-%m-%d")))
+            } else if date == today {
+                Some("Due today".to_string())
+            } else {
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::controller::groups::Group;
+use crate::controller::rows;
+use crate::models::project::Project;
+use crate::models::task::Task;
+use crate::utils::color::{BOLD, RED, paint, priority_color};
+use crate::utils::text::{pad, width};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+/// How listing commands print tasks.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum OutputMode {
+    /// Aligned columns with a header, for reading
+    Table,
+    /// One line per task, for grep
+    #[default]
+    Plain,
+    /// Tab-separated full Todoist ID, status, priority, due date, project, labels and title, for awk
+    Tsv,
+}
+
+/// Prints tasks in the given mode. Colors only apply to `plain` and `table` output.
+pub fn print_tasks(
+    tasks: &[&Task],
+    projects: &[Project],
+    today: NaiveDate,
+    relative_dates: bool,
+    color: bool,
+    mode: OutputMode,
+    id_len: usize,
+) {
+    print_sections(
+        &[(None, tasks)],
+        projects,
+        today,
+        relative_dates,
+        color,
+        mode,
+        id_len,
+    );
+}
+
+/// Prints grouped tasks under "Title (count)" headers; `tsv` output puts the group title in a
+/// leading column instead, so every line stays a record.
+pub fn print_groups(
+    groups: &[Group],
+    projects: &[Project],
+    today: NaiveDate,
+    relative_dates: bool,
+    color: bool,
+    mode: OutputMode,
+    id_len: usize,
+) {
+    let sections = groups
+        .iter()
+        .map(|g| (Some(g.title.as_str()), g.tasks.as_slice()))
+        .collect::<Vec<_>>();
+    print_sections(&sections, projects, today, relative_dates, color, mode, id_len);
+}
+
+/// Prints sections of tasks with optional titles.
+fn print_sections(
+    sections: &[(Option<&str>, &[&Task])],
+    projects: &[Project],
+    today: NaiveDate,
+    relative_dates: bool,
+    color: bool,
+    mode: OutputMode,
+    id_len: usize,
+) {
+    let header = |i: usize, title: Option<&str>, count: usize| {
+        if let Some(title) = title {
+            let blank = if i > 0 { "\n" } else { "" };
+            println!(
+                "{}{}",
+                blank,
+                paint(&format!("{} ({})", title, count), BOLD, color)
+            );
+        }
+    };
+    match mode {
+        OutputMode::Plain => {
+            for (i, (title, tasks)) in sections.iter().enumerate() {
+                header(i, *title, tasks.len());
+                for task in tasks.iter() {
+                    let row = rows::format_row(task, projects, today, relative_dates, color);
+                    println!("{} {}", task.short_id(id_len), row);
+                }
+            }
+        }
+        OutputMode::Table => {
+            let table = Table::new(sections, projects, today, relative_dates, id_len);
+            table.print_header();
+            for (i, (title, tasks)) in sections.iter().enumerate() {
+                header(i, *title, tasks.len());
+                for task in tasks.iter() {
+                    table.print_row(task, projects, today, color);
+                }
+            }
+        }
+        OutputMode::Tsv => {
+            for (title, tasks) in sections {
+                for task in tasks.iter() {
+                    match title {
+                        Some(title) => println!("{}\t{}", title, tsv_row(task, projects)),
+                        None => println!("{}", tsv_row(task, projects)),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Prints tasks as a tree with each subtask under its parent, e.g. `6X7rM899 └── [ ] Subtask`.
+/// Subtasks whose parent isn't listed become roots; siblings keep their order.
+pub fn print_tree(
+    tasks: &[&Task],
+    projects: &[Project],
+    today: NaiveDate,
+    relative_dates: bool,
+    color: bool,
+    id_len: usize,
+) {
+    let id_width = tasks.iter().map(|t| t.short_id(id_len).chars().count()).max().unwrap_or(0);
+    let is_listed = |id: &str| tasks.iter().any(|t| t.todoist_id == id);
+    let roots = tasks
+        .iter()
+        .filter(|t| t.parent_id.as_deref().is_none_or(|parent| !is_listed(parent)));
+    let mut stack = roots.rev().map(|&task| (task, String::new(), None)).collect::<Vec<_>>();
+    // Depth-first with an explicit stack; each entry carries its indentation and branch
+    while let Some((task, indent, last)) = stack.pop() {
+        let branch = match last {
+            Some(true) => "└── ",
+            Some(false) => "├── ",
+            None => "",
+        };
+        let row = rows::format_row(task, projects, today, relative_dates, color);
+        println!("{:<width$} {}{}{}", task.short_id(id_len), indent, branch, row, width = id_width);
+        let child_indent = match last {
+            Some(true) => format!("{}    ", indent),
+            Some(false) => format!("{}│   ", indent),
+            None => indent,
+        };
+        let children = tasks
+            .iter()
+            .filter(|t| t.parent_id.as_deref() == Some(task.todoist_id.as_str()))
+            .collect::<Vec<_>>();
+        for (i, &&child) in children.iter().enumerate().rev() {
+            stack.push((child, child_indent.clone(), Some(i + 1 == children.len())));
+        }
+    }
+}
+
+/// Column headers of table output.
+const TABLE_HEADER: [&str; 6] = ["ID", "DONE", "PRI", "DUE", "PROJECT", "TITLE"];
+
+/// Table cells of every task, with column widths fitting all of them so sections line up.
+struct Table {
+    cells: HashMap<usize, [String; 6]>,
+    widths: [usize; 6],
+}
+
+impl Table {
+    fn new(
+        sections: &[(Option<&str>, &[&Task])],
+        projects: &[Project],
+        today: NaiveDate,
+        relative_dates: bool,
+        id_len: usize,
+    ) -> Self {
+        let mut widths = TABLE_HEADER.map(width);
+        let mut cells = HashMap::new();
+        for task in sections.iter().flat_map(|(_, tasks)| tasks.iter()) {
+            let mut title = task.title.clone();
+            if let Some(place) = &task.location {
+                title.push_str(&format!(" 📍 {}", place));
+            }
+            let row = [
+                task.short_id(id_len).to_string(),
+                if task.is_done() { "[x]" } else { "[ ]" }.to_string(),
+                format!("p{}", 5 - task.priority.clamp(1, 4)),
+                task.due
+                    .as_ref()
+                    .map_or(String::new(), |due| due.display(today, relative_dates)),
+                project_name(task, projects).to_string(),
+                title,
+            ];
+            for (column_width, cell) in widths.iter_mut().zip(&row) {
+                *column_width = (*column_width).max(width(cell));
+            }
+            cells.insert(task.id, row);
+        }
+        Table { cells, widths }
+    }
+
+    fn print_header(&self) {
+        let header = TABLE_HEADER
+            .iter()
+            .zip(self.widths)
+            .map(|(h, width)| pad(h, width))
+            .collect::<Vec<_>>();
+        println!("{}", header.join("  ").trim_end());
+    }
+
+    /// Prints a task's row; cells are padded before coloring so escapes don't skew the widths.
+    fn print_row(&self, task: &Task, projects: &[Project], today: NaiveDate, color: bool) {
+        let Some(row) = self.cells.get(&task.id) else {
+            return;
+        };
+        let project = projects.iter().find(|p| p.id == task.project_id);
+        let styles = [
+            None,
+            None,
+            priority_color(task.priority).map(str::to_string),
+            task.is_overdue(today).then(|| RED.to_string()),
+            project.map(rows::project_color),
+            None,
+        ];
+        let line = row
+            .iter()
+            .zip(self.widths)
+            .zip(styles)
+            .enumerate()
+            .map(|(i, ((cell, width), style))| {
+                // The title is last, so it isn't padded
+                let text = if i + 1 == row.len() {
+                    cell.clone()
+                } else {
+                    pad(cell, width)
+                };
+                match style {
+                    Some(style) if !cell.is_empty() => paint(&text, &style, color),
+                    _ => text,
+                }
+            })
+            .collect::<Vec<_>>();
+        println!("{}", line.join("  "));
+    }
+}
+
+/// Builds a TSV line with the due date in `YYYY-MM-DD` form and labels joined by commas. Tabs
+/// and line breaks in titles are replaced by spaces to keep one task per line.
+fn tsv_row(task: &Task, projects: &[Project]) -> String {
+    let title = task.title.replace(['\t', '\n', '\r'], " ");
+    let due = task.due.as_ref().map_or("", |due| due.date.as_str());
+    [
+        task.todoist_id.clone(),
+        if task.is_done() { "x" } else { "" }.to_string(),
+        format!("p{}", 5 - task.priority.clamp(1, 4)),
+        due.to_string(),
+        project_name(task, projects).to_string(),
+        task.labels.join(","),
+        title,
+    ]
+    .join("\t")
+}
+
+/// Gets the name of a task's project, or an empty string when projects aren't loaded.
+fn project_name<'a>(task: &Task, projects: &'a [Project]) -> &'a str {
+    projects
+        .iter()
+        .find(|p| p.id == task.project_id)
+        .map_or("", |p| p.name.as_str())
+}
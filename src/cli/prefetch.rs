@@ -0,0 +1,15 @@
+use crate::controller::app::App;
+use crate::utils::error::AppResult;
+use chrono::{Duration, Utc};
+
+/// Refreshes everything the app caches: tasks, projects, labels, saved filters, the daily goal and the tasks
+/// completed in the last `days` days. Prints nothing unless something fails, so cron only
+/// mails about problems. The stats and completed tasks need API v1.
+pub async fn run(app: &mut App, days: i64) -> AppResult<()> {
+    app.incremental_sync().await?;
+    app.sync_projects().await?;
+    app.sync_labels().await?;
+    app.sync_filters().await?;
+    app.sync_daily_goal().await?;
+    app.sync_completed_history(Utc::now() - Duration::days(days)).await
+}
@@ -0,0 +1,96 @@
+use crate::controller::app::App;
+use crate::controller::rows;
+use crate::models::project;
+use crate::utils::color::paint;
+use crate::utils::error::AppResult;
+use clap::Subcommand;
+
+/// Project actions.
+#[derive(Subcommand)]
+pub enum ProjectsAction {
+    /// Lists projects with sub-projects indented under their parents, favorites first
+    List {
+        /// Also list archived projects
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Creates a project
+    Add {
+        /// Project name
+        name: String,
+        /// Name of the project to nest it under
+        #[arg(long)]
+        parent: Option<String>,
+    },
+    /// Archives a project along with its tasks
+    Archive {
+        /// Project name
+        name: String,
+    },
+    /// Restores an archived project
+    Unarchive {
+        /// Project name
+        name: String,
+    },
+    /// Adds a project to the favorites
+    Favorite {
+        /// Project name
+        name: String,
+    },
+    /// Removes a project from the favorites
+    Unfavorite {
+        /// Project name
+        name: String,
+    },
+}
+
+/// Runs a project action; listed project names are drawn in their Todoist colors when `color` is set.
+pub async fn run(app: &mut App, action: &ProjectsAction, color: bool) -> AppResult<()> {
+    match action {
+        ProjectsAction::List { archived } => {
+            app.sync_projects().await?;
+            // Stable sort keeps Todoist's order within each group
+            let mut projects = app.projects().clone();
+            projects.sort_by_key(|p| !p.is_favorite);
+            for (depth, project) in project::nested(&projects) {
+                let star = if project.is_favorite { "★ " } else { "" };
+                let name = paint(&project.name, &rows::project_color(project), color);
+                println!("{}{}{}", "  ".repeat(depth), star, name);
+            }
+            if *archived {
+                for project in app.archived_projects().await? {
+                    println!("{} (archived)", project.name);
+                }
+            }
+            Ok(())
+        }
+        ProjectsAction::Add { name, parent } => {
+            let project = app.add_project(name, parent.as_deref()).await?;
+            match parent {
+                Some(parent) => println!("Added project '{}' under '{}'", project.name, parent),
+                None => println!("Added project '{}'", project.name),
+            }
+            Ok(())
+        }
+        ProjectsAction::Archive { name } => {
+            let project = app.set_project_archived(name, true).await?;
+            println!("Archived project '{}'", project.name);
+            Ok(())
+        }
+        ProjectsAction::Unarchive { name } => {
+            let project = app.set_project_archived(name, false).await?;
+            println!("Unarchived project '{}'", project.name);
+            Ok(())
+        }
+        ProjectsAction::Favorite { name } => {
+            let project = app.set_project_favorite(name, true).await?;
+            println!("Added '{}' to favorites", project.name);
+            Ok(())
+        }
+        ProjectsAction::Unfavorite { name } => {
+            let project = app.set_project_favorite(name, false).await?;
+            println!("Removed '{}' from favorites", project.name);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::api::sync::SyncCommand;
+use crate::controller::app::{App, ConflictChoice};
+use crate::utils::error::AppResult;
+use chrono::{DateTime, Local};
+use clap::Subcommand;
+
+/// Offline queue actions.
+#[derive(Subcommand)]
+pub enum QueueAction {
+    /// Lists the changes waiting to be sent, oldest first
+    List,
+    /// Sends the queued changes to Todoist now
+    Retry,
+    /// Removes a change from the queue without sending it
+    Drop {
+        /// Number of the change in `queue list`
+        n: usize,
+    },
+    /// Sends the held edits of a task also changed in Todoist, over those changes
+    KeepMine {
+        /// Number of the conflict in `queue list`
+        n: usize,
+    },
+    /// Drops the held edits of a task also changed in Todoist, keeping Todoist's copy
+    KeepTheirs {
+        /// Number of the conflict in `queue list`
+        n: usize,
+    },
+}
+
+/// Runs a queue action on the changes made while Todoist couldn't be reached.
+pub async fn run(app: &mut App, action: &QueueAction) -> AppResult<()> {
+    match action {
+        QueueAction::List => {
+            let operations = app.queued_operations()?;
+            if operations.is_empty() {
+                println!("No changes queued");
+            }
+            for (n, (queued_at, command)) in operations.iter().enumerate() {
+                let at = DateTime::parse_from_rfc3339(queued_at)
+                    .map(|at| at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or(queued_at.clone());
+                println!("{:>3}. {} {}", n + 1, at, describe(app, command));
+            }
+            if !app.conflicts().is_empty() {
+                println!("\nHeld back, edited in Todoist too (queue keep-mine/keep-theirs <n>):");
+            }
+            for (n, conflict) in app.conflicts().iter().enumerate() {
+                println!("{:>3}. \"{}\"", n + 1, conflict.mine.title);
+                for (field, mine, theirs) in conflict.differences() {
+                    println!("       {}: mine \"{}\", Todoist's \"{}\"", field, mine, theirs);
+                }
+            }
+        }
+        QueueAction::Retry => {
+            if app.queued_changes() == 0 {
+                println!("No changes queued");
+                return Ok(());
+            }
+            app.send_queued().await?;
+            if let Some(status) = app.status() {
+                println!("{}", status);
+            }
+        }
+        QueueAction::KeepMine { n } => {
+            app.resolve_conflict(*n, ConflictChoice::KeepMine).await?;
+            println!("Sent your edits");
+        }
+        QueueAction::KeepTheirs { n } => {
+            app.resolve_conflict(*n, ConflictChoice::KeepTheirs).await?;
+            println!("Kept Todoist's copy");
+        }
+        QueueAction::Drop { n } => {
+            let command = app.drop_queued(*n)?;
+            println!("Dropped {}", describe(app, &command));
+        }
+    }
+    Ok(())
+}
+
+/// Describes a queued command by its type and the task it's for, e.g. `item_close "Call Bo"`.
+fn describe(app: &App, command: &SyncCommand) -> String {
+    let args = &command.args;
+    let task = args["id"].as_str().map(|id| {
+        match app.tasks().iter().find(|t| t.todoist_id == id) {
+            Some(task) => format!("\"{}\"", task.title),
+            None => id.to_string(),
+        }
+    });
+    match (args["content"].as_str(), task) {
+        (Some(content), Some(task)) => format!("{} {} -> \"{}\"", command.kind, task, content),
+        (Some(content), None) => format!("{} \"{}\"", command.kind, content),
+        (None, Some(task)) => format!("{} {}", command.kind, task),
+        (None, None) => command.kind.clone(),
+    }
+}
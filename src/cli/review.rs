@@ -0,0 +1,264 @@
+use crate::controller::app::{App, ReviewAction};
+use crate::models::review::WeeklyReview;
+use crate::models::task::{Task, TaskUpdate};
+use crate::utils::error::AppResult;
+use chrono::{Days, Local, NaiveDate, Utc};
+use std::io::{self, BufRead, Write};
+
+/// A step of the weekly review.
+#[derive(Clone, Copy, PartialEq)]
+enum WeeklyStep {
+    /// Clarify each Inbox task: move it to a project, schedule, complete or delete it.
+    Inbox,
+    /// Decide again about each overdue task.
+    Overdue,
+    /// Follow up on tasks labeled as waiting on someone else.
+    Waiting,
+    /// Look over the next seven days.
+    NextWeek,
+}
+
+const WEEKLY_STEPS: [WeeklyStep; 4] = [WeeklyStep::Inbox, WeeklyStep::Overdue, WeeklyStep::Waiting, WeeklyStep::NextWeek];
+
+impl WeeklyStep {
+    fn title(self) -> &'static str {
+        match self {
+            WeeklyStep::Inbox => "Empty the Inbox",
+            WeeklyStep::Overdue => "Check overdue tasks",
+            WeeklyStep::Waiting => "Check what you're waiting on",
+            WeeklyStep::NextWeek => "Preview next week",
+        }
+    }
+
+    /// Printed when the step has no tasks to go through.
+    fn done_message(self) -> &'static str {
+        match self {
+            WeeklyStep::Inbox => "The Inbox is empty",
+            WeeklyStep::Overdue => "Nothing is overdue",
+            WeeklyStep::Waiting => "Nothing is waiting on others",
+            WeeklyStep::NextWeek => "Nothing is due in the next seven days",
+        }
+    }
+
+    /// Todoist IDs of the open tasks the step goes through, in order.
+    fn tasks(self, app: &App, today: NaiveDate) -> Vec<String> {
+        let inbox = app.projects().iter().find(|p| p.name.eq_ignore_ascii_case("Inbox")).map(|p| &p.id);
+        let mut tasks = app
+            .tasks()
+            .iter()
+            .filter(|t| !t.is_done())
+            .filter(|t| match self {
+                WeeklyStep::Inbox => Some(&t.project_id) == inbox,
+                WeeklyStep::Overdue => t.is_overdue(today),
+                WeeklyStep::Waiting => app.is_waiting(t),
+                WeeklyStep::NextWeek => t
+                    .due
+                    .as_ref()
+                    .and_then(|d| d.local_date())
+                    .is_some_and(|date| date > today && date <= today + Days::new(7)),
+            })
+            .collect::<Vec<_>>();
+        tasks.sort_by_key(|t| t.due.as_ref().and_then(|d| d.sort_key()));
+        tasks.into_iter().map(|t| t.todoist_id.clone()).collect()
+    }
+}
+
+/// What to do with a task during the weekly review.
+enum WeeklyAction {
+    Move(String),
+    Reschedule(String),
+    Complete,
+    Delete,
+    Skip,
+}
+
+/// Guides through a GTD-style weekly review, one step after another. Progress is saved after
+/// every answer, so quitting midway and running it again continues where it stopped, unless
+/// `restart` is set.
+pub async fn run_weekly(app: &mut App, restart: bool) -> AppResult<()> {
+    // Inbox tasks are found by their project's name
+    app.sync_projects().await?;
+    let mut progress = match app.cache().load_weekly_review()? {
+        Some(progress) if !restart && progress.step < WEEKLY_STEPS.len() => {
+            let started = chrono::DateTime::parse_from_rfc3339(&progress.started_at)
+                .map(|at| at.with_timezone(&Local).format("%a %-d %b").to_string())
+                .unwrap_or_default();
+            println!(
+                "Resuming the weekly review started {} at step {}/{}",
+                started,
+                progress.step + 1,
+                WEEKLY_STEPS.len()
+            );
+            progress
+        }
+        _ => WeeklyReview { started_at: Utc::now().to_rfc3339(), step: 0, handled: Vec::new() },
+    };
+    let today = Local::now().date_naive();
+    while let Some(&step) = WEEKLY_STEPS.get(progress.step) {
+        println!("\nStep {}/{}: {}", progress.step + 1, WEEKLY_STEPS.len(), step.title());
+        let tasks = step
+            .tasks(app, today)
+            .into_iter()
+            .filter(|id| !progress.handled.contains(id))
+            .collect::<Vec<_>>();
+        if tasks.is_empty() {
+            println!("{}", step.done_message());
+        }
+        if step == WeeklyStep::NextWeek {
+            preview(app, &tasks);
+        } else {
+            for (i, todoist_id) in tasks.iter().enumerate() {
+                // Tasks completed or deleted since the step started are passed over
+                let Some(task) = app.tasks().iter().find(|t| &t.todoist_id == todoist_id) else {
+                    continue;
+                };
+                println!("\n[{}/{}] {}", i + 1, tasks.len(), describe(task));
+                let id = task.id;
+                let Some(action) = ask_weekly(app, step)? else {
+                    app.cache().save_weekly_review(&progress)?;
+                    println!("Paused; run `review --weekly` again to continue");
+                    return Ok(());
+                };
+                match action {
+                    WeeklyAction::Move(project_id) => app.edit_task(id, &serde_json::json!({}), Some(&project_id)).await?,
+                    WeeklyAction::Reschedule(due) => {
+                        let mut update = TaskUpdate::default();
+                        update.set_due(&due);
+                        app.update_task(id, update).await?;
+                    }
+                    WeeklyAction::Complete => app.set_task_done(id, true).await?,
+                    WeeklyAction::Delete => app.delete_task(id).await?,
+                    WeeklyAction::Skip => {}
+                }
+                progress.handled.push(todoist_id.clone());
+                app.cache().save_weekly_review(&progress)?;
+            }
+        }
+        progress.step += 1;
+        progress.handled.clear();
+        app.cache().save_weekly_review(&progress)?;
+    }
+    app.cache().clear_weekly_review()?;
+    println!("\nWeekly review done");
+    Ok(())
+}
+
+/// Asks what to do with a task in a step of the weekly review; `None` pauses the review.
+fn ask_weekly(app: &App, step: WeeklyStep) -> AppResult<Option<WeeklyAction>> {
+    let question = match step {
+        WeeklyStep::Inbox => "[m]ove to project, [r]eschedule, [c]omplete, [d]elete, [s]kip, [q]uit: ",
+        _ => "[r]eschedule, [c]omplete, [d]elete, [s]kip, [q]uit: ",
+    };
+    loop {
+        let Some(answer) = prompt(question)? else {
+            return Ok(None);
+        };
+        match answer.as_str() {
+            "m" if step == WeeklyStep::Inbox => {
+                let Some(name) = prompt("Project: ")? else {
+                    return Ok(None);
+                };
+                match app.projects().iter().find(|p| p.name.eq_ignore_ascii_case(&name)) {
+                    Some(project) => return Ok(Some(WeeklyAction::Move(project.id.clone()))),
+                    None if name.is_empty() => continue,
+                    None => println!("No project named '{}'", name),
+                }
+            }
+            "r" => match prompt("New due date: ")? {
+                Some(due) if !due.is_empty() => return Ok(Some(WeeklyAction::Reschedule(due))),
+                Some(_) => continue,
+                None => return Ok(None),
+            },
+            "c" => return Ok(Some(WeeklyAction::Complete)),
+            "d" => return Ok(Some(WeeklyAction::Delete)),
+            "s" => return Ok(Some(WeeklyAction::Skip)),
+            "q" => return Ok(None),
+            _ => continue,
+        }
+    }
+}
+
+/// Prints the tasks due in the coming days under a heading per day.
+fn preview(app: &App, todoist_ids: &[String]) {
+    let mut day = None;
+    for task in todoist_ids.iter().filter_map(|id| app.tasks().iter().find(|t| &t.todoist_id == id)) {
+        let date = task.due.as_ref().and_then(|d| d.local_date());
+        if date != day {
+            day = date;
+            if let Some(date) = date {
+                println!("{}", date.format("%a %-d %b"));
+            }
+        }
+        println!("  {}", describe(task));
+    }
+}
+
+/// Walks through tasks untouched for `days` days (or the configured `stale_days`) one at a time,
+/// asking what to do with each.
+pub async fn run(app: &mut App, days: Option<i64>) -> AppResult<()> {
+    let days = days.unwrap_or(app.settings().stale_days);
+    let total = app.start_review(days)?;
+    if total == 0 {
+        println!("No tasks untouched for {} days", days);
+        return Ok(());
+    }
+    while let Some(task) = app.review_task() {
+        let position = total - app.review_remaining() + 1;
+        println!("\n[{}/{}] {}", position, total, describe(task));
+        let action = loop {
+            let Some(answer) = prompt("[k]eep, [r]eschedule, [d]elete, [p] deprioritize, [q]uit: ")? else {
+                break None;
+            };
+            match answer.as_str() {
+                "k" => break Some(ReviewAction::Keep),
+                "r" => match prompt("New due date: ")? {
+                    Some(due) if !due.is_empty() => break Some(ReviewAction::Reschedule(due)),
+                    Some(_) => continue,
+                    None => break None,
+                },
+                "d" => break Some(ReviewAction::Delete),
+                "p" => break Some(ReviewAction::Deprioritize),
+                "q" => break None,
+                _ => continue,
+            }
+        };
+        let Some(action) = action else {
+            break;
+        };
+        app.review(action).await?;
+    }
+    app.end_review();
+    Ok(())
+}
+
+/// Summarizes a task for review, e.g. "Water plants (due in 2 days, p3, unchanged for 45 days)".
+pub fn describe(task: &Task) -> String {
+    let today = Local::now().date_naive();
+    let mut details = Vec::new();
+    if let Some(due) = &task.due {
+        details.push(format!("due {}", due.display(today, true)));
+    }
+    if task.priority > 1 {
+        details.push(format!("p{}", 5 - task.priority));
+    }
+    if let Some(updated) = task.updated() {
+        details.push(format!("unchanged for {} days", (Utc::now() - updated).num_days()));
+    }
+    if details.is_empty() {
+        task.title.clone()
+    } else {
+        format!("{} ({})", task.title, details.join(", "))
+    }
+}
+
+/// Asks a question on stdin, returning the trimmed answer or `None` at end of input.
+fn prompt(question: &str) -> AppResult<Option<String>> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer)? == 0 {
+        println!();
+        return Ok(None);
+    }
+    Ok(Some(answer.trim().to_string()))
+}
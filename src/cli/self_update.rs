@@ -0,0 +1,152 @@
+use crate::utils::error::AppResult;
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/joelpalmer/todoist-cli/releases/latest";
+
+/// Release asset listing `<sha256>  <file name>` for every binary.
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Checks GitHub for a newer release and, unless `check_only`, replaces the running binary with
+/// it after verifying its SHA-256 checksum.
+pub async fn run(check_only: bool) -> AppResult<()> {
+    let client = Client::new();
+    let release: Release = get(&client, RELEASES_URL).await?.json().await?;
+    let current = env!("CARGO_PKG_VERSION");
+    let latest = release.tag_name.trim_start_matches('v');
+    if parse_version(latest) <= parse_version(current) {
+        println!("todoist-cli {} is up to date", current);
+        return Ok(());
+    }
+    if check_only {
+        println!(
+            "todoist-cli {} is available (installed: {}); run `todoist-cli self-update` to install it",
+            latest, current
+        );
+        return Ok(());
+    }
+
+    let name = asset_name();
+    let binary = find_asset(&release, &name)?;
+    let checksums = find_asset(&release, CHECKSUMS_ASSET)?;
+    let sums = get(&client, &checksums.browser_download_url)
+        .await?
+        .text()
+        .await?;
+    let expected = sums
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, file)| file.trim().trim_start_matches('*') == name)
+        .map(|(sum, _)| sum.to_lowercase())
+        .ok_or_else(|| anyhow::anyhow!("{} has no checksum for {}", CHECKSUMS_ASSET, name))?;
+
+    println!("Downloading {} {}...", name, latest);
+    let bytes = get(&client, &binary.browser_download_url)
+        .await?
+        .bytes()
+        .await?;
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        anyhow::bail!(
+            "Checksum mismatch for {}: expected {}, got {}; the binary was not replaced",
+            name,
+            expected,
+            actual
+        );
+    }
+
+    let exe = std::env::current_exe()?;
+    replace_binary(&exe, &bytes)?;
+    println!(
+        "Updated todoist-cli {} → {} at {}",
+        current,
+        latest,
+        exe.display()
+    );
+    Ok(())
+}
+
+async fn get(client: &Client, url: &str) -> AppResult<reqwest::Response> {
+    let response = client
+        .get(url)
+        .header("User-Agent", "todoist-cli")
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Failed to fetch {}: status {}", url, status);
+    }
+    Ok(response)
+}
+
+fn find_asset<'a>(release: &'a Release, name: &str) -> AppResult<&'a Asset> {
+    release
+        .assets
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Release {} has no {} asset; download it from GitHub instead",
+                release.tag_name,
+                name
+            )
+        })
+}
+
+/// Release binary for this platform, e.g. `todoist-cli-linux-x86_64` or
+/// `todoist-cli-windows-x86_64.exe`.
+fn asset_name() -> String {
+    format!(
+        "todoist-cli-{}-{}{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+/// Parses `major.minor.patch`, treating missing or non-numeric parts as 0.
+fn parse_version(version: &str) -> (u64, u64, u64) {
+    let mut parts = version
+        .split(['.', '-', '+'])
+        .map(|part| part.parse().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+/// Swaps the new binary in next to the old one. The running binary is renamed rather than
+/// overwritten, which also works on Windows, and removed afterwards where the OS allows it.
+fn replace_binary(exe: &Path, bytes: &[u8]) -> AppResult<()> {
+    let new = exe.with_extension("new");
+    let old = exe.with_extension("old");
+    std::fs::write(&new, bytes)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&new, std::fs::Permissions::from_mode(0o755))?;
+    }
+    std::fs::rename(exe, &old)?;
+    if let Err(e) = std::fs::rename(&new, exe) {
+        // Put the old binary back so the install keeps working
+        std::fs::rename(&old, exe)?;
+        return Err(e.into());
+    }
+    let _ = std::fs::remove_file(&old);
+    Ok(())
+}
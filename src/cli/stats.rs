@@ -0,0 +1,48 @@
+use crate::api::metrics;
+use crate::cli::statusline::Summary;
+use crate::controller::app::App;
+use crate::formats;
+use crate::utils::error::AppResult;
+use chrono::{DateTime, Local};
+
+/// Prints task counts, or with `api` the API usage of the last session that made requests. With
+/// `csv` it prints the weekly trends per project behind the TUI stats overlay instead.
+pub async fn run(app: &mut App, api: bool, csv: bool) -> AppResult<()> {
+    if api {
+        return print_api_stats(app);
+    }
+    if csv {
+        print!("{}", formats::csv::export_trends(&app.project_trends().await?));
+        return Ok(());
+    }
+    let tasks = app.tasks();
+    let done = tasks.iter().filter(|t| t.is_done()).count();
+    let summary = Summary::from_tasks(tasks, Local::now().date_naive());
+    println!(
+        "{} open, {} done, {} overdue, {} due today",
+        tasks.len() - done,
+        done,
+        summary.overdue,
+        summary.due_today
+    );
+    Ok(())
+}
+
+fn print_api_stats(app: &App) -> AppResult<()> {
+    let Some((session, stats)) = app.cache().last_api_stats()? else {
+        println!("No API requests recorded yet");
+        return Ok(());
+    };
+    let started = DateTime::parse_from_rfc3339(&session)
+        .map(|at| {
+            at.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or(session);
+    println!("Session started {}", started);
+    for line in metrics::format_table(&stats) {
+        println!("{}", line);
+    }
+    Ok(())
+}
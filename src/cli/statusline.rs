@@ -0,0 +1,48 @@
+use crate::models::task::Task;
+use crate::utils::color::{RED, YELLOW, paint};
+use crate::utils::text::truncate;
+use chrono::{Local, NaiveDate};
+
+/// Counts of open tasks needing attention, plus the next one due.
+pub struct Summary<'a> {
+    pub overdue: usize,
+    pub due_today: usize,
+    pub next: Option<&'a Task>,
+}
+
+impl<'a> Summary<'a> {
+    /// Summarizes open tasks relative to the given date.
+    pub fn from_tasks(tasks: &'a [Task], today: NaiveDate) -> Self {
+        let open = tasks.iter().filter(|t| !t.is_done());
+        Summary {
+            overdue: open.clone().filter(|t| t.is_overdue(today)).count(),
+            due_today: open
+                .clone()
+                .filter(|t| t.due.as_ref().and_then(|d| d.naive_date()) == Some(today))
+                .count(),
+            next: open
+                .filter_map(|t| Some((t.due.as_ref()?.sort_key()?, t)))
+                .min_by_key(|(key, _)| *key)
+                .map(|(_, t)| t),
+        }
+    }
+}
+
+/// Renders a compact summary such as "⏰3 ⚑1 next: Buy milk", truncated to `max_len` terminal columns.
+pub fn render(tasks: &[Task], max_len: usize, color: bool) -> String {
+    let summary = Summary::from_tasks(tasks, Local::now().date_naive());
+    let today = format!("⏰{}", summary.due_today);
+    let overdue = format!("⚑{}", summary.overdue);
+    let mut plain = format!("{} {}", today, overdue);
+    if let Some(next) = summary.next {
+        plain.push_str(&format!(" next: {}", next.title));
+    }
+    let plain = truncate(&plain, max_len);
+    if !color {
+        return plain;
+    }
+    // Color only the counters so truncation never splits an escape sequence
+    plain
+        .replacen(&today, &paint(&today, YELLOW, true), 1)
+        .replacen(&overdue, &paint(&overdue, RED, true), 1)
+}
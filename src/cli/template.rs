@@ -0,0 +1,98 @@
+use crate::controller::app::App;
+use crate::models::template::Template;
+use crate::utils::error::AppResult;
+use chrono::Local;
+use clap::Subcommand;
+use std::collections::VecDeque;
+
+/// Task template actions.
+#[derive(Subcommand)]
+pub enum TemplateAction {
+    /// Saves a task and its subtasks as a reusable template
+    Save {
+        /// Template name
+        name: String,
+        /// Todoist ID of the task to save, or enough of its start to be unambiguous
+        #[arg(long)]
+        from: String,
+    },
+    /// Creates tasks from a saved template
+    Apply {
+        /// Template name
+        name: String,
+        /// Project name to create the tasks in; created if it doesn't exist
+        #[arg(long)]
+        project: Option<String>,
+    },
+}
+
+/// Runs a template action.
+pub async fn run(app: &mut App, action: &TemplateAction) -> AppResult<()> {
+    match action {
+        TemplateAction::Save { name, from } => save(app, name, from),
+        TemplateAction::Apply { name, project } => apply(app, name, project.as_deref()).await,
+    }
+}
+
+/// Stores the task with the given ID as a template, replacing today's date with `{date}`.
+fn save(app: &App, name: &str, id: &str) -> AppResult<()> {
+    let task = app.find_task(id)?;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let template = Template::from_task(task, app.tasks(), &today);
+    app.cache().save_template(name, &template)?;
+    println!(
+        "Saved template '{}' ({} subtasks)",
+        name,
+        template.subtasks.len()
+    );
+    Ok(())
+}
+
+/// Creates the template's task tree.
+async fn apply(app: &mut App, name: &str, project: Option<&str>) -> AppResult<()> {
+    let template = app
+        .cache()
+        .load_template(name)?
+        .ok_or_else(|| anyhow::anyhow!("No template named '{}'", name))?;
+    let created = create_tree(app, std::slice::from_ref(&template), project).await?;
+    println!("Created {} tasks from template '{}'", created, name);
+    Ok(())
+}
+
+/// Creates tasks for each template in order, parents before their subtasks, returning how many were created.
+pub async fn create_tree(
+    app: &mut App,
+    templates: &[Template],
+    project: Option<&str>,
+) -> AppResult<usize> {
+    let project_id = match project {
+        Some(project) => {
+            app.sync_projects().await?;
+            Some(app.resolve_project(project).await?)
+        }
+        None => None,
+    };
+    let today = Local::now().format("%Y-%m-%d").to_string();
+
+    let mut pending = templates
+        .iter()
+        .map(|template| (template, None))
+        .collect::<VecDeque<_>>();
+    let mut created = 0;
+    while let Some((template, parent_id)) = pending.pop_front() {
+        let mut new_task = template.new_task(&today);
+        // Subtasks inherit the project of their parent
+        match parent_id {
+            Some(parent_id) => new_task.parent_id = Some(parent_id),
+            None => new_task.project_id = project_id.clone(),
+        }
+        let Some(task) = app.create_task(&new_task).await? else {
+            continue;
+        };
+        created += 1;
+        for subtask in &template.subtasks {
+            pending.push_back((subtask, Some(task.todoist_id.clone())));
+        }
+    }
+    Ok(created)
+}
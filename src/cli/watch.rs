@@ -0,0 +1,24 @@
+use crate::controller::app::App;
+use crate::utils::error::AppResult;
+use chrono::Local;
+use std::time::Duration;
+
+/// Prints task events collaborators make in shared projects as they happen, until interrupted.
+/// Only the `projects` named are watched when any are given.
+pub async fn run(app: &mut App, projects: &[String], interval: u64) -> AppResult<()> {
+    // The first check only notes what's already in the activity log, and fails fast on bad names
+    app.poll_activity(projects).await?;
+    eprintln!("Watching for activity in shared projects; press Ctrl-C to stop");
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+        match app.poll_activity(projects).await {
+            Ok(messages) => {
+                for message in messages {
+                    println!("{}  {}", Local::now().format("%H:%M"), message);
+                }
+            }
+            Err(e) => eprintln!("Activity check failed: {}", e),
+        }
+        app.save_api_stats()?;
+    }
+}
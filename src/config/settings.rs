@@ -0,0 +1,223 @@
+use crate::api::client::ApiVersion;
+use crate::controller::columns::{Column, default_columns};
+use crate::models::task::{NewTask, Task};
+use crate::models::template::Template;
+use crate::utils::error::AppResult;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// User settings read from `config.toml`.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Todoist API to use: "v1", "rest-v2", or "auto" to detect it.
+    pub api: ApiVersion,
+    /// Block startup on a sync; when false the TUI opens from cache and syncs in the background.
+    pub sync_on_start: bool,
+    /// Sync only Today/Upcoming and opened projects in the TUI, loading other projects on demand.
+    pub lazy_projects: bool,
+    /// Seconds between background syncs while the TUI is open; 0 turns them off.
+    pub sync_interval: u64,
+    /// Show due dates relative to today ("in 3 days") instead of as absolute dates.
+    pub relative_dates: bool,
+    /// Columns of the TUI task list, in order.
+    pub columns: Vec<Column>,
+    /// Days without changes after which a task comes up in `review`.
+    pub stale_days: i64,
+    /// Label of tasks waiting on someone else, checked in `review --weekly` and `followups`.
+    pub waiting_label: String,
+    /// Days after a task gets `waiting_label` until it's due a follow-up.
+    pub followup_days: i64,
+    /// Label given in Todoist to tasks with open blockers, and removed once they're done.
+    pub blocked_label: String,
+    pub smtp: Option<SmtpSettings>,
+    /// Named checklists of tasks created together by `checklist run`.
+    pub checklists: HashMap<String, Vec<Template>>,
+    /// Saved filter views, mapping a name to a filter query.
+    pub filters: HashMap<String, String>,
+    /// Per-project settings by project name, from `[project.<name>]` sections.
+    #[serde(rename = "project")]
+    pub projects: HashMap<String, ProjectDefaults>,
+    /// Rotating snapshots of the cache, from a `[backups]` section; off unless configured.
+    pub backups: Option<BackupSettings>,
+    /// Directory where the tasks of projects archived or deleted in Todoist are saved before
+    /// they leave the cache, `archive` in the working directory by default.
+    pub archive_dir: PathBuf,
+    /// Collaborator activity shown as TUI toasts, from an `[activity]` section; off unless configured.
+    pub activity: Option<ActivitySettings>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            api: ApiVersion::Auto,
+            sync_on_start: true,
+            lazy_projects: false,
+            sync_interval: 300,
+            relative_dates: true,
+            columns: default_columns(),
+            stale_days: 30,
+            waiting_label: "waiting".to_string(),
+            followup_days: 3,
+            blocked_label: "blocked".to_string(),
+            smtp: None,
+            checklists: HashMap::new(),
+            filters: HashMap::new(),
+            projects: HashMap::new(),
+            backups: None,
+            archive_dir: PathBuf::from("archive"),
+            activity: None,
+        }
+    }
+}
+
+/// Fields filled in on tasks added to a project, unless given explicitly, and the project's
+/// priority escalation rules.
+#[derive(Deserialize)]
+pub struct ProjectDefaults {
+    /// Natural-language due date, e.g. "today".
+    pub default_due: Option<String>,
+    #[serde(default)]
+    pub default_labels: Vec<String>,
+    /// Rules raising the priority of overdue tasks after each sync; none unless configured.
+    #[serde(default)]
+    pub escalate: Vec<EscalationRule>,
+}
+
+/// Raises a task to `priority` once it is overdue by `overdue_days`, e.g.
+/// `{ overdue_days = 2, priority = 1 }` for "p1 after two days overdue".
+#[derive(Deserialize)]
+pub struct EscalationRule {
+    pub overdue_days: i64,
+    /// Priority as shown in Todoist, from 1 (p1, urgent) to 4.
+    pub priority: u8,
+}
+
+impl EscalationRule {
+    /// Gets the API priority (4 for p1) the rule raises a task to, if it applies and the task
+    /// doesn't already have that priority or higher.
+    pub fn raise(&self, task: &Task, today: NaiveDate) -> Option<u8> {
+        let due = task.due.as_ref()?.local_date()?;
+        let priority = 5 - self.priority.clamp(1, 4);
+        ((today - due).num_days() >= self.overdue_days && priority > task.priority).then_some(priority)
+    }
+}
+
+impl ProjectDefaults {
+    /// Sets the default due date when the task has none, and adds any missing default labels.
+    pub fn apply(&self, new_task: &mut NewTask) {
+        let has_due = new_task.due_string.is_some()
+            || new_task.due_date.is_some()
+            || new_task.due_datetime.is_some();
+        if !has_due && let Some(due) = &self.default_due {
+            new_task.set_due(due);
+        }
+        for label in &self.default_labels {
+            if !new_task.labels.contains(label) {
+                new_task.labels.push(label.clone());
+            }
+        }
+    }
+}
+
+/// When and where snapshots of the cache are written.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Snapshots kept; the oldest are deleted once there are more.
+    pub keep: usize,
+    pub every: BackupInterval,
+    /// Directory of the snapshots, `backups` in the working directory by default.
+    pub dir: PathBuf,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        BackupSettings {
+            keep: 7,
+            every: BackupInterval::Day,
+            dir: PathBuf::from("backups"),
+        }
+    }
+}
+
+/// How often a successful sync snapshots the cache.
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupInterval {
+    /// After every successful sync.
+    Sync,
+    /// After the first successful sync of each day.
+    Day,
+}
+
+/// Which shared projects' activity is reported, and how often it's checked.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ActivitySettings {
+    /// Names of the shared projects to report; all shared projects when empty.
+    pub projects: Vec<String>,
+    /// Seconds between checks of the activity log.
+    pub interval: u64,
+}
+
+impl Default for ActivitySettings {
+    fn default() -> Self {
+        ActivitySettings {
+            projects: Vec::new(),
+            interval: 60,
+        }
+    }
+}
+
+/// SMTP server used to send email digests.
+#[derive(Deserialize)]
+pub struct SmtpSettings {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    pub username: Option<String>,
+    /// Falls back to the `TODOIST_SMTP_PASSWORD` environment variable when unset.
+    pub password: Option<String>,
+    pub from: String,
+    pub to: String,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+impl Settings {
+    /// Path of the config file: `$TODOIST_CLI_CONFIG`, or `todoist-cli/config.toml` in the user config directory.
+    pub fn path() -> Option<PathBuf> {
+        if let Ok(path) = std::env::var("TODOIST_CLI_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+        Some(dirs::config_dir()?.join("todoist-cli").join("config.toml"))
+    }
+
+    /// Resolves a saved filter view name to its query; anything else is returned unchanged.
+    pub fn resolve_filter<'a>(&'a self, query: &'a str) -> &'a str {
+        self.filters.get(query).map_or(query, String::as_str)
+    }
+
+    /// Gets the new-task defaults for a project, matching its name case-insensitively.
+    pub fn project_defaults(&self, project: &str) -> Option<&ProjectDefaults> {
+        self.projects
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(project))
+            .map(|(_, defaults)| defaults)
+    }
+
+    /// Loads settings from the config file, using defaults when it doesn't exist.
+    pub fn load() -> AppResult<Self> {
+        let Some(path) = Self::path().filter(|p| p.exists()) else {
+            return Ok(Settings::default());
+        };
+        let contents = std::fs::read_to_string(&path)?;
+        toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))
+    }
+}
@@ -1,234 +1,3721 @@
-use crate::api::client::ApiClient;
+use crate::api::client::{self, ApiClient, TaskChanges};
+use crate::api::metrics::Usage;
+use crate::api::sync::{self, MAX_COMMANDS, SyncCommand};
+use crate::config::settings::Settings;
+use crate::controller::columns::Row;
+use crate::controller::followups;
+use crate::controller::rows::RowCache;
+use crate::controller::planner::{self, DayPlanner};
+use crate::controller::quadrants::{self, QuadrantView};
+use crate::controller::trends::{self, ProjectTrend};
+use crate::controller::week::{WeekView, tasks_on};
+use crate::db::archive::{self, ProjectArchive};
+use crate::db::backup;
 use crate::db::cache::Cache;
-use crate::models::task::Task;
+use crate::models::conflict::Conflict;
+use crate::models::event::{self, AuditEntry};
+use crate::models::label::Label;
+use crate::models::project::{self, Project};
+use crate::models::saved_filter::SavedFilter;
+use crate::models::user::Collaborator;
+use crate::models::task::{NewTask, Task, TaskUpdate};
+use crate::utils::browser;
 use crate::utils::error::AppResult;
+use crate::utils::filter::Filter;
+use crate::utils::progress;
+use crate::utils::random;
+use crate::utils::text::{similarity, thousands, unique_prefix_len};
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use futures::stream::{self, StreamExt};
+use indicatif::ProgressBar;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 use ratatui::widgets::ListState;
+use tokio::sync::mpsc;
 
-/// Application mode: Normal (navigation), InsertAdd (adding new task), or InsertEdit (editing task).
+/// Fewest characters of a Todoist ID shown by the CLI.
+pub const SHORT_ID_LEN: usize = 8;
+
+/// Titles at least this similar to an open task's title count as duplicates.
+const DUPLICATE_SIMILARITY: f64 = 0.85;
+
+/// Quiet period after the last mutation before tasks are written to the cache.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Maximum number of API requests in flight during bulk operations.
+const BULK_CONCURRENCY: usize = 5;
+
+/// Frames of the spinner shown while background work runs.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How often the spinner advances.
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the TUI checks for tasks coming due.
+pub const REMINDER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Minutes after its due time that a task still triggers a reminder, so opening the TUI
+/// doesn't replay reminders for everything overdue.
+const REMINDER_GRACE_MINUTES: i64 = 5;
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// How long tasks that first appeared in a sync stay flagged in the list.
+const NEW_ROW_DURATION: Duration = Duration::from_secs(10);
+
+/// Loading state of a project's tasks when projects are loaded lazily.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProjectLoad {
+    Loading,
+    Loaded,
+}
+
+/// Tasks fetched by a background sync.
+pub enum Fetched {
+    /// Today/Upcoming and the loaded projects, with lazy loading.
+    Visible(Vec<Task>),
+    /// Changes since the last sync from the Sync API.
+    Changes(TaskChanges),
+}
+
+/// A finished background fetch waiting to be applied by `App::apply_background`.
+pub enum Background {
+    Sync(Option<AppResult<Fetched>>),
+    Project(String, AppResult<Vec<Task>>),
+}
+
+/// Task changes waiting to be written to the cache.
+#[derive(Default)]
+struct PendingWrites {
+    /// Local IDs of added or changed tasks.
+    changed: HashSet<usize>,
+    /// Local IDs of deleted tasks.
+    removed: HashSet<usize>,
+    /// Rewrite the whole list, e.g. after a project's tasks were replaced.
+    all: bool,
+}
+
+/// Progress toward the daily goal: today's count from Todoist's stats plus completions made since.
+struct DailyGoal {
+    goal: usize,
+    date: NaiveDate,
+    completed: usize,
+}
+
+impl DailyGoal {
+    /// Tasks completed today, starting over when the day changes.
+    fn completed(&self, today: NaiveDate) -> usize {
+        if self.date == today { self.completed } else { 0 }
+    }
+}
+
+/// Where the selection was before the task list changed: the selected task, its position among
+/// the visible tasks, and its row on screen.
+struct SelectionAnchor {
+    todoist_id: String,
+    position: usize,
+    screen_row: usize,
+}
+
+/// Collaborators of a shared project to pick an assignee from, while `Mode::Assign` is on.
+struct AssignPicker {
+    /// Local ID of the task being assigned.
+    task: usize,
+    /// User IDs with the names shown for them; `None` unassigns.
+    choices: Vec<(Option<String>, String)>,
+    selected: usize,
+}
+
+/// Projects to move a task to, while `Mode::Move` is on.
+struct ProjectPicker {
+    /// Local ID of the task being moved.
+    task: usize,
+    /// Project IDs with the names shown for them, indented under their parents.
+    choices: Vec<(String, String)>,
+    selected: usize,
+}
+
+/// Application mode: Normal (navigation), InsertAdd (adding new task), InsertEdit (editing task),
+/// EditDescription (editing a task's notes over several lines), Filter (typing a filter query),
+/// ConfirmDuplicate (deciding about a likely duplicate task), Review (going through stale tasks),
+/// ReviewDue (typing a new due date during a review), Assign (picking who a task is assigned to),
+/// Place (moving a task to a time slot in the day planner), Move (picking a task's project), or
+/// ResolveConflict (choosing between an offline edit and a change made in Todoist).
 #[derive(PartialEq)]
 pub enum Mode {
     Normal,
     InsertAdd,
     InsertEdit,
+    EditDescription,
+    Filter,
+    ConfirmDuplicate,
+    Review,
+    ReviewDue,
+    Assign,
+    Place,
+    Move,
+    ResolveConflict,
+}
+
+/// Layout of the TUI's main pane.
+#[derive(Clone, Copy, PartialEq)]
+pub enum View {
+    List,
+    Week,
+    /// Today's tasks on an hour-by-hour timeline.
+    Day,
+    /// Open tasks in the Eisenhower matrix's four quadrants.
+    Quadrants,
+}
+
+/// A row of the TUI's project sidebar.
+pub enum SidebarRow<'a> {
+    /// Shows every task again.
+    AllTasks,
+    /// Names the group of projects below it; the selection skips it.
+    Heading(&'static str),
+    /// A project with its depth in the tree.
+    Project(usize, &'a Project),
+    /// A favorite label, filtering the list to its tasks.
+    Label(&'a Label),
+    /// A favorite saved filter, filtering the list with its query.
+    Filter(&'a SavedFilter),
+}
+
+impl SidebarRow<'_> {
+    /// The filter query a label or saved filter row applies to the list.
+    pub fn query(&self) -> Option<String> {
+        match self {
+            SidebarRow::Label(label) => Some(format!("@{}", label.name)),
+            SidebarRow::Filter(filter) => Some(filter.query.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// What to do with a stale task during a review.
+pub enum ReviewAction {
+    /// Leave the task as is; it won't come up again until it goes stale anew.
+    Keep,
+    /// Set a new due date from natural language.
+    Reschedule(String),
+    Delete,
+    /// Lower the priority by one step.
+    Deprioritize,
+}
+
+/// How to resolve a new task that looks like a duplicate.
+pub enum DuplicateChoice {
+    /// Select the existing task instead of adding a new one.
+    Jump,
+    AddAnyway,
+    Cancel,
+}
+
+/// How to resolve a task edited both offline and in Todoist.
+pub enum ConflictChoice {
+    /// Send the offline edits over the changes made in Todoist.
+    KeepMine,
+    /// Drop the offline edits and take Todoist's copy.
+    KeepTheirs,
+    /// Decide after the next sync.
+    Later,
 }
 
 /// Application state managing tasks and TUI mode.
 pub struct App {
     tasks: Vec<Task>,
+    projects: Vec<Project>,
+    /// Labels with their colors and favorite flags.
+    labels: Vec<Label>,
+    /// Filters saved in Todoist, for the sidebar's favorites.
+    filters: Vec<SavedFilter>,
+    /// The daily goal, once fetched and when the user has set one.
+    daily_goal: Option<DailyGoal>,
     next_id: usize,
     list_state: ListState,
     mode: Mode,
     pub input_buffer: String,
     api_client: ApiClient,
     cache: Cache,
+    settings: Settings,
+    sync_rx: Option<mpsc::Receiver<AppResult<Fetched>>>,
+    /// Tasks received so far by the background sync.
+    sync_fetched: Arc<AtomicUsize>,
+    /// When the next periodic background sync starts, once enabled for the TUI.
+    next_sync: Option<Instant>,
+    /// When the app started, which drives the spinner animation.
+    started: Instant,
+    /// Start of this session in RFC 3339 (UTC), the key of its saved API stats.
+    session: String,
+    status: Option<String>,
+    dirty_at: Option<Instant>,
+    pending: PendingWrites,
+    project_loads: HashMap<String, ProjectLoad>,
+    project_tx: mpsc::UnboundedSender<(String, AppResult<Vec<Task>>)>,
+    project_rx: mpsc::UnboundedReceiver<(String, AppResult<Vec<Task>>)>,
+    row_cache: RowCache,
+    /// Active TUI filter with the query it was parsed from.
+    filter: Option<(String, Filter)>,
+    /// Title awaiting confirmation and the local ID of the open task it resembles.
+    duplicate: Option<(String, usize)>,
+    view: View,
+    week: WeekView,
+    planner: DayPlanner,
+    quadrants: QuadrantView,
+    /// Local IDs of tasks whose reminders fired and haven't been dismissed, oldest first.
+    reminders: Vec<usize>,
+    /// Whether filtering also searches the completed-task history.
+    include_completed: bool,
+    /// Archived completed tasks matching the active filter, when `include_completed` is set.
+    completed_matches: Vec<Task>,
+    /// Todoist IDs of the stale tasks left to review, next first.
+    review: Vec<String>,
+    /// Show terminal progress bars for syncs and bulk operations; off for the TUI.
+    progress_bars: bool,
+    /// Whether the TUI still shows the first-run welcome screen.
+    welcome: bool,
+    /// Whether the TUI shows the API usage overlay.
+    api_overlay: bool,
+    /// Per-project trends shown by the TUI stats overlay, computed when it's opened.
+    stats_overlay: Option<Vec<ProjectTrend>>,
+    /// Who local changes are logged as in the audit log, e.g. "joel via tui".
+    actor: String,
+    /// Kept open once used, since on X11 copied text is only served while it's alive.
+    clipboard: Option<arboard::Clipboard>,
+    /// Whether the TUI renders for screen readers: no borders, and a status line describing the
+    /// current state.
+    plain_ui: bool,
+    /// ID of the token's user, once fetched.
+    user_id: Option<String>,
+    /// Collaborators of the shared projects fetched so far, by project ID.
+    collaborators: HashMap<String, Vec<Collaborator>>,
+    /// Names of those collaborators by user ID, "me" for the token's user, for the list's
+    /// assignee tags.
+    people: HashMap<String, String>,
+    /// IDs of the activity log events in the last check; `None` before the first.
+    activity_seen: Option<HashSet<String>>,
+    assign_picker: Option<AssignPicker>,
+    /// The project picker, while `Mode::Move` is on.
+    project_picker: Option<ProjectPicker>,
+    /// Project picked in the TUI sidebar; views only show its tasks and its sub-projects' ones.
+    project_view: Option<String>,
+    /// Whether `j`/`k` move the sidebar's selection rather than the list's.
+    sidebar_focused: bool,
+    /// Row of the sidebar's selection, which tells a favorite's two rows apart.
+    sidebar_row: usize,
+    /// Archived projects listed at the bottom of the sidebar, while shown.
+    archived_shown: Option<Vec<Project>>,
+    /// Collaborator activity shown in the TUI's corner, oldest first, with when each goes away.
+    toasts: Vec<(String, Instant)>,
+    /// Local IDs of tasks that first appeared in a sync, flagged in the list until the time given.
+    new_rows: Option<(HashSet<usize>, Instant)>,
+    /// Blocked-by links as (blocked task, blocker) pairs of Todoist IDs, kept until the blocker is done.
+    blockers: Vec<(String, String)>,
+    /// Changes made while Todoist couldn't be reached, waiting in the cache for the next sync.
+    queued: usize,
+    /// When each waiting task started waiting or was last followed up on, by Todoist ID.
+    waiting_since: HashMap<String, DateTime<Utc>>,
+    /// Tasks whose queued edits are held back because they changed in Todoist too.
+    conflicts: Vec<Conflict>,
 }
 
 impl App {
-    /// Initializes the app with API client and cache.
+    /// Initializes the app with API client, cache, and settings.
     pub fn new(token: String) -> AppResult<Self> {
-        let cache = Cache::new()?;
-        let mut tasks = cache.load_tasks()?;
-        if tasks.is_empty() {
-            tasks = vec![
-                Task::new(1, "Buy Milk", false),
-                Task::new(2, "Write Code", false),
-                Task::new(3, "Fix Bugs", false),
-            ];
-        }
+        Self::with_backend(ApiClient::new(token), Cache::new()?)
+    }
+
+    /// Initializes the app against an in-memory sample account and cache, leaving the real
+    /// `tasks.db` untouched.
+    pub fn demo() -> AppResult<Self> {
+        Self::with_backend(ApiClient::demo(), Cache::in_memory()?)
+    }
+
+    fn with_backend(api_client: ApiClient, cache: Cache) -> AppResult<Self> {
+        let settings = Settings::load()?;
+        let row_cache = RowCache::new(settings.columns.clone(), settings.relative_dates);
+        let tasks = cache.load_tasks()?;
+        let projects = cache.load_projects()?;
+        let labels = cache.load_labels()?;
+        let filters = cache.load_filters()?;
+        let daily_goal = cache
+            .load_daily_goal()?
+            .filter(|(goal, _, _)| *goal > 0)
+            .map(|(goal, date, completed)| DailyGoal { goal, date, completed });
+        let blockers = cache.load_blockers()?;
+        let queued = cache.count_operations()?;
+        let conflicts = cache.load_conflicts()?;
+        let waiting_since = cache
+            .waiting_since()?
+            .into_iter()
+            .filter_map(|(id, since)| Some((id, DateTime::parse_from_rfc3339(&since).ok()?.with_timezone(&Utc))))
+            .collect();
+        let welcome = cache.is_new();
         let mut list_state = ListState::default();
         if !tasks.is_empty() {
             list_state.select(Some(0));
         }
         let next_id = tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+        let (project_tx, project_rx) = mpsc::unbounded_channel();
         Ok(App {
             tasks,
+            projects,
+            labels,
+            filters,
+            daily_goal,
             next_id,
             list_state,
             mode: Mode::Normal,
             input_buffer: String::new(),
-            api_client: ApiClient::new(token),
+            api_client: api_client.with_version(settings.api),
             cache,
+            settings,
+            sync_rx: None,
+            sync_fetched: Arc::new(AtomicUsize::new(0)),
+            next_sync: None,
+            started: Instant::now(),
+            session: Utc::now().to_rfc3339(),
+            status: None,
+            dirty_at: None,
+            pending: PendingWrites::default(),
+            project_loads: HashMap::new(),
+            project_tx,
+            project_rx,
+            row_cache,
+            filter: None,
+            duplicate: None,
+            view: View::List,
+            week: WeekView::new(Local::now().date_naive()),
+            planner: DayPlanner::new(Local::now().date_naive()),
+            quadrants: QuadrantView::default(),
+            reminders: Vec::new(),
+            include_completed: false,
+            completed_matches: Vec::new(),
+            review: Vec::new(),
+            progress_bars: false,
+            welcome,
+            api_overlay: false,
+            stats_overlay: None,
+            actor: format!("{} via tui", event::local_user()),
+            clipboard: None,
+            plain_ui: false,
+            user_id: None,
+            collaborators: HashMap::new(),
+            people: HashMap::new(),
+            activity_seen: None,
+            assign_picker: None,
+            project_picker: None,
+            project_view: None,
+            sidebar_focused: false,
+            sidebar_row: 0,
+            archived_shown: None,
+            toasts: Vec::new(),
+            new_rows: None,
+            blockers,
+            queued,
+            waiting_since,
+            conflicts,
         })
     }
 
-    /// Syncs tasks with the Todoist API and updates cache.
-    pub async fn sync_tasks(&mut self) -> AppResult<()> {
-        let api_tasks = self.api_client.fetch_tasks().await?;
-        let mut tasks = Vec::new();
-        for (i, mut task) in api_tasks.into_iter().enumerate() {
-            task.id = self.next_id + i;
-            tasks.push(task);
-        }
-        self.next_id += tasks.len();
-        self.tasks = tasks;
-        self.cache.save_tasks(&self.tasks)?;
-        if !self.tasks.is_empty() && self.list_state.selected().is_none() {
-            self.list_state.select(Some(0));
+    /// Shows progress bars on stderr for syncs and bulk operations run from the command line.
+    pub fn enable_progress_bars(&mut self) {
+        self.progress_bars = true;
+    }
+
+    /// Logs changes as made from `surface`, e.g. "cli", instead of the TUI.
+    pub fn set_surface(&mut self, surface: &str) {
+        self.actor = format!("{} via {}", event::local_user(), surface);
+    }
+
+    /// Renders the TUI for screen readers.
+    pub fn enable_plain_ui(&mut self) {
+        self.plain_ui = true;
+    }
+
+    /// Returns true if the TUI renders for screen readers.
+    pub fn plain_ui(&self) -> bool {
+        self.plain_ui
+    }
+
+    /// Logs every API request and response, with the token redacted, to the file at `path`.
+    pub fn enable_http_log(&mut self, path: &Path) -> AppResult<()> {
+        self.api_client = self.api_client.clone().with_http_log(path)?;
+        Ok(())
+    }
+
+    /// Creates a progress bar for `len` steps, hidden unless progress bars are enabled.
+    fn progress_bar(&self, len: usize, message: &str) -> ProgressBar {
+        if self.progress_bars {
+            progress::bar(len as u64, message)
+        } else {
+            ProgressBar::hidden()
         }
+    }
+
+    /// Syncs tasks with the Todoist API and updates cache. Unlike `incremental_sync` this always
+    /// fetches every task, for `md-sync`, which must never write a file from a partial list.
+    pub async fn sync_tasks(&mut self) -> AppResult<()> {
+        self.send_queued().await?;
+        let api_tasks = if self.progress_bars {
+            let fetched = Arc::new(AtomicUsize::new(0));
+            let api_client = self.api_client.with_progress(fetched.clone());
+            let bar = progress::counter("Syncing", "tasks");
+            let fetch = api_client.fetch_tasks();
+            tokio::pin!(fetch);
+            // The client only counts tasks, so copy the count over while pages arrive
+            let result = loop {
+                tokio::select! {
+                    result = &mut fetch => break result,
+                    _ = tokio::time::sleep(SPINNER_INTERVAL) => {
+                        bar.set_position(fetched.load(Ordering::Relaxed) as u64)
+                    }
+                }
+            };
+            bar.finish_and_clear();
+            result?
+        } else {
+            self.api_client.fetch_tasks().await?
+        };
+        self.apply_synced_tasks(api_tasks, true)?;
+        self.after_sync(true).await;
         Ok(())
     }
 
-    /// Adds a new task locally and to Todoist.
-    // todo: for add and update, allow for labels in TUI and API
-    pub async fn add_task(&mut self, title: &str) -> AppResult<()> {
-        if !title.trim().is_empty() {
-            let mut task = self.api_client.add_task(title).await?;
-            task.id = self.next_id;
-            self.tasks.push(task);
-            self.next_id += 1;
-            self.list_state.select(Some(self.tasks.len() - 1));
-            self.cache.save_tasks(&self.tasks)?;
+    /// Syncs only what changed in Todoist since the last sync, through the Sync API and the
+    /// token kept in the cache. The first sync, or one Todoist asks to start over, fetches every
+    /// task. With lazy loading this syncs the visible tasks instead.
+    pub async fn incremental_sync(&mut self) -> AppResult<()> {
+        if self.settings.lazy_projects {
+            return self.sync_visible_tasks().await;
         }
+        self.send_queued().await?;
+        let sync_token = self.cache.load_sync_token()?;
+        let changes = self
+            .api_client
+            .fetch_task_changes(sync_token.as_deref().unwrap_or("*"))
+            .await?;
+        self.apply_task_changes(changes)?;
+        self.after_sync(true).await;
         Ok(())
     }
 
-    /// Updates a task locally and in Todoist.
-    pub async fn update_task(&mut self, id: usize, title: &str, checked: bool) -> AppResult<()> {
-        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-            if !title.trim().is_empty() {
-                self.api_client
-                    .update_task(&task.todoist_id, title, checked)
-                    .await?;
-                task.title = title.to_string();
-                task.checked = checked; // Update local checked status
-                self.cache.save_tasks(&self.tasks)?;
+    /// Syncs only Today/Upcoming and already loaded projects, for lazy loading.
+    async fn sync_visible_tasks(&mut self) -> AppResult<()> {
+        self.send_queued().await?;
+        let api_tasks = self
+            .api_client
+            .fetch_visible_tasks(&self.loaded_project_ids())
+            .await?;
+        self.apply_synced_tasks(api_tasks, false)?;
+        self.after_sync(false).await;
+        Ok(())
+    }
+
+    /// Starts fetching tasks on a background task unless a sync is already running; results are
+    /// applied by `apply_background`.
+    pub fn start_background_sync(&mut self) {
+        if self.sync_rx.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel(1);
+        self.sync_fetched = Arc::new(AtomicUsize::new(0));
+        // Any sync restarts the wait for the next periodic one
+        if self.next_sync.is_some() {
+            self.next_sync = Some(Instant::now() + Duration::from_secs(self.settings.sync_interval));
+        }
+        let api_client = self.api_client.with_progress(self.sync_fetched.clone());
+        let lazy_projects = self.settings.lazy_projects.then(|| self.loaded_project_ids());
+        // Without a readable token the sync starts over, which is slower but complete
+        let sync_token = self.cache.load_sync_token().ok().flatten();
+        tokio::spawn(async move {
+            let result = match lazy_projects {
+                Some(project_ids) => api_client.fetch_visible_tasks(&project_ids).await.map(Fetched::Visible),
+                None => api_client
+                    .fetch_task_changes(sync_token.as_deref().unwrap_or("*"))
+                    .await
+                    .map(Fetched::Changes),
+            };
+            let _ = tx.send(result).await;
+        });
+        self.sync_rx = Some(rx);
+    }
+
+    /// Syncs in the background every `sync_interval` seconds from now on, so changes made in
+    /// Todoist elsewhere show up while the TUI is open. A `sync_interval` of 0 leaves this off.
+    pub fn enable_periodic_sync(&mut self) {
+        if self.settings.sync_interval > 0 {
+            self.next_sync = Some(Instant::now() + Duration::from_secs(self.settings.sync_interval));
+        }
+    }
+
+    /// Gets when the next periodic sync is due, unless a sync is already running.
+    pub fn sync_deadline(&self) -> Option<Instant> {
+        self.next_sync.filter(|_| self.sync_rx.is_none())
+    }
+
+    /// Starts loading a project's tasks in the background unless it is already loaded or loading.
+    pub fn request_project_load(&mut self, project_id: &str) {
+        if !self.settings.lazy_projects || self.project_loads.contains_key(project_id) {
+            return;
+        }
+        self.project_loads
+            .insert(project_id.to_string(), ProjectLoad::Loading);
+        let api_client = self.api_client.clone();
+        let tx = self.project_tx.clone();
+        let project_id = project_id.to_string();
+        tokio::spawn(async move {
+            let result = api_client.fetch_project_tasks(&project_id).await;
+            let _ = tx.send((project_id, result));
+        });
+    }
+
+    /// Gets the lazy loading state of a project; `None` means it hasn't been requested.
+    pub fn project_load(&self, project_id: &str) -> Option<ProjectLoad> {
+        self.project_loads.get(project_id).copied()
+    }
+
+    /// Describes running background work with an animated spinner, e.g. "⠙ Syncing 1,240 tasks…".
+    pub fn progress(&self) -> Option<String> {
+        let mut operations = Vec::new();
+        if self.sync_rx.is_some() {
+            match self.sync_fetched.load(Ordering::Relaxed) {
+                0 => operations.push("Syncing tasks…".to_string()),
+                fetched => operations.push(format!("Syncing {} tasks…", thousands(fetched))),
+            }
+        }
+        let loading = self
+            .project_loads
+            .iter()
+            .filter(|(_, load)| **load == ProjectLoad::Loading)
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        match loading.as_slice() {
+            [] => {}
+            [id] => {
+                let name = self.projects.iter().find(|p| &&p.id == id).map(|p| p.name.as_str());
+                operations.push(format!("Loading {}…", name.unwrap_or("project")));
+            }
+            many => operations.push(format!("Loading {} projects…", many.len())),
+        }
+        if operations.is_empty() {
+            return None;
+        }
+        let frame = self.started.elapsed().as_millis() / SPINNER_INTERVAL.as_millis();
+        Some(format!("{} {}", SPINNER[frame as usize % SPINNER.len()], operations.join(", ")))
+    }
+
+    fn loaded_project_ids(&self) -> Vec<String> {
+        self.project_loads
+            .iter()
+            .filter(|(_, load)| **load == ProjectLoad::Loaded)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Waits for a background sync or project load to finish. Only receives, so it is safe to
+    /// race against input and drop; `apply_background` does the rest.
+    pub async fn wait_for_background(&mut self) -> Background {
+        let sync_rx = &mut self.sync_rx;
+        tokio::select! {
+            result = async {
+                match sync_rx {
+                    Some(rx) => rx.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => Background::Sync(result),
+            Some((project_id, result)) = self.project_rx.recv() => Background::Project(project_id, result),
+        }
+    }
+
+    /// Applies a finished background sync or project load.
+    pub async fn apply_background(&mut self, update: Background) -> AppResult<()> {
+        match update {
+            // The sync task ended without sending a result
+            Background::Sync(None) => {
+                self.sync_rx = None;
+            }
+            // Todoist is reachable again, but the tasks were fetched without the queued changes;
+            // those held back by conflicts wait for the user
+            Background::Sync(Some(Ok(_))) if self.queued > self.held_changes()? => {
+                self.sync_rx = None;
+                match self.send_queued().await {
+                    Ok(()) => self.start_background_sync(),
+                    Err(e) => self.status = Some(format!("Sync failed: {}", e)),
+                }
+            }
+            Background::Sync(Some(Ok(fetched))) => {
+                self.sync_rx = None;
+                // With lazy loading the background sync only fetches part of the account
+                let complete = match fetched {
+                    Fetched::Visible(api_tasks) => {
+                        self.apply_synced_tasks(api_tasks, false)?;
+                        false
+                    }
+                    Fetched::Changes(changes) => {
+                        self.apply_task_changes(changes)?;
+                        true
+                    }
+                };
+                self.after_sync(complete).await;
+            }
+            Background::Sync(Some(Err(e))) if client::is_offline(&e) => {
+                self.sync_rx = None;
+                self.report_offline();
+            }
+            Background::Sync(Some(Err(e))) => {
+                self.sync_rx = None;
+                self.status = Some(format!("Sync failed: {}", e));
+            }
+            Background::Project(project_id, Ok(mut project_tasks)) => {
+                // Replace the project's tasks with the freshly loaded ones
+                self.assign_local_ids(&mut project_tasks);
+                let anchor = self.selection_anchor();
+                self.tasks.retain(|t| t.project_id != project_id);
+                self.tasks.extend(project_tasks);
+                self.project_loads.insert(project_id, ProjectLoad::Loaded);
+                self.mark_dirty();
+                self.restore_selection(anchor);
+            }
+            Background::Project(project_id, Err(e)) => {
+                self.project_loads.remove(&project_id);
+                self.status = Some(format!("Loading project failed: {}", e));
             }
         }
         Ok(())
     }
 
-    /// Deletes a task locally and in Todoist.
-    pub async fn delete_task(&mut self, id: usize) -> AppResult<()> {
-        if let Some(index) = self.tasks.iter().position(|t| t.id == id) {
-            let task = &self.tasks[index];
-            self.api_client.delete_task(&task.todoist_id).await?;
-            self.tasks.remove(index);
-            self.cache.save_tasks(&self.tasks)?;
-            if self.tasks.is_empty() {
-                self.list_state.select(None);
-            } else if index <= self.list_state.selected().unwrap_or(0) {
-                let new_index = self.list_state.selected().unwrap_or(1).saturating_sub(1);
-                self.list_state.select(Some(new_index));
+    /// Applies tasks from the Sync API: every task on a full sync, else the changes merged into
+    /// the current ones. Changed tasks keep their location unless its reminder changed too.
+    /// The token is saved last, so a failed write refetches the same changes next time.
+    fn apply_task_changes(&mut self, changes: TaskChanges) -> AppResult<()> {
+        let TaskChanges { sync_token, full_sync, tasks: changed, removed, mut locations, projects } = changes;
+        // A full sync leaves out cached projects archived or deleted since; a delta flags them.
+        // Tasks added offline to the Inbox have no project ID yet, so they never count
+        let gone = match &projects {
+            Some(sent) if full_sync => self
+                .projects
+                .iter()
+                .map(|p| &p.id)
+                .filter(|id| !sent.iter().any(|(sent_id, active)| sent_id == *id && *active))
+                .cloned()
+                .collect::<HashSet<_>>(),
+            Some(sent) => sent.iter().filter(|(_, active)| !active).map(|(id, _)| id.clone()).collect(),
+            None => HashSet::new(),
+        };
+        let gone = gone
+            .into_iter()
+            .filter(|id| !id.is_empty() && !sync::is_temp_id(id))
+            .collect::<HashSet<_>>();
+        if !gone.is_empty() {
+            self.archive_projects(&gone)?;
+        }
+        let tasks = if full_sync {
+            changed
+        } else {
+            let removed = removed.into_iter().collect::<HashSet<_>>();
+            let mut tasks = self
+                .tasks
+                .iter()
+                .filter(|t| !removed.contains(&t.todoist_id))
+                .cloned()
+                .collect::<Vec<_>>();
+            for mut task in changed {
+                match tasks.iter_mut().find(|t| t.todoist_id == task.todoist_id) {
+                    Some(current) => {
+                        task.location = current.location.take();
+                        *current = task;
+                    }
+                    None => tasks.push(task),
+                }
+            }
+            for task in &mut tasks {
+                if let Some(location) = locations.remove(&task.todoist_id) {
+                    task.location = location;
+                }
             }
+            tasks
+        };
+        self.apply_synced_tasks(tasks, true)?;
+        self.cache.save_sync_token(&sync_token)
+    }
+
+    /// Saves the cached tasks of archived or deleted projects to `archive_dir`, one
+    /// file per project, then drops the projects and their tasks. The status names the files.
+    fn archive_projects(&mut self, project_ids: &HashSet<String>) -> AppResult<()> {
+        let mut saved = Vec::new();
+        for project_id in project_ids {
+            let tasks = self
+                .tasks
+                .iter()
+                .filter(|t| &t.project_id == project_id)
+                .cloned()
+                .collect::<Vec<_>>();
+            // The demo's in-memory account leaves no files behind
+            if tasks.is_empty() || self.cache.is_in_memory() {
+                continue;
+            }
+            let project = self
+                .projects
+                .iter()
+                .find(|p| &p.id == project_id)
+                .map_or(project_id.clone(), |p| p.name.clone());
+            let archive = ProjectArchive {
+                project_id: project_id.clone(),
+                project,
+                archived_at: Utc::now().to_rfc3339(),
+                tasks,
+            };
+            saved.push((archive.project.clone(), archive::save(&self.settings.archive_dir, &archive)?));
         }
+        self.tasks.retain(|t| !project_ids.contains(&t.project_id));
+        let before = self.projects.len();
+        self.projects.retain(|p| !project_ids.contains(&p.id));
+        if self.projects.len() != before {
+            self.cache.save_projects(&self.projects)?;
+        }
+        self.status = match saved.as_slice() {
+            [] => return Ok(()),
+            [(project, path)] => Some(format!("Saved the tasks of {} to {}", project, path.display())),
+            many => Some(format!(
+                "Saved the tasks of {} projects to {}",
+                many.len(),
+                self.settings.archive_dir.display()
+            )),
+        };
         Ok(())
     }
 
-    /// Closes a task locally and in Todoist.
-    // todo: Rename to close_task.
-    // todo: create a reopen_task method.
-    pub async fn toggle_task(&mut self) -> AppResult<()> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(task) = self.tasks.get_mut(i) {
-                let new_checked = !task.checked;
-                task.checked = new_checked;
-                self.api_client
-                    .close_task(&task.todoist_id)
-                    .await?; // Pass new_checked
-                self.cache.save_tasks(&self.tasks)?;
+    /// Runs what follows every sync: priority escalation and releasing tasks whose blockers are
+    /// done. `complete` as for `apply_synced_tasks`.
+    async fn after_sync(&mut self, complete: bool) {
+        self.escalate_priorities().await;
+        self.release_unblocked(complete).await;
+        if let Err(e) = self.track_waiting(complete) {
+            self.status = Some(format!("Tracking follow-ups failed: {}", e));
+        }
+    }
+
+    /// Replaces the task list with freshly fetched tasks and updates cache. When `complete`,
+    /// i.e. the whole account was fetched, the changes since the last sync are stored for
+    /// `changes`; a first sync has nothing to compare with. A partial sync also drops the sync
+    /// token, since the Sync API's changes can't be merged into part of the account.
+    fn apply_synced_tasks(&mut self, mut tasks: Vec<Task>, complete: bool) -> AppResult<()> {
+        let had_tasks = !self.tasks.is_empty();
+        let appeared = self.assign_local_ids(&mut tasks);
+        let anchor = self.selection_anchor();
+        if complete && !self.tasks.is_empty() {
+            let changes = event::sync_diff(&self.tasks, &tasks)
+                .into_iter()
+                .filter_map(|(before, after)| AuditEntry::remote(before, after))
+                .collect::<Vec<_>>();
+            if !changes.is_empty() {
+                self.cache.save_remote_changes(&changes)?;
             }
         }
+        self.tasks = tasks;
+        self.row_cache.clear();
+        if had_tasks && !appeared.is_empty() {
+            let mut flagged = self.new_rows.take().map(|(ids, _)| ids).unwrap_or_default();
+            flagged.retain(|id| self.tasks.iter().any(|t| t.id == *id));
+            flagged.extend(appeared);
+            self.new_rows = Some((flagged, Instant::now() + NEW_ROW_DURATION));
+        }
+        self.cache.save_tasks(&self.tasks)?;
+        if !complete {
+            self.cache.clear_sync_token()?;
+        }
+        self.dirty_at = None;
+        self.pending = PendingWrites::default();
+        // A failed snapshot shouldn't fail the sync it follows
+        if let Some(backups) = &self.settings.backups
+            && !self.cache.is_in_memory()
+            && let Err(e) = backup::snapshot(&self.cache, backups, false)
+        {
+            self.status = Some(format!("Backup failed: {}", e));
+        }
+        self.restore_selection(anchor);
         Ok(())
     }
 
-    /// Moves selection to the next task.
-    pub fn next(&mut self) {
-        if self.tasks.is_empty() {
-            self.list_state.select(None);
-            return;
+    /// Gives fetched tasks the local IDs of the tasks they replace, so cached rows, reminders and
+    /// the selection follow them, and new IDs to the rest, which are returned.
+    fn assign_local_ids(&mut self, fetched: &mut [Task]) -> HashSet<usize> {
+        let mut known = self
+            .tasks
+            .iter()
+            .filter(|t| !t.todoist_id.is_empty())
+            .map(|t| (t.todoist_id.as_str(), t.id))
+            .collect::<HashMap<_, _>>();
+        let mut appeared = HashSet::new();
+        for task in fetched {
+            task.id = match known.remove(task.todoist_id.as_str()) {
+                Some(id) => id,
+                None => {
+                    appeared.insert(self.next_id);
+                    self.next_id += 1;
+                    self.next_id - 1
+                }
+            };
         }
-        let i = match self.list_state.selected() {
-            Some(i) => (i + 1) % self.tasks.len(),
-            None => 0,
+        appeared
+    }
+
+    /// Notes the selected task and where it is, for keeping it selected as the list changes.
+    fn selection_anchor(&self) -> Option<SelectionAnchor> {
+        let task = self.selected_task()?;
+        let (position, screen_row) = match self.view {
+            View::List => {
+                let selected = self.list_state.selected()?;
+                (selected, selected.saturating_sub(self.list_state.offset()))
+            }
+            View::Week => (self.week.row(), self.week.row()),
+            View::Day => (self.planner.row(), self.planner.row()),
+            View::Quadrants => (self.quadrants.row(), self.quadrants.row()),
         };
-        self.list_state.select(Some(i));
+        Some(SelectionAnchor { todoist_id: task.todoist_id.clone(), position, screen_row })
     }
 
-    /// Moves selection to the previous task.
-    pub fn previous(&mut self) {
-        if self.tasks.is_empty() {
+    /// Selects the anchored task again at the same row on screen, or the task now in its place
+    /// when it's gone, instead of whatever moved under the cursor.
+    fn restore_selection(&mut self, anchor: Option<SelectionAnchor>) {
+        if self.view != View::List {
+            let (day, current) = match self.view {
+                View::Week => (self.week_days().swap_remove(self.week.day()).1, self.week.row()),
+                View::Quadrants => {
+                    let quadrant = self.quadrant_tasks().into_iter().nth(self.quadrants.quadrant());
+                    (quadrant.unwrap_or_default(), self.quadrants.row())
+                }
+                _ => (self.planner_tasks(), self.planner.row()),
+            };
+            let day = day.iter().map(|t| t.todoist_id.clone()).collect::<Vec<_>>();
+            let row = match &anchor {
+                Some(anchor) => day.iter().position(|id| *id == anchor.todoist_id).unwrap_or(anchor.position),
+                None => current,
+            };
+            let row = row.min(day.len().saturating_sub(1));
+            match self.view {
+                View::Week => self.week.select_row(row),
+                View::Quadrants => self.quadrants.select_row(row),
+                _ => self.planner.select_row(row),
+            }
+            return;
+        }
+        let visible = self.visible_indices();
+        if visible.is_empty() {
             self.list_state.select(None);
             return;
         }
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.tasks.len() - 1
-                } else {
-                    i - 1
-                }
+        let Some(anchor) = anchor else {
+            if self.list_state.selected().is_none_or(|i| i >= visible.len()) {
+                self.list_state.select(Some(0));
             }
-            None => 0,
+            return;
         };
-        self.list_state.select(Some(i));
+        match visible.iter().position(|&i| self.tasks[i].todoist_id == anchor.todoist_id) {
+            Some(position) => {
+                self.list_state.select(Some(position));
+                *self.list_state.offset_mut() = position.saturating_sub(anchor.screen_row);
+            }
+            None => self.list_state.select(Some(anchor.position.min(visible.len() - 1))),
+        }
     }
 
-    /// Enters Insert mode for adding a new task.
-    pub fn enter_insert_add_mode(&mut self) {
-        self.input_buffer.clear();
-        self.mode = Mode::InsertAdd;
+    /// Returns true if the task first appeared in a recent sync and is still flagged as new.
+    pub fn is_new(&self, id: usize) -> bool {
+        self.new_rows.as_ref().is_some_and(|(ids, _)| ids.contains(&id))
     }
 
-    /// Enters Insert mode for editing the selected task.
-    pub fn enter_insert_edit_mode(&mut self) {
-        self.input_buffer.clear();
-        if let Some(i) = self.list_state.selected() {
-            if let Some(task) = self.tasks.get(i) {
-                self.input_buffer = task.title.clone();
-            }
+    /// Raises the priority of overdue tasks by the `escalate` rules of their projects and
+    /// summarizes the changes, or a failure, in the status.
+    async fn escalate_priorities(&mut self) {
+        if self.settings.projects.values().all(|p| p.escalate.is_empty()) {
+            return;
+        }
+        match self.apply_escalation_rules().await {
+            Ok(raised) if raised.is_empty() => {}
+            Ok(raised) => self.status = Some(format!("Raised priority: {}", raised.join(", "))),
+            Err(e) => self.status = Some(format!("Priority escalation failed: {}", e)),
         }
-        self.mode = Mode::InsertEdit;
     }
 
-    /// Exits Insert mode, saving the input as a new or updated task.
-    pub async fn exit_insert_mode(&mut self) -> AppResult<()> {
-        let input = self.input_buffer.clone();
-        if !input.trim().is_empty() {
-            match self.mode {
-                Mode::InsertAdd => {
-                    self.add_task(&input).await?;
-                }
-                Mode::InsertEdit => {
-                    if let Some(i) = self.list_state.selected() {
-                        if let Some(task) = self.tasks.get(i) {
-                            self.update_task(task.id, &input, task.checked).await?;
-                        } else {
-                            self.add_task(&input).await?;
-                        }
-                    } else {
-                        self.add_task(&input).await?;
-                    }
-                }
-                Mode::Normal => {}
+    /// Applies the escalation rules through the API, returning e.g. "Pay rent → p1" for each raised task.
+    async fn apply_escalation_rules(&mut self) -> AppResult<Vec<String>> {
+        // Rules are keyed by project name
+        if self.projects.is_empty() {
+            self.sync_projects().await?;
+        }
+        let today = Local::now().date_naive();
+        let mut changes = Vec::new();
+        for task in self.tasks.iter().filter(|t| !t.is_done()) {
+            let Some(project) = self.projects.iter().find(|p| p.id == task.project_id) else {
+                continue;
+            };
+            let Some(defaults) = self.settings.project_defaults(&project.name) else {
+                continue;
+            };
+            if let Some(priority) = defaults.escalate.iter().filter_map(|r| r.raise(task, today)).max() {
+                changes.push((task.todoist_id.clone(), priority));
             }
         }
-        self.mode = Mode::Normal;
-        self.input_buffer.clear();
+        let mut raised = Vec::new();
+        for (todoist_id, priority) in changes {
+            let updated = self.api_client.set_task_priority(&todoist_id, priority).await?;
+            self.replace_task_fields(&updated);
+            raised.push(format!("{} → p{}", updated.title, 5 - priority));
+        }
+        if !raised.is_empty() {
+            self.row_cache.clear();
+        }
+        Ok(raised)
+    }
+
+    /// Fetches projects from the Todoist API.
+    pub async fn sync_projects(&mut self) -> AppResult<()> {
+        self.projects = self.api_client.fetch_projects().await?;
+        self.cache.save_projects(&self.projects)?;
+        self.row_cache.clear();
         Ok(())
     }
 
-    /// Handles input in Insert mode.
-    pub fn handle_input(&mut self, c: char) {
-        self.input_buffer.push(c);
+    /// Fetches labels from the Todoist API and caches them, so label chips can be drawn in their
+    /// colors and favorite labels listed in the sidebar.
+    pub async fn sync_labels(&mut self) -> AppResult<()> {
+        self.labels = self.api_client.fetch_labels().await?;
+        self.cache.save_labels(&self.labels)?;
+        self.row_cache.clear();
+        Ok(())
     }
 
-    /// Handles backspace in Insert mode.
-    pub fn handle_backspace(&mut self) {
-        self.input_buffer.pop();
+    /// Fetches the filters saved in Todoist and caches them, for the sidebar's favorites.
+    pub async fn sync_filters(&mut self) -> AppResult<()> {
+        self.filters = self.api_client.fetch_filters().await?;
+        self.cache.save_filters(&self.filters)?;
+        Ok(())
     }
 
-    /// Gets the current mode.
-    pub fn mode(&self) -> &Mode {
-        &self.mode
+    /// Fetches the daily goal and today's completions from Todoist's productivity stats, and
+    /// caches them.
+    pub async fn sync_daily_goal(&mut self) -> AppResult<()> {
+        let stats = self.api_client.fetch_stats().await?;
+        let today = Local::now().date_naive();
+        let completed = stats.completed_on(today);
+        self.cache.save_daily_goal(stats.goals.daily_goal, today, completed)?;
+        self.daily_goal = (stats.goals.daily_goal > 0).then_some(DailyGoal {
+            goal: stats.goals.daily_goal,
+            date: today,
+            completed,
+        });
+        Ok(())
     }
 
-    /// Gets the task list.
-    pub fn tasks(&self) -> &Vec<Task> {
-        &self.tasks
+    /// Tasks completed today and the daily goal, when a goal is set.
+    pub fn daily_progress(&self) -> Option<(usize, usize)> {
+        let goal = self.daily_goal.as_ref()?;
+        Some((goal.completed(Local::now().date_naive()), goal.goal))
     }
 
-    /// Gets the mutable list state.
-    pub fn list_state(&mut self) -> &mut ListState {
-        &mut self.list_state
+    /// Counts a task completed (or reopened) here toward today's goal, congratulating in the
+    /// status when it's reached.
+    fn count_completion(&mut self, completed: bool) {
+        let Some(goal) = &mut self.daily_goal else {
+            return;
+        };
+        let today = Local::now().date_naive();
+        goal.completed = goal.completed(today);
+        goal.date = today;
+        if !completed {
+            goal.completed = goal.completed.saturating_sub(1);
+            return;
+        }
+        goal.completed += 1;
+        if goal.completed == goal.goal {
+            self.status = Some(format!("Daily goal reached: {} tasks done today!", goal.goal));
+        }
     }
-}
\ No newline at end of file
+
+    /// Fetches archived projects from the Todoist API.
+    pub async fn archived_projects(&self) -> AppResult<Vec<Project>> {
+        self.api_client.fetch_archived_projects().await
+    }
+
+    /// Archives or unarchives a project by name. Archiving also drops the project's tasks
+    /// locally, since Todoist archives them along with it.
+    pub async fn set_project_archived(&mut self, name: &str, archived: bool) -> AppResult<Project> {
+        let candidates = if archived {
+            self.sync_projects().await?;
+            self.projects.clone()
+        } else {
+            self.archived_projects().await?
+        };
+        let project = candidates
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let state = if archived { "active" } else { "archived" };
+                anyhow::anyhow!("No {} project named '{}'", state, name)
+            })?;
+        let updated = self
+            .api_client
+            .set_project_archived(&project.id, archived)
+            .await?;
+        if archived {
+            self.archive_projects(&HashSet::from([updated.id.clone()]))?;
+            self.row_cache.clear();
+            self.mark_dirty();
+        } else {
+            self.projects.push(updated.clone());
+        }
+        Ok(updated)
+    }
+
+    /// Adds a project to or removes it from the favorites by name.
+    pub async fn set_project_favorite(&mut self, name: &str, favorite: bool) -> AppResult<Project> {
+        self.sync_projects().await?;
+        let project = self
+            .projects
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow::anyhow!("No project named '{}'", name))?;
+        let updated = self
+            .api_client
+            .set_project_favorite(&project.id, favorite)
+            .await?;
+        *project = updated.clone();
+        Ok(updated)
+    }
+
+    /// Finds a project's ID by name or ID. Projects cached by an earlier run may be out of date,
+    /// so they're fetched again before giving up.
+    pub async fn find_project(&mut self, name_or_id: &str) -> AppResult<Option<String>> {
+        if project::find(&self.projects, name_or_id).is_none() {
+            self.sync_projects().await?;
+        }
+        Ok(project::find(&self.projects, name_or_id).map(|p| p.id.clone()))
+    }
+
+    /// Finds a project by name or ID, creating one with that name in Todoist if it doesn't exist.
+    pub async fn resolve_project(&mut self, name: &str) -> AppResult<String> {
+        if let Some(id) = self.find_project(name).await? {
+            return Ok(id);
+        }
+        let project = self.api_client.add_project(name, None).await?;
+        let id = project.id.clone();
+        self.projects.push(project);
+        Ok(id)
+    }
+
+    /// Creates a project in Todoist, as a sub-project of the project named `parent` if given.
+    pub async fn add_project(&mut self, name: &str, parent: Option<&str>) -> AppResult<Project> {
+        self.sync_projects().await?;
+        let parent_id = match parent {
+            Some(parent) => Some(
+                self.projects
+                    .iter()
+                    .find(|p| p.name.eq_ignore_ascii_case(parent))
+                    .map(|p| p.id.clone())
+                    .ok_or_else(|| anyhow::anyhow!("No project named '{}'", parent))?,
+            ),
+            None => None,
+        };
+        let project = self.api_client.add_project(name, parent_id.as_deref()).await?;
+        self.projects.push(project.clone());
+        Ok(project)
+    }
+
+    /// Fetches tasks completed within the given time range from Todoist.
+    pub async fn completed_tasks(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> AppResult<Vec<Task>> {
+        self.api_client
+            .fetch_completed_tasks(&since.to_rfc3339(), &until.to_rfc3339())
+            .await
+    }
+
+    /// Fetches the tasks completed since `since` into the completed-task history, where
+    /// filters, trends and `reopen` find them.
+    pub async fn sync_completed_history(&mut self, since: DateTime<Utc>) -> AppResult<()> {
+        let completed = self.completed_tasks(since, Utc::now()).await?;
+        self.cache.archive_tasks(&completed)
+    }
+
+    /// Adds a new task locally and to Todoist.
+    // todo: for add and update, allow for labels in TUI and API
+    pub async fn add_task(&mut self, title: &str) -> AppResult<()> {
+        let mut new_task = NewTask::new(title);
+        // Tasks added from the week view or day planner land on the shown day
+        if self.view == View::Week {
+            new_task.due_date = Some(self.week.selected_date().format("%Y-%m-%d").to_string());
+        } else if self.view == View::Day {
+            new_task.due_date = Some(self.planner.date().format("%Y-%m-%d").to_string());
+        }
+        self.add_to_project(new_task, None).await?;
+        Ok(())
+    }
+
+    /// Creates a task in the named project (the Inbox when `None`), applying that project's configured defaults.
+    pub async fn add_to_project(&mut self, mut new_task: NewTask, project: Option<&str>) -> AppResult<Option<Task>> {
+        if let Some(project) = project {
+            if self.projects.is_empty() {
+                self.sync_projects().await?;
+            }
+            new_task.project_id = Some(self.resolve_project(project).await?);
+        }
+        if let Some(defaults) = self.settings.project_defaults(project.unwrap_or("Inbox")) {
+            defaults.apply(&mut new_task);
+        }
+        self.create_task(&new_task).await
+    }
+
+    /// Creates a task with full field details locally and in Todoist, returning it unless the title is blank.
+    pub async fn create_task(&mut self, new_task: &NewTask) -> AppResult<Option<Task>> {
+        if new_task.content.trim().is_empty() {
+            return Ok(None);
+        }
+        let task = match self.send_or_queue(self.api_client.add_task(new_task)).await? {
+            Some(task) => task,
+            None => {
+                // The queued command's temporary ID stands in for the Todoist ID until it's sent
+                let command = SyncCommand::item_add(new_task);
+                let task = new_task.preview(command.temp_id.as_deref().unwrap_or_default());
+                self.queue([command])?;
+                task
+            }
+        };
+        Ok(Some(self.insert_created(task)))
+    }
+
+    /// Adds a task in Todoist, failing rather than queueing it when offline, for callers that
+    /// need its real Todoist ID right away.
+    pub async fn create_task_online(&mut self, new_task: &NewTask) -> AppResult<Task> {
+        let task = self.api_client.add_task(new_task).await?;
+        Ok(self.insert_created(task))
+    }
+
+    /// Adds a newly created task to the list and selects it.
+    fn insert_created(&mut self, mut task: Task) -> Task {
+        task.id = self.next_id;
+        self.tasks.push(task.clone());
+        self.next_id += 1;
+        self.select_last();
+        self.mark_changed(task.id);
+        self.record([(None, Some(task.clone()))]);
+        task
+    }
+
+    /// Adds a task through Todoist's Quick Add, which reads the due date, project, labels and
+    /// priority from `text`, and returns it.
+    pub async fn quick_add(&mut self, text: &str) -> AppResult<Task> {
+        let mut task = self.api_client.quick_add(text.trim()).await?;
+        task.id = self.next_id;
+        self.next_id += 1;
+        self.tasks.push(task.clone());
+        self.mark_changed(task.id);
+        self.record([(None, Some(task.clone()))]);
+        Ok(task)
+    }
+
+    /// Creates many tasks through batched Sync API requests, then refreshes the task list.
+    /// Returns the Todoist ID of each created task, or the error that prevented it, including
+    /// a request that failed for its whole chunk.
+    pub async fn create_tasks_batch(&mut self, new_tasks: &[NewTask]) -> AppResult<Vec<Result<String, String>>> {
+        let api_client = &self.api_client;
+        let bar = self.progress_bar(new_tasks.len(), "Creating tasks");
+        let responses = stream::iter(new_tasks.chunks(MAX_COMMANDS))
+            .map(|chunk| {
+                let bar = &bar;
+                async move {
+                    let commands = chunk.iter().map(SyncCommand::item_add).collect::<Vec<_>>();
+                    let response = api_client.sync_commands(&commands).await;
+                    bar.inc(chunk.len() as u64);
+                    (commands, response)
+                }
+            })
+            .buffered(BULK_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+        bar.finish_and_clear();
+
+        let mut results = Vec::with_capacity(new_tasks.len());
+        for (commands, response) in responses {
+            // A failed request fails its own chunk; the other chunks' tasks were still created
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    results.extend(commands.iter().map(|_| Err(e.to_string())));
+                    continue;
+                }
+            };
+            for command in &commands {
+                let result = match response.error_for(command) {
+                    Some(error) => Err(error),
+                    None => command
+                        .temp_id
+                        .as_ref()
+                        .and_then(|temp_id| response.temp_id_mapping.get(temp_id))
+                        .cloned()
+                        .ok_or_else(|| "no ID returned".to_string()),
+                };
+                results.push(result);
+            }
+        }
+        if results.iter().any(Result::is_ok) {
+            self.incremental_sync().await?;
+        }
+        let added = results
+            .iter()
+            .filter_map(|result| result.as_ref().ok())
+            .filter_map(|todoist_id| self.tasks.iter().find(|t| &t.todoist_id == todoist_id))
+            .map(|task| (None, Some(task.clone())))
+            .collect::<Vec<_>>();
+        self.record(added);
+        Ok(results)
+    }
+
+    /// Replaces the labels of many tasks, given by local ID, through batched Sync API requests,
+    /// updating the local copies of those that succeeded. Returns each task's outcome in order;
+    /// tasks missing from the cache aren't sent, and a failed request fails only its own chunk.
+    pub async fn set_labels_batch(&mut self, changes: &[(usize, Vec<String>)]) -> AppResult<Vec<Result<(), String>>> {
+        let mut results = vec![Ok(()); changes.len()];
+        // Positions in `changes` of the commands sent
+        let mut sent = Vec::with_capacity(changes.len());
+        let mut commands = Vec::with_capacity(changes.len());
+        for (i, (id, labels)) in changes.iter().enumerate() {
+            match self.tasks.iter().find(|t| t.id == *id) {
+                Some(task) => {
+                    sent.push(i);
+                    commands.push(SyncCommand::item_update(&task.todoist_id, serde_json::json!({ "labels": labels })));
+                }
+                None => results[i] = Err("not in the local cache".to_string()),
+            }
+        }
+        let api_client = &self.api_client;
+        let bar = self.progress_bar(commands.len(), "Updating labels");
+        let responses = stream::iter(commands.chunks(MAX_COMMANDS))
+            .map(|chunk| {
+                let bar = &bar;
+                async move {
+                    let response = api_client.sync_commands(chunk).await;
+                    bar.inc(chunk.len() as u64);
+                    response
+                }
+            })
+            .buffered(BULK_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+        bar.finish_and_clear();
+
+        let chunks = commands.chunks(MAX_COMMANDS).zip(sent.chunks(MAX_COMMANDS));
+        for ((chunk, positions), response) in chunks.zip(responses) {
+            for (command, &i) in chunk.iter().zip(positions) {
+                results[i] = match &response {
+                    Ok(response) => response.error_for(command).map_or(Ok(()), Err),
+                    // Earlier chunks were applied; only this one's tasks are left as they were
+                    Err(e) => Err(e.to_string()),
+                };
+            }
+        }
+        let mut changed = Vec::new();
+        for ((id, labels), result) in changes.iter().zip(&results) {
+            if result.is_ok()
+                && let Some(task) = self.tasks.iter_mut().find(|t| t.id == *id)
+            {
+                let before = task.clone();
+                task.labels = labels.clone();
+                changed.push((Some(before), Some(task.clone())));
+                self.mark_changed(*id);
+            }
+        }
+        self.record(changed);
+        Ok(results)
+    }
+
+    /// Sends the fields of `update` that differ from a task to Todoist and replaces the local
+    /// copy with the result.
+    pub async fn update_task(&mut self, id: usize, update: TaskUpdate) -> AppResult<()> {
+        let index = self
+            .tasks
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| anyhow::anyhow!("No task with ID {}", id))?;
+        if update.content.as_deref().is_some_and(|title| title.trim().is_empty()) {
+            return Err(anyhow::anyhow!("Task title can't be empty"));
+        }
+        let update = update.changes_from(&self.tasks[index]);
+        if update.is_empty() {
+            return Ok(());
+        }
+        let todoist_id = self.tasks[index].todoist_id.clone();
+        let mut updated = match self.send_or_queue(self.api_client.update_task(&todoist_id, &update)).await? {
+            Some(updated) => updated,
+            None => {
+                let base = self.tasks[index].updated_at.clone();
+                self.queue_edit(base.as_deref(), SyncCommand::task_update(&todoist_id, &update))?;
+                let mut updated = self.tasks[index].clone();
+                update.apply_to(&mut updated);
+                updated
+            }
+        };
+        updated.id = id;
+        // Task updates don't include reminders
+        updated.location = self.tasks[index].location.take();
+        if update.checked == Some(true) && updated.completed_at.is_none() {
+            updated.completed_at = Some(Utc::now().to_rfc3339());
+        }
+        let before = std::mem::replace(&mut self.tasks[index], updated.clone());
+        self.mark_changed(id);
+        self.record([(Some(before), Some(updated))]);
+        if let Some(checked) = update.checked {
+            self.count_completion(checked);
+        }
+        Ok(())
+    }
+
+    /// Deletes a task locally and in Todoist.
+    pub async fn delete_task(&mut self, id: usize) -> AppResult<()> {
+        if let Some(index) = self.tasks.iter().position(|t| t.id == id) {
+            let todoist_id = self.tasks[index].todoist_id.clone();
+            if self.send_or_queue(self.api_client.delete_task(&todoist_id)).await?.is_none() {
+                self.queue([SyncCommand::item_delete(&todoist_id)])?;
+            }
+            let position = self.visible_indices().iter().position(|&i| i == index);
+            let removed = self.tasks.remove(index);
+            self.mark_removed(id);
+            self.record([(Some(removed), None)]);
+            if self.visible_indices().is_empty() {
+                self.list_state.select(None);
+            } else if position.is_some_and(|p| p <= self.list_state.selected().unwrap_or(0)) {
+                let new_index = self.list_state.selected().unwrap_or(1).saturating_sub(1);
+                self.list_state.select(Some(new_index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues open tasks untouched for at least `days` days for review, least recently touched
+    /// first, and returns how many there are. Reviewing a task counts as touching it.
+    pub fn start_review(&mut self, days: i64) -> AppResult<usize> {
+        let cutoff = Utc::now() - chrono::Duration::days(days);
+        let reviewed = self.cache.reviewed()?;
+        let mut stale = self
+            .tasks
+            .iter()
+            .filter(|t| !t.is_done() && !t.todoist_id.is_empty())
+            .filter_map(|t| {
+                let reviewed_at = reviewed
+                    .get(&t.todoist_id)
+                    .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                    .map(|at| at.to_utc());
+                let touched = t.updated().max(reviewed_at)?;
+                (touched < cutoff).then(|| (touched, t.todoist_id.clone()))
+            })
+            .collect::<Vec<_>>();
+        stale.sort();
+        self.review = stale.into_iter().map(|(_, id)| id).collect();
+        Ok(self.review.len())
+    }
+
+    /// Enters the TUI review of tasks untouched for the configured number of days.
+    pub fn enter_review_mode(&mut self) -> AppResult<()> {
+        match self.start_review(self.settings.stale_days)? {
+            0 => self.status = Some("No stale tasks to review".to_string()),
+            _ => self.mode = Mode::Review,
+        }
+        Ok(())
+    }
+
+    /// Starts typing a new due date for the task under review.
+    pub fn enter_review_due_mode(&mut self) {
+        self.mode = Mode::ReviewDue;
+        self.input_buffer.clear();
+    }
+
+    /// Goes back from typing a due date to the task under review.
+    pub fn cancel_review_due(&mut self) {
+        self.mode = Mode::Review;
+        self.input_buffer.clear();
+    }
+
+    /// The task under review.
+    pub fn review_task(&self) -> Option<&Task> {
+        let todoist_id = self.review.first()?;
+        self.tasks.iter().find(|t| &t.todoist_id == todoist_id)
+    }
+
+    /// How many tasks are left to review, including the current one.
+    pub fn review_remaining(&self) -> usize {
+        self.review.len()
+    }
+
+    /// Applies an action to the task under review and moves on to the next one,
+    /// returning to Normal mode once the review is done.
+    pub async fn review(&mut self, action: ReviewAction) -> AppResult<()> {
+        if let Some(task) = self.review_task().cloned() {
+            match action {
+                ReviewAction::Keep => {}
+                ReviewAction::Reschedule(input) => {
+                    let updated = self.api_client.set_task_due(&task.todoist_id, &input).await?;
+                    self.replace_task_fields(&updated);
+                }
+                ReviewAction::Delete => self.delete_task(task.id).await?,
+                ReviewAction::Deprioritize if task.priority > 1 => {
+                    let updated = self
+                        .api_client
+                        .set_task_priority(&task.todoist_id, task.priority - 1)
+                        .await?;
+                    self.replace_task_fields(&updated);
+                }
+                ReviewAction::Deprioritize => {}
+            }
+            self.cache.mark_reviewed(&task.todoist_id)?;
+        }
+        if !self.review.is_empty() {
+            self.review.remove(0);
+        }
+        // Tasks deleted or completed since the review started are skipped
+        while !self.review.is_empty() && self.review_task().is_none() {
+            self.review.remove(0);
+        }
+        if self.review.is_empty() {
+            self.end_review();
+        } else if self.mode == Mode::ReviewDue {
+            self.mode = Mode::Review;
+        }
+        Ok(())
+    }
+
+    /// Stops reviewing, leaving the remaining tasks for next time.
+    pub fn end_review(&mut self) {
+        self.review.clear();
+        if matches!(self.mode, Mode::Review | Mode::ReviewDue) {
+            self.mode = Mode::Normal;
+            self.input_buffer.clear();
+        }
+    }
+
+    /// Copies the due date, priority and modification time of a task returned by the API onto the local copy.
+    fn replace_task_fields(&mut self, updated: &Task) {
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.todoist_id == updated.todoist_id) {
+            let before = task.clone();
+            task.due = updated.due.clone();
+            task.priority = updated.priority;
+            task.updated_at = updated.updated_at.clone();
+            let (id, after) = (task.id, task.clone());
+            self.mark_changed(id);
+            self.record([(Some(before), Some(after))]);
+        }
+    }
+
+    /// Applies field changes to a task in Todoist, first moving it to `project_id` when given,
+    /// and replaces the local copy with the result.
+    pub async fn edit_task(
+        &mut self,
+        id: usize,
+        fields: &serde_json::Value,
+        project_id: Option<&str>,
+    ) -> AppResult<()> {
+        let index = self
+            .tasks
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| anyhow::anyhow!("No task with ID {}", id))?;
+        let todoist_id = self.tasks[index].todoist_id.clone();
+        let before = self.tasks[index].clone();
+        if let Some(project_id) = project_id {
+            self.api_client.move_task(&todoist_id, project_id).await?;
+            self.tasks[index].project_id = project_id.to_string();
+        }
+        if fields.as_object().is_some_and(|fields| !fields.is_empty()) {
+            let mut updated = self.api_client.update_task_fields(&todoist_id, fields).await?;
+            updated.id = id;
+            // Task updates don't include reminders
+            updated.location = self.tasks[index].location.take();
+            self.tasks[index] = updated;
+        }
+        self.mark_changed(id);
+        let after = self.tasks[index].clone();
+        self.record([(Some(before), Some(after))]);
+        Ok(())
+    }
+
+    /// Completes or reopens the selected task locally and in Todoist.
+    pub async fn toggle_task(&mut self) -> AppResult<()> {
+        if let Some((id, done)) = self.selected_task().map(|t| (t.id, t.is_done())) {
+            self.set_task_done(id, !done).await?;
+        }
+        Ok(())
+    }
+
+    /// Completes (`done`) or reopens a task locally and in Todoist.
+    pub async fn set_task_done(&mut self, id: usize, done: bool) -> AppResult<()> {
+        let index = self
+            .tasks
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| anyhow::anyhow!("No task with ID {}", id))?;
+        if self.tasks[index].is_done() == done {
+            return Ok(());
+        }
+        let todoist_id = self.tasks[index].todoist_id.clone();
+        let request = async {
+            if done {
+                self.api_client.close_task(&todoist_id).await
+            } else {
+                self.api_client.reopen_task(&todoist_id).await
+            }
+        };
+        if self.send_or_queue(request).await?.is_none() {
+            let command = if done { SyncCommand::item_close(&todoist_id) } else { SyncCommand::item_uncomplete(&todoist_id) };
+            self.queue([command])?;
+        }
+        // Only change the local copy once Todoist has accepted the change, or it's queued
+        let task = &mut self.tasks[index];
+        let before = task.clone();
+        task.checked = done;
+        task.completed_at = done.then(|| Utc::now().to_rfc3339());
+        let after = task.clone();
+        self.mark_changed(id);
+        self.count_completion(done);
+        self.record([(Some(before), Some(after))]);
+        if done {
+            self.release_unblocked(false).await;
+        }
+        Ok(())
+    }
+
+    /// Reopens a completed task given its Todoist ID, or enough of its start to be unambiguous.
+    /// Tasks archived by `cleanup` come back from the history; other IDs not in the cache, e.g.
+    /// of tasks completed elsewhere, are sent as given and show up with the next sync.
+    pub async fn reopen_task(&mut self, id: &str) -> AppResult<()> {
+        if let Ok(task) = self.find_task(id) {
+            return self.set_task_done(task.id, false).await;
+        }
+        let history = self.cache.load_history()?;
+        let matches = history
+            .iter()
+            .filter(|t| !id.is_empty() && t.todoist_id.starts_with(id))
+            .collect::<Vec<_>>();
+        let archived = match matches.as_slice() {
+            [] => None,
+            [task] => Some((*task).clone()),
+            _ => anyhow::bail!("Task ID '{}' is ambiguous; give more characters", id),
+        };
+        let Some(mut task) = archived else {
+            return self.api_client.reopen_task(id).await;
+        };
+        self.api_client.reopen_task(&task.todoist_id).await?;
+        task.id = self.next_id;
+        self.next_id += 1;
+        let before = task.clone();
+        task.checked = false;
+        task.completed_at = None;
+        self.tasks.push(task.clone());
+        self.mark_changed(task.id);
+        self.record([(Some(before), Some(task))]);
+        Ok(())
+    }
+
+    /// Closes many tasks concurrently, keeping local copies in step with those that succeeded.
+    pub async fn close_tasks_by_todoist_id(&mut self, todoist_ids: &[String]) -> AppResult<()> {
+        let api_client = &self.api_client;
+        let bar = self.progress_bar(todoist_ids.len(), "Completing tasks");
+        let results = stream::iter(todoist_ids)
+            .map(|todoist_id| {
+                let bar = &bar;
+                async move {
+                    let result = api_client.close_task(todoist_id).await;
+                    bar.inc(1);
+                    (todoist_id, result)
+                }
+            })
+            .buffer_unordered(BULK_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+        bar.finish_and_clear();
+
+        let mut first_error = None;
+        let mut closed = Vec::new();
+        for (todoist_id, result) in results {
+            match result {
+                Ok(()) => {
+                    if let Some(task) = self.tasks.iter_mut().find(|t| &t.todoist_id == todoist_id) {
+                        let before = task.clone();
+                        task.checked = true;
+                        task.completed_at = Some(Utc::now().to_rfc3339());
+                        closed.push((Some(before), Some(task.clone())));
+                        let id = task.id;
+                        self.mark_changed(id);
+                        self.count_completion(true);
+                    }
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        self.record(closed);
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Moves tasks completed before `before` from the live cache into the history table,
+    /// returning how many were archived. Completed tasks without a timestamp are kept.
+    pub fn archive_completed(&mut self, before: DateTime<Utc>) -> AppResult<usize> {
+        let (old, live): (Vec<Task>, Vec<Task>) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(|t| {
+                t.is_done()
+                    && t.completed_at
+                        .as_deref()
+                        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                        .is_some_and(|at| at < before)
+            });
+        self.tasks = live;
+        if !old.is_empty() {
+            self.cache.archive_tasks(&old)?;
+            self.cache.save_tasks(&self.tasks)?;
+            self.dirty_at = None;
+            self.pending = PendingWrites::default();
+        }
+        Ok(old.len())
+    }
+
+    /// Deletes many tasks by local ID concurrently, removing those that succeeded.
+    pub async fn delete_tasks(&mut self, ids: &[usize]) -> AppResult<()> {
+        let todoist_ids = self
+            .tasks
+            .iter()
+            .filter(|t| ids.contains(&t.id))
+            .map(|t| t.todoist_id.clone())
+            .collect::<Vec<_>>();
+        // Earlier changes still queued must reach Todoist first, so these wait behind them
+        let (mut queued, todoist_ids) = if self.queued > 0 {
+            (todoist_ids, Vec::new())
+        } else {
+            (Vec::new(), todoist_ids)
+        };
+        let api_client = &self.api_client;
+        let bar = self.progress_bar(todoist_ids.len(), "Deleting tasks");
+        let results = stream::iter(&todoist_ids)
+            .map(|todoist_id| {
+                let bar = &bar;
+                async move {
+                    let result = api_client.delete_task(todoist_id).await;
+                    bar.inc(1);
+                    (todoist_id, result)
+                }
+            })
+            .buffer_unordered(BULK_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+        bar.finish_and_clear();
+
+        let mut first_error = None;
+        let mut deleted = Vec::new();
+        for (todoist_id, result) in results {
+            match result {
+                Ok(()) => deleted.push(todoist_id.clone()),
+                Err(e) if client::is_offline(&e) => queued.push(todoist_id.clone()),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if !queued.is_empty() {
+            self.queue(queued.iter().map(|todoist_id| SyncCommand::item_delete(todoist_id)))?;
+            deleted.extend(queued);
+        }
+        if !deleted.is_empty() {
+            let (removed, kept) = std::mem::take(&mut self.tasks)
+                .into_iter()
+                .partition::<Vec<_>, _>(|t| deleted.contains(&t.todoist_id));
+            self.tasks = kept;
+            for task in &removed {
+                self.mark_removed(task.id);
+            }
+            self.record(removed.into_iter().map(|task| (Some(task), None)));
+            let visible = self.visible_indices().len();
+            if visible == 0 {
+                self.list_state.select(None);
+            } else if self.list_state.selected().is_some_and(|i| i >= visible) {
+                self.list_state.select(Some(visible - 1));
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Sends a change to Todoist unless earlier ones are still queued; then, or when Todoist
+    /// can't be reached, returns `None` for the caller to queue the change instead.
+    async fn send_or_queue<T>(&self, request: impl Future<Output = AppResult<T>>) -> AppResult<Option<T>> {
+        if self.queued > 0 {
+            return Ok(None);
+        }
+        match request.await {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if client::is_offline(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Queues the Sync API commands of a change made while offline, to be sent by the next sync.
+    fn queue(&mut self, commands: impl IntoIterator<Item = SyncCommand>) -> AppResult<()> {
+        self.queue_edit(None, commands)
+    }
+
+    /// Queues the commands of an offline edit made on the task as last updated at `base`, so
+    /// sending them can tell whether Todoist changed it since.
+    fn queue_edit(&mut self, base: Option<&str>, commands: impl IntoIterator<Item = SyncCommand>) -> AppResult<()> {
+        for command in commands {
+            self.cache.queue_operation(&command, base)?;
+            self.queued += 1;
+        }
+        self.status = Some("Offline: queued for the next sync".to_string());
+        Ok(())
+    }
+
+    /// Notes in the status that Todoist couldn't be reached, so the tasks shown are the cached ones.
+    pub fn report_offline(&mut self) {
+        self.status = Some("Offline: showing cached tasks".to_string());
+    }
+
+    /// Shows in the status line why a key press's action failed, e.g. a request while offline,
+    /// so the TUI stays open.
+    pub fn report_failure(&mut self, action: &str, result: AppResult<()>) {
+        if let Err(e) = result {
+            self.status = Some(format!("{} failed: {}", action, e));
+        }
+    }
+
+    /// Number of changes waiting for Todoist to be reachable.
+    pub fn queued_changes(&self) -> usize {
+        self.queued
+    }
+
+    /// Gets the changes queued while offline, oldest first, with when each was queued (RFC 3339).
+    pub fn queued_operations(&self) -> AppResult<Vec<(String, SyncCommand)>> {
+        Ok(self
+            .cache
+            .list_operations()?
+            .into_iter()
+            .map(|(_, queued_at, command)| (queued_at, command))
+            .collect())
+    }
+
+    /// Removes the `n`th queued change, counting from 1, without sending it. A task added
+    /// offline goes away with its queued `item_add`.
+    pub fn drop_queued(&mut self, n: usize) -> AppResult<SyncCommand> {
+        let operations = self.cache.list_operations()?;
+        let (seq, _, command) = n
+            .checked_sub(1)
+            .and_then(|i| operations.into_iter().nth(i))
+            .ok_or_else(|| anyhow::anyhow!("No queued change {}; see `queue list`", n))?;
+        self.cache.drop_operation(seq)?;
+        self.queued = self.queued.saturating_sub(1);
+        if let Some(todoist_id) = command.task_id()
+            && !self.cache.load_operations()?.iter().any(|(_, c)| c.task_id() == Some(todoist_id))
+            && self.conflicts.iter().any(|c| c.todoist_id == todoist_id)
+        {
+            self.conflicts.retain(|c| c.todoist_id != todoist_id);
+            self.cache.remove_conflict(todoist_id)?;
+        }
+        if let Some(temp_id) = &command.temp_id {
+            let before = self.tasks.len();
+            self.tasks.retain(|t| &t.todoist_id != temp_id);
+            if self.tasks.len() != before {
+                self.mark_dirty();
+            }
+        }
+        Ok(command)
+    }
+
+    /// Sends the changes queued while offline to Todoist, oldest first, before a sync fetches
+    /// tasks. Tasks added offline get their Todoist IDs; changes Todoist rejects, e.g. to tasks
+    /// deleted elsewhere, are dropped and reported in the status. Each answered batch is taken
+    /// off the queue with its new IDs saved, so a failure part way leaves the rest ready to resend.
+    /// Edits to tasks also changed in Todoist since are held back as conflicts for the user to
+    /// resolve instead.
+    pub async fn send_queued(&mut self) -> AppResult<()> {
+        if self.queued == 0 {
+            return Ok(());
+        }
+        self.find_conflicts().await?;
+        let operations = self
+            .cache
+            .load_operations()?
+            .into_iter()
+            .filter(|(_, command)| !self.is_held(command))
+            .collect::<Vec<_>>();
+        let mut temp_id_mapping = HashMap::new();
+        let mut rejected = Vec::new();
+        for chunk in operations.chunks(MAX_COMMANDS) {
+            let commands = chunk
+                .iter()
+                .map(|(_, command)| command.with_ids(&temp_id_mapping))
+                .collect::<Vec<_>>();
+            let response = self.api_client.sync_commands(&commands).await?;
+            rejected.extend(commands.iter().filter_map(|command| response.error_for(command)));
+            self.apply_temp_ids(&response.temp_id_mapping)?;
+            self.cache
+                .remove_operations(&chunk.iter().map(|(seq, _)| *seq).collect::<Vec<_>>())?;
+            self.cache.remap_temp_ids(&response.temp_id_mapping)?;
+            self.queued = self.queued.saturating_sub(chunk.len());
+            temp_id_mapping.extend(response.temp_id_mapping);
+        }
+        self.queued = self.cache.count_operations()?;
+        let mut status = match rejected.first() {
+            Some(error) => vec![format!("Todoist rejected {} of the offline changes: {}", rejected.len(), error)],
+            None if operations.is_empty() => Vec::new(),
+            None if operations.len() == 1 => vec!["Sent 1 offline change".to_string()],
+            None => vec![format!("Sent {} offline changes", operations.len())],
+        };
+        // Conflicts put off until later come up again with every sync
+        if !self.conflicts.is_empty() {
+            status.push(match self.conflicts.len() {
+                1 => "1 task was edited offline and in Todoist; choose which version to keep".to_string(),
+                n => format!("{} tasks were edited offline and in Todoist; choose which versions to keep", n),
+            });
+            if self.mode == Mode::Normal {
+                self.mode = Mode::ResolveConflict;
+            }
+        }
+        self.status = Some(status.join("; "));
+        Ok(())
+    }
+
+    /// Compares the tasks with queued edits against Todoist's copies, fetched with the changes
+    /// since the last sync, and saves a conflict for each one changed there since it was edited
+    /// here.
+    async fn find_conflicts(&mut self) -> AppResult<()> {
+        let bases = self.cache.operation_bases()?;
+        let mut edited: HashMap<String, String> = HashMap::new();
+        for (seq, command) in self.cache.load_operations()? {
+            if let (Some(base), Some(id)) = (bases.get(&seq), command.task_id())
+                && !self.conflicts.iter().any(|c| c.todoist_id == id)
+            {
+                // Later edits were made on top of the first, so it holds the base Todoist knew
+                edited.entry(id.to_string()).or_insert_with(|| base.clone());
+            }
+        }
+        if edited.is_empty() {
+            return Ok(());
+        }
+        let sync_token = self.cache.load_sync_token()?;
+        let changes = self
+            .api_client
+            .fetch_task_changes(sync_token.as_deref().unwrap_or("*"))
+            .await?;
+        for theirs in changes.tasks {
+            let Some(base) = edited.get(&theirs.todoist_id) else {
+                continue;
+            };
+            if theirs.updated_at.as_ref() == Some(base) {
+                continue;
+            }
+            let Some(mine) = self.tasks.iter().find(|t| t.todoist_id == theirs.todoist_id).cloned() else {
+                continue;
+            };
+            let conflict = Conflict {
+                todoist_id: theirs.todoist_id.clone(),
+                mine,
+                theirs,
+            };
+            self.cache.save_conflict(&conflict)?;
+            self.conflicts.push(conflict);
+        }
+        Ok(())
+    }
+
+    /// Whether a queued change is held back by a conflict over its task.
+    fn is_held(&self, command: &SyncCommand) -> bool {
+        command
+            .task_id()
+            .is_some_and(|id| self.conflicts.iter().any(|c| c.todoist_id == id))
+    }
+
+    /// Number of queued changes held back by conflicts.
+    fn held_changes(&self) -> AppResult<usize> {
+        Ok(self
+            .cache
+            .load_operations()?
+            .iter()
+            .filter(|(_, command)| self.is_held(command))
+            .count())
+    }
+
+    /// Gets the conflicts waiting for the user to choose a side, oldest first.
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// Gets the first conflict waiting for the user to choose a side, with how many there are.
+    pub fn conflict(&self) -> Option<(&Conflict, usize)> {
+        self.conflicts.first().map(|c| (c, self.conflicts.len()))
+    }
+
+    /// Resolves the first conflict: keeping mine sends the held edits over Todoist's changes,
+    /// keeping theirs drops them and takes Todoist's copy, and `Later` leaves it for the next
+    /// sync. `n` counts from 1 in the order `queue list` shows the conflicts.
+    pub async fn resolve_conflict(&mut self, n: usize, choice: ConflictChoice) -> AppResult<()> {
+        let Some(index) = n.checked_sub(1).filter(|i| *i < self.conflicts.len()) else {
+            self.mode = Mode::Normal;
+            return Err(anyhow::anyhow!("No conflict {}", n));
+        };
+        if let ConflictChoice::Later = choice {
+            self.mode = Mode::Normal;
+            return Ok(());
+        }
+        let conflict = self.conflicts.remove(index);
+        if self.conflicts.is_empty() {
+            self.mode = Mode::Normal;
+        }
+        let keep = match choice {
+            ConflictChoice::KeepMine => {
+                // Based on Todoist's copy now, so the edits aren't held again
+                self.cache
+                    .rebase_operations(&conflict.todoist_id, conflict.theirs.updated_at.as_deref())?;
+                conflict.mine.clone()
+            }
+            _ => {
+                for (seq, command) in self.cache.load_operations()? {
+                    if command.task_id() == Some(conflict.todoist_id.as_str()) {
+                        self.cache.drop_operation(seq)?;
+                    }
+                }
+                self.queued = self.cache.count_operations()?;
+                conflict.theirs.clone()
+            }
+        };
+        self.cache.remove_conflict(&conflict.todoist_id)?;
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.todoist_id == conflict.todoist_id) {
+            let before = task.clone();
+            *task = Task { id: before.id, location: before.location.clone(), ..keep };
+            let after = task.clone();
+            self.mark_changed(after.id);
+            self.record([(Some(before), Some(after))]);
+        }
+        if let ConflictChoice::KeepMine = choice {
+            self.send_queued().await?;
+        }
+        Ok(())
+    }
+
+    /// Gives tasks added offline the Todoist IDs their temporary ones were mapped to, along with
+    /// their blocked-by links and follow-ups, and saves the tasks right away so the mapping isn't
+    /// lost if a later request fails. The cache's links and follow-ups are remapped by
+    /// `Cache::remap_temp_ids`.
+    fn apply_temp_ids(&mut self, temp_id_mapping: &HashMap<String, String>) -> AppResult<()> {
+        if temp_id_mapping.is_empty() {
+            return Ok(());
+        }
+        let remap = |id: &mut String| {
+            if let Some(todoist_id) = temp_id_mapping.get(id) {
+                *id = todoist_id.clone();
+            }
+        };
+        for task in &mut self.tasks {
+            remap(&mut task.todoist_id);
+            if let Some(parent_id) = &mut task.parent_id {
+                remap(parent_id);
+            }
+        }
+        for (task, blocker) in &mut self.blockers {
+            remap(task);
+            remap(blocker);
+        }
+        for (temp_id, todoist_id) in temp_id_mapping {
+            if let Some(since) = self.waiting_since.remove(temp_id) {
+                self.waiting_since.insert(todoist_id.clone(), since);
+            }
+        }
+        self.mark_dirty();
+        self.flush_cache()
+    }
+
+    /// Adds a comment to a task in Todoist.
+    pub async fn add_comment(&self, todoist_id: &str, content: &str) -> AppResult<()> {
+        self.api_client.add_comment(todoist_id, content).await
+    }
+
+    /// The task with a local ID.
+    fn task(&self, id: usize) -> AppResult<&Task> {
+        self.tasks
+            .iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| anyhow::anyhow!("No task with ID {}", id))
+    }
+
+    /// Records that a task can't start before another, its blocker, is done. The link is kept in
+    /// the cache and mirrored in Todoist by `blocked_label` and a comment naming the blocker.
+    pub async fn block_task(&mut self, id: usize, blocker: usize) -> AppResult<()> {
+        let task = self.task(id)?.clone();
+        let blocker = self.task(blocker)?.clone();
+        if task.todoist_id == blocker.todoist_id {
+            anyhow::bail!("A task can't block itself");
+        }
+        if blocker.is_done() {
+            anyhow::bail!("\"{}\" is already done", blocker.title);
+        }
+        if self.waits_on(&blocker.todoist_id, &task.todoist_id) {
+            anyhow::bail!("\"{}\" already waits on \"{}\"", blocker.title, task.title);
+        }
+        let link = (task.todoist_id.clone(), blocker.todoist_id.clone());
+        if self.blockers.contains(&link) {
+            return Ok(());
+        }
+        self.set_blocked_label(id, true).await?;
+        let comment = format!("Blocked by \"{}\" ({})", blocker.title, blocker.web_url());
+        self.add_comment(&task.todoist_id, &comment).await?;
+        self.cache.add_blocker(&link.0, &link.1)?;
+        self.blockers.push(link);
+        Ok(())
+    }
+
+    /// Removes a task's link to `blocker`, or all its blocked-by links, dropping `blocked_label`
+    /// once none are left.
+    pub async fn unblock_task(&mut self, id: usize, blocker: Option<usize>) -> AppResult<()> {
+        let task = self.task(id)?.clone();
+        let blocker = blocker.map(|b| self.task(b).map(|t| t.todoist_id.clone())).transpose()?;
+        let links = self
+            .blockers
+            .iter()
+            .filter(|(t, b)| *t == task.todoist_id && blocker.as_ref().is_none_or(|blocker| b == blocker))
+            .cloned()
+            .collect::<Vec<_>>();
+        if links.is_empty() {
+            anyhow::bail!("\"{}\" isn't blocked{}", task.title, if blocker.is_some() { " by that task" } else { "" });
+        }
+        for (todoist_id, blocker_id) in &links {
+            self.cache.remove_blocker(todoist_id, blocker_id)?;
+        }
+        self.blockers.retain(|link| !links.contains(link));
+        if !self.is_blocked(&task) {
+            self.set_blocked_label(id, false).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the task waits on a blocker that isn't done yet.
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        self.blockers.iter().any(|(t, _)| *t == task.todoist_id)
+    }
+
+    /// Titles of the tasks blocking a task, or their Todoist IDs when they aren't in the cache.
+    pub fn blockers_of(&self, task: &Task) -> Vec<String> {
+        self.blockers
+            .iter()
+            .filter(|(t, _)| *t == task.todoist_id)
+            .map(|(_, blocker)| match self.tasks.iter().find(|t| t.todoist_id == *blocker) {
+                Some(blocker) => blocker.title.clone(),
+                None => blocker.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns true if `todoist_id` is blocked by `blocker_id`, directly or through other tasks.
+    fn waits_on(&self, todoist_id: &str, blocker_id: &str) -> bool {
+        let mut seen = HashSet::new();
+        let mut waiting = vec![todoist_id];
+        while let Some(id) = waiting.pop() {
+            for (_, blocker) in self.blockers.iter().filter(|(t, _)| t == id) {
+                if blocker == blocker_id {
+                    return true;
+                }
+                if seen.insert(blocker.as_str()) {
+                    waiting.push(blocker);
+                }
+            }
+        }
+        false
+    }
+
+    /// Adds or removes `blocked_label` on a task in Todoist; an empty label setting turns this off.
+    async fn set_blocked_label(&mut self, id: usize, blocked: bool) -> AppResult<()> {
+        let label = self.settings.blocked_label.clone();
+        let task = self.task(id)?;
+        if label.is_empty() || task.labels.iter().any(|l| l.eq_ignore_ascii_case(&label)) == blocked {
+            return Ok(());
+        }
+        let mut labels = task.labels.clone();
+        if blocked {
+            labels.push(label);
+        } else {
+            labels.retain(|l| !l.eq_ignore_ascii_case(&label));
+        }
+        self.edit_task(id, &serde_json::json!({ "labels": labels }), None).await
+    }
+
+    /// Drops the blocked-by links of done tasks and to done blockers, surfacing the tasks left
+    /// without blockers: they're flagged as new in the list and named in the status. Tasks
+    /// missing from the cache only count as done after a sync of the whole account (`complete`).
+    async fn release_unblocked(&mut self, complete: bool) {
+        if self.blockers.is_empty() {
+            return;
+        }
+        match self.release_blocked_tasks(complete).await {
+            Ok(released) if released.is_empty() => {}
+            Ok(released) => self.status = Some(format!("Unblocked: {}", released.join(", "))),
+            Err(e) => self.status = Some(format!("Unblocking failed: {}", e)),
+        }
+    }
+
+    /// Removes resolved blocked-by links, returning the titles of the tasks they freed.
+    async fn release_blocked_tasks(&mut self, complete: bool) -> AppResult<Vec<String>> {
+        let resolved = |todoist_id: &str| match self.tasks.iter().find(|t| t.todoist_id == todoist_id) {
+            Some(task) => task.is_done(),
+            None => complete,
+        };
+        let links = self
+            .blockers
+            .iter()
+            .filter(|(t, b)| resolved(t) || resolved(b))
+            .cloned()
+            .collect::<Vec<_>>();
+        for (todoist_id, blocker_id) in &links {
+            self.cache.remove_blocker(todoist_id, blocker_id)?;
+        }
+        self.blockers.retain(|link| !links.contains(link));
+        let mut freed = Vec::new();
+        for (todoist_id, _) in links {
+            let Some(task) = self.tasks.iter().find(|t| t.todoist_id == todoist_id && !t.is_done()) else {
+                continue;
+            };
+            if !self.is_blocked(task) && !freed.contains(&task.id) {
+                freed.push(task.id);
+            }
+        }
+        let mut released = Vec::new();
+        for id in &freed {
+            self.set_blocked_label(*id, false).await?;
+            released.push(format!("\"{}\"", self.task(*id)?.title));
+        }
+        if !freed.is_empty() {
+            let mut flagged = self.new_rows.take().map(|(ids, _)| ids).unwrap_or_default();
+            flagged.extend(freed);
+            self.new_rows = Some((flagged, Instant::now() + NEW_ROW_DURATION));
+        }
+        Ok(released)
+    }
+
+    /// Returns true if an open task is waiting on someone else, i.e. has `waiting_label`, with or
+    /// without an interval such as `waiting-7d`.
+    pub fn is_waiting(&self, task: &Task) -> bool {
+        !task.is_done() && self.followup_interval(task).is_some()
+    }
+
+    fn followup_interval(&self, task: &Task) -> Option<i64> {
+        followups::interval(task, &self.settings.waiting_label, self.settings.followup_days)
+    }
+
+    /// The day to follow up on a waiting task: its interval after it started waiting or was
+    /// last followed up on.
+    pub fn followup_on(&self, task: &Task) -> Option<NaiveDate> {
+        if task.is_done() {
+            return None;
+        }
+        let days = self.followup_interval(task)?;
+        let since = self
+            .waiting_since
+            .get(&task.todoist_id)
+            .copied()
+            .unwrap_or_else(|| followups::started_waiting(task));
+        Some(followups::follow_up_on(since, days))
+    }
+
+    /// Returns true if a waiting task's follow-up day has come.
+    pub fn needs_nudge(&self, task: &Task) -> bool {
+        self.followup_on(task)
+            .is_some_and(|date| date <= Local::now().date_naive())
+    }
+
+    /// Waiting tasks with their follow-up days, soonest first: those due a follow-up, or all
+    /// of them with `all`.
+    pub fn followups(&self, all: bool) -> Vec<(&Task, NaiveDate)> {
+        let today = Local::now().date_naive();
+        let mut followups = self
+            .tasks
+            .iter()
+            .filter_map(|task| Some((task, self.followup_on(task)?)))
+            .filter(|(_, date)| all || *date <= today)
+            .collect::<Vec<_>>();
+        followups.sort_by_key(|(_, date)| *date);
+        followups
+    }
+
+    /// Records that you followed up on a waiting task, restarting its interval from now, and
+    /// returns the next follow-up day.
+    pub fn record_followup(&mut self, id: usize) -> AppResult<NaiveDate> {
+        let task = self.task(id)?;
+        if !self.is_waiting(task) {
+            anyhow::bail!("\"{}\" isn't labeled {}", task.title, self.settings.waiting_label);
+        }
+        let todoist_id = task.todoist_id.clone();
+        let now = Utc::now();
+        self.cache.save_waiting_since(&todoist_id, &now.to_rfc3339())?;
+        self.waiting_since.insert(todoist_id, now);
+        let task = self.task(id)?;
+        self.followup_on(task)
+            .ok_or_else(|| anyhow::anyhow!("\"{}\" has no follow-up", task.title))
+    }
+
+    /// Starts the follow-up clock of tasks seen waiting for the first time, and stops it for
+    /// tasks no longer waiting. Tasks missing from the cache only stop after a sync of the
+    /// whole account (`complete`).
+    fn track_waiting(&mut self, complete: bool) -> AppResult<()> {
+        let started = self
+            .tasks
+            .iter()
+            .filter(|t| self.is_waiting(t) && !self.waiting_since.contains_key(&t.todoist_id))
+            .map(|t| (t.todoist_id.clone(), followups::started_waiting(t)))
+            .collect::<Vec<_>>();
+        let stopped = self
+            .waiting_since
+            .keys()
+            .filter(|id| match self.tasks.iter().find(|t| &&t.todoist_id == id) {
+                Some(task) => !self.is_waiting(task),
+                None => complete,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for (todoist_id, since) in started {
+            self.cache.save_waiting_since(&todoist_id, &since.to_rfc3339())?;
+            self.waiting_since.insert(todoist_id, since);
+        }
+        for todoist_id in stopped {
+            self.cache.forget_waiting(&todoist_id)?;
+            self.waiting_since.remove(&todoist_id);
+        }
+        Ok(())
+    }
+
+    /// Marks the whole task list as changed so it is rewritten after the debounce period.
+    fn mark_dirty(&mut self) {
+        self.pending.all = true;
+        self.dirty_at = Some(Instant::now());
+    }
+
+    /// Marks a task as added or changed so its row is written after the debounce period.
+    fn mark_changed(&mut self, id: usize) {
+        self.pending.changed.insert(id);
+        self.dirty_at = Some(Instant::now());
+    }
+
+    /// Marks a task as deleted so its row is removed after the debounce period.
+    fn mark_removed(&mut self, id: usize) {
+        self.pending.changed.remove(&id);
+        self.pending.removed.insert(id);
+        self.dirty_at = Some(Instant::now());
+    }
+
+    /// Writes what a local action did to tasks, as before/after pairs, to the audit log. The
+    /// changes already reached Todoist, so failing to log them only shows in the status.
+    fn record(&mut self, changes: impl IntoIterator<Item = (Option<Task>, Option<Task>)>) {
+        let entries = changes
+            .into_iter()
+            .filter_map(|(before, after)| AuditEntry::new(&self.actor, before, after))
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return;
+        }
+        if let Err(e) = self.cache.log_events(&entries) {
+            self.status = Some(format!("Audit log failed: {}", e));
+        }
+    }
+
+    /// Gets the audit log, newest `limit` entries, optionally of one task by Todoist ID prefix.
+    pub fn events(&self, todoist_id: Option<&str>, limit: usize) -> AppResult<Vec<AuditEntry>> {
+        self.cache.load_events(todoist_id, limit)
+    }
+
+    /// Gets changes found between syncs, newest `limit`, and marks them seen; only those not
+    /// seen before unless `all` is set.
+    pub fn remote_changes(&self, all: bool, limit: usize) -> AppResult<Vec<AuditEntry>> {
+        let changes = self.cache.load_remote_changes(all, limit)?;
+        self.cache.mark_remote_changes_seen()?;
+        Ok(changes)
+    }
+
+    /// Gets when pending task changes should be written to the cache, if any are pending.
+    pub fn flush_deadline(&self) -> Option<Instant> {
+        self.dirty_at.map(|at| at + SAVE_DEBOUNCE)
+    }
+
+    /// Gets the request counts and latencies of this session by endpoint.
+    pub fn api_stats(&self) -> Usage {
+        self.api_client.metrics().snapshot()
+    }
+
+    /// Saves this session's API usage for `stats --api`, unless it made no requests.
+    pub fn save_api_stats(&self) -> AppResult<()> {
+        let stats = self.api_stats();
+        if stats.is_empty() {
+            return Ok(());
+        }
+        self.cache.save_api_stats(&self.session, &stats)
+    }
+
+    /// Writes pending task changes to the cache immediately, touching only the affected rows
+    /// unless the whole list changed.
+    pub fn flush_cache(&mut self) -> AppResult<()> {
+        if self.dirty_at.take().is_none() {
+            return Ok(());
+        }
+        let pending = std::mem::take(&mut self.pending);
+        if pending.all {
+            return self.cache.save_tasks(&self.tasks);
+        }
+        let changed = self
+            .tasks
+            .iter()
+            .filter(|t| pending.changed.contains(&t.id))
+            .collect::<Vec<_>>();
+        let removed = pending.removed.into_iter().collect::<Vec<_>>();
+        self.cache.write_tasks(&changed, &removed)
+    }
+
+    /// Moves selection to the next task.
+    pub fn next(&mut self) {
+        if self.view == View::Week {
+            let count = self.week_days()[self.week.day()].1.len();
+            self.week.shift_row(1, count);
+            return;
+        }
+        if self.view == View::Day {
+            let count = self.planner_tasks().len();
+            self.planner.shift_row(1, count);
+            return;
+        }
+        if self.view == View::Quadrants {
+            let counts = self.quadrant_tasks().map(|tasks| tasks.len());
+            self.quadrants.shift_row(1, counts);
+            return;
+        }
+        let len = self.visible_indices().len();
+        if len == 0 {
+            self.list_state.select(None);
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) => (i + 1) % len,
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    /// Moves selection to the previous task.
+    pub fn previous(&mut self) {
+        if self.view == View::Week {
+            let count = self.week_days()[self.week.day()].1.len();
+            self.week.shift_row(-1, count);
+            return;
+        }
+        if self.view == View::Day {
+            let count = self.planner_tasks().len();
+            self.planner.shift_row(-1, count);
+            return;
+        }
+        if self.view == View::Quadrants {
+            let counts = self.quadrant_tasks().map(|tasks| tasks.len());
+            self.quadrants.shift_row(-1, counts);
+            return;
+        }
+        let len = self.visible_indices().len();
+        if len == 0 {
+            self.list_state.select(None);
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    len - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    /// Moves selection to the last visible task.
+    pub fn select_last(&mut self) {
+        let len = self.visible_indices().len();
+        self.list_state.select(len.checked_sub(1));
+    }
+
+    /// Enters Insert mode for adding a new task.
+    pub fn enter_insert_add_mode(&mut self) {
+        self.input_buffer.clear();
+        self.mode = Mode::InsertAdd;
+    }
+
+    /// Enters Insert mode for editing the selected task.
+    pub fn enter_insert_edit_mode(&mut self) {
+        self.input_buffer.clear();
+        if let Some(task) = self.selected_task() {
+            self.input_buffer = task.title.clone();
+        }
+        self.mode = Mode::InsertEdit;
+    }
+
+    /// Enters EditDescription mode with the selected task's description in the input buffer.
+    pub fn enter_description_mode(&mut self) {
+        if let Some(description) = self.selected_task().map(|t| t.description.clone()) {
+            self.input_buffer = description;
+            self.mode = Mode::EditDescription;
+        }
+    }
+
+    /// Saves the edited description of the selected task and returns to Normal mode.
+    pub async fn save_description(&mut self) -> AppResult<()> {
+        let description = std::mem::take(&mut self.input_buffer);
+        self.mode = Mode::Normal;
+        if let Some(id) = self.selected_task().map(|t| t.id) {
+            let update = TaskUpdate {
+                description: Some(description),
+                ..Default::default()
+            };
+            self.update_task(id, update).await?;
+        }
+        Ok(())
+    }
+
+    /// Enters Filter mode, starting from the active filter query.
+    pub fn enter_filter_mode(&mut self) {
+        self.input_buffer = self.filter_query().unwrap_or_default().to_string();
+        self.mode = Mode::Filter;
+    }
+
+    /// Finds an open task whose title closely matches `title`.
+    pub fn find_duplicate(&self, title: &str) -> Option<&Task> {
+        self.tasks
+            .iter()
+            .filter(|t| !t.is_done())
+            .map(|t| (similarity(&t.title, title), t))
+            .filter(|(score, _)| *score >= DUPLICATE_SIMILARITY)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, t)| t)
+    }
+
+    /// Gets the pending title and the existing task it resembles while confirming a duplicate.
+    pub fn duplicate(&self) -> Option<(&str, &Task)> {
+        let (title, id) = self.duplicate.as_ref()?;
+        Some((title.as_str(), self.tasks.iter().find(|t| t.id == *id)?))
+    }
+
+    /// Resolves a pending duplicate by jumping to the existing task, adding the new one, or dropping it.
+    pub async fn resolve_duplicate(&mut self, choice: DuplicateChoice) -> AppResult<()> {
+        self.mode = Mode::Normal;
+        let Some((title, existing)) = self.duplicate.take() else {
+            return Ok(());
+        };
+        match choice {
+            DuplicateChoice::Jump => self.select_task(existing),
+            DuplicateChoice::AddAnyway => self.add_task(&title).await?,
+            DuplicateChoice::Cancel => {}
+        }
+        Ok(())
+    }
+
+    /// Queues reminders for open tasks that came due in the last few minutes and records them as
+    /// notified, returning their local IDs. Each due time fires once, also across `notify` runs.
+    pub fn fire_reminders(&mut self) -> AppResult<Vec<usize>> {
+        let now = Local::now().naive_local();
+        let since = now - chrono::Duration::minutes(REMINDER_GRACE_MINUTES);
+        let mut fired = Vec::new();
+        for task in self.tasks.iter().filter(|t| !t.is_done()) {
+            let Some(due) = &task.due else {
+                continue;
+            };
+            let (Some(at), Some(key)) = (due.naive_datetime(), due.datetime.as_deref()) else {
+                continue;
+            };
+            if at <= since || at > now || self.cache.was_notified(&task.todoist_id, key)? {
+                continue;
+            }
+            self.cache.mark_notified(&task.todoist_id, key)?;
+            fired.push(task.id);
+        }
+        self.reminders.extend(&fired);
+        Ok(fired)
+    }
+
+    /// The task of the oldest reminder still waiting to be dismissed.
+    pub fn reminder(&self) -> Option<&Task> {
+        let id = *self.reminders.first()?;
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// Dismisses the current reminder, showing the next one if any.
+    pub fn dismiss_reminder(&mut self) {
+        if !self.reminders.is_empty() {
+            self.reminders.remove(0);
+        }
+    }
+
+    /// Checks the activity log for task events other collaborators made in shared projects since
+    /// the last check, oldest first, as e.g. "Team: Ana completed Ship it". Only the `projects`
+    /// named are reported when any are given. The first check just notes the events already there.
+    pub async fn poll_activity(&mut self, projects: &[String]) -> AppResult<Vec<String>> {
+        let me = self.user_id().await?;
+        if self.projects.is_empty() {
+            self.sync_projects().await?;
+        }
+        let shared = self.projects.iter().filter(|p| p.is_shared).collect::<Vec<_>>();
+        if let Some(name) = projects.iter().find(|n| !shared.iter().any(|p| p.name.eq_ignore_ascii_case(n))) {
+            anyhow::bail!("No shared project named \"{}\"", name);
+        }
+        let watched = shared
+            .into_iter()
+            .filter(|p| projects.is_empty() || projects.iter().any(|n| p.name.eq_ignore_ascii_case(n)))
+            .map(|p| (p.id.clone(), p.name.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let activities = self.api_client.fetch_activities().await?;
+        let ids = activities.iter().map(|a| a.id.clone()).collect();
+        let Some(seen) = self.activity_seen.replace(ids) else {
+            return Ok(Vec::new());
+        };
+        let mut messages = Vec::new();
+        for activity in activities.iter().rev().filter(|a| !seen.contains(&a.id)) {
+            let Some((project_id, project)) =
+                activity.parent_project_id.as_ref().and_then(|id| watched.get_key_value(id))
+            else {
+                continue;
+            };
+            self.collaborators(project_id).await?;
+            let initiator = activity.initiator_id.as_ref().and_then(|id| self.people.get(id));
+            if let Some(text) = activity.describe(&me, initiator.map(String::as_str)) {
+                messages.push(format!("{}: {}", project, text));
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Checks for collaborator activity in the projects from the `[activity]` settings and shows
+    /// new events as toasts, or the failure in the status. Returns true if anything changed.
+    pub async fn check_activity(&mut self) -> bool {
+        let projects = self.settings.activity.as_ref().map(|a| a.projects.clone()).unwrap_or_default();
+        match self.poll_activity(&projects).await {
+            Ok(messages) => {
+                let until = Instant::now() + TOAST_DURATION;
+                self.toasts.extend(messages.into_iter().map(|m| (m, until)));
+                !self.toasts.is_empty()
+            }
+            Err(e) => {
+                self.status = Some(format!("Activity check failed: {}", e));
+                true
+            }
+        }
+    }
+
+    /// Toasts currently on screen, oldest first.
+    pub fn toasts(&self) -> impl Iterator<Item = &str> {
+        self.toasts.iter().map(|(message, _)| message.as_str())
+    }
+
+    /// When the next toast or new-task flag goes away.
+    pub fn fade_deadline(&self) -> Option<Instant> {
+        let toasts = self.toasts.iter().map(|(_, until)| *until);
+        toasts.chain(self.new_rows.as_ref().map(|(_, until)| *until)).min()
+    }
+
+    /// Removes the toasts and new-task flags whose time is up.
+    pub fn fade(&mut self) {
+        let now = Instant::now();
+        self.toasts.retain(|(_, until)| *until > now);
+        if self.new_rows.as_ref().is_some_and(|(_, until)| *until <= now) {
+            self.new_rows = None;
+        }
+    }
+
+    /// Selects the reminded task in the list view and dismisses its reminder.
+    pub fn jump_to_reminder(&mut self) {
+        if let Some(id) = self.reminder().map(|t| t.id) {
+            self.view = View::List;
+            self.select_task(id);
+        }
+        self.dismiss_reminder();
+    }
+
+    /// Selects a random open task among those shown in the list view, returning whether there was one.
+    pub fn select_random(&mut self) -> bool {
+        let open = self
+            .visible_indices()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, i)| !self.tasks[i].is_done())
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        let Some(pick) = random::index(open.len()) else {
+            return false;
+        };
+        self.view = View::List;
+        self.list_state.select(Some(open[pick]));
+        true
+    }
+
+    /// Opens the selected task in the Todoist web app, reporting failures in the status.
+    pub fn open_selected_in_browser(&mut self) {
+        let Some(url) = self.selected_task().map(Task::web_url) else {
+            return;
+        };
+        if let Err(e) = browser::open(&url) {
+            self.status = Some(e.to_string());
+        }
+    }
+
+    /// Copies the selected task's web URL, or its Todoist ID when `id` is set, to the clipboard.
+    pub fn copy_selected(&mut self, id: bool) {
+        let Some(text) = self.selected_task().map(|t| if id { t.todoist_id.clone() } else { t.web_url() }) else {
+            return;
+        };
+        let copied = match &mut self.clipboard {
+            Some(clipboard) => clipboard.set_text(&text),
+            None => arboard::Clipboard::new().and_then(|clipboard| {
+                self.clipboard.insert(clipboard).set_text(&text)
+            }),
+        };
+        self.status = Some(match copied {
+            Ok(()) => format!("Copied {}", text),
+            Err(e) => format!("Copy failed: {}", e),
+        });
+    }
+
+    /// ID of the token's user, fetched once.
+    async fn user_id(&mut self) -> AppResult<String> {
+        if let Some(id) = &self.user_id {
+            return Ok(id.clone());
+        }
+        let id = self.api_client.fetch_user().await?.id;
+        self.people.insert(id.clone(), "me".to_string());
+        self.row_cache.set_people(self.people.clone());
+        Ok(self.user_id.insert(id).clone())
+    }
+
+    /// Collaborators of a shared project, fetched once per project.
+    async fn collaborators(&mut self, project_id: &str) -> AppResult<&[Collaborator]> {
+        if !self.collaborators.contains_key(project_id) {
+            let collaborators = self.api_client.fetch_collaborators(project_id).await?;
+            for collaborator in &collaborators {
+                if self.user_id.as_ref() != Some(&collaborator.id) {
+                    self.people.insert(collaborator.id.clone(), collaborator.name.clone());
+                }
+            }
+            self.collaborators.insert(project_id.to_string(), collaborators);
+            self.row_cache.set_people(self.people.clone());
+        }
+        Ok(&self.collaborators[project_id])
+    }
+
+    /// Fetches the collaborators of the shared projects with assigned tasks, so the list can
+    /// show who each task is assigned to.
+    pub async fn sync_collaborators(&mut self) -> AppResult<()> {
+        let assigned = self
+            .tasks
+            .iter()
+            .filter(|t| t.responsible_uid.is_some())
+            .map(|t| t.project_id.clone())
+            .collect::<HashSet<_>>();
+        if assigned.is_empty() {
+            return Ok(());
+        }
+        self.user_id().await?;
+        for project_id in assigned {
+            self.collaborators(&project_id).await?;
+        }
+        Ok(())
+    }
+
+    /// The selected task, when it's in a shared project; otherwise says why not in the status.
+    async fn selected_shared_task(&mut self) -> AppResult<Option<(usize, String)>> {
+        let Some((id, project_id)) = self.selected_task().map(|t| (t.id, t.project_id.clone())) else {
+            return Ok(None);
+        };
+        if self.projects.is_empty() {
+            self.sync_projects().await?;
+        }
+        if !self.projects.iter().any(|p| p.id == project_id && p.is_shared) {
+            self.status = Some("Only tasks in shared projects can be assigned".to_string());
+            return Ok(None);
+        }
+        Ok(Some((id, project_id)))
+    }
+
+    /// Assigns the selected task to the token's user, when it's in a shared project. Errors,
+    /// e.g. while offline, are shown in the status line.
+    pub async fn assign_selected_to_me(&mut self) {
+        if let Err(e) = self.try_assign_selected_to_me().await {
+            self.status = Some(format!("Assigning failed: {}", e));
+        }
+    }
+
+    async fn try_assign_selected_to_me(&mut self) -> AppResult<()> {
+        let Some((id, _)) = self.selected_shared_task().await? else {
+            return Ok(());
+        };
+        let me = self.user_id().await?;
+        self.update_task(id, TaskUpdate { assignee_id: Some(Some(me)), ..Default::default() }).await?;
+        self.status = Some("Assigned to you".to_string());
+        Ok(())
+    }
+
+    /// Opens a picker of the collaborators in the selected task's shared project to assign it to.
+    /// Errors, e.g. while offline, are shown in the status line.
+    pub async fn enter_assign_mode(&mut self) {
+        if let Err(e) = self.open_assign_picker().await {
+            self.status = Some(format!("Loading collaborators failed: {}", e));
+        }
+    }
+
+    async fn open_assign_picker(&mut self) -> AppResult<()> {
+        let Some((task, project_id)) = self.selected_shared_task().await? else {
+            return Ok(());
+        };
+        let me = self.user_id().await?;
+        let mut choices = self
+            .collaborators(&project_id)
+            .await?
+            .iter()
+            .map(|c| {
+                let name = if c.id == me { format!("{} (me)", c.name) } else { c.name.clone() };
+                (Some(c.id.clone()), name)
+            })
+            .collect::<Vec<_>>();
+        choices.push((None, "Nobody".to_string()));
+        let current = self.tasks.iter().find(|t| t.id == task).and_then(|t| t.responsible_uid.clone());
+        let selected = choices.iter().position(|(id, _)| *id == current).unwrap_or(0);
+        self.assign_picker = Some(AssignPicker { task, choices, selected });
+        self.mode = Mode::Assign;
+        Ok(())
+    }
+
+    /// Names in the assignee picker, with the index of the selected one.
+    pub fn assign_choices(&self) -> Option<(Vec<&str>, usize)> {
+        let picker = self.assign_picker.as_ref()?;
+        let names = picker.choices.iter().map(|(_, name)| name.as_str()).collect();
+        Some((names, picker.selected))
+    }
+
+    /// Moves the assignee picker's selection by `step`, wrapping around.
+    pub fn shift_assign_choice(&mut self, step: isize) {
+        if let Some(picker) = &mut self.assign_picker {
+            let len = picker.choices.len() as isize;
+            picker.selected = (picker.selected as isize + step).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Assigns the task to the person picked, or just closes the picker when `apply` isn't set.
+    pub async fn exit_assign_mode(&mut self, apply: bool) -> AppResult<()> {
+        self.mode = Mode::Normal;
+        let Some(picker) = self.assign_picker.take() else {
+            return Ok(());
+        };
+        let Some((assignee, name)) = picker.choices.get(picker.selected).cloned() else {
+            return Ok(());
+        };
+        if apply {
+            self.status = Some(match &assignee {
+                Some(_) => format!("Assigned to {}", name),
+                None => "Unassigned".to_string(),
+            });
+            self.update_task(picker.task, TaskUpdate { assignee_id: Some(assignee), ..Default::default() })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Opens a picker of the active projects, as a tree, to move the selected task to. Errors,
+    /// e.g. while offline without cached projects, are shown in the status line.
+    pub async fn enter_move_mode(&mut self) {
+        if let Err(e) = self.open_project_picker().await {
+            self.status = Some(format!("Loading projects failed: {}", e));
+        }
+    }
+
+    async fn open_project_picker(&mut self) -> AppResult<()> {
+        let Some(task) = self.selected_task() else {
+            return Ok(());
+        };
+        let (task, current) = (task.id, task.project_id.clone());
+        if self.projects.is_empty() {
+            self.sync_projects().await?;
+        }
+        let choices = self
+            .sidebar_projects()
+            .into_iter()
+            .map(|(depth, p)| (p.id.clone(), format!("{}{}", "  ".repeat(depth), p.name)))
+            .collect::<Vec<_>>();
+        if choices.is_empty() {
+            return Ok(());
+        }
+        let selected = choices.iter().position(|(id, _)| *id == current).unwrap_or(0);
+        self.project_picker = Some(ProjectPicker { task, choices, selected });
+        self.mode = Mode::Move;
+        Ok(())
+    }
+
+    /// Names in the project picker, indented by depth, with the index of the selected one.
+    pub fn project_choices(&self) -> Option<(Vec<&str>, usize)> {
+        let picker = self.project_picker.as_ref()?;
+        let names = picker.choices.iter().map(|(_, name)| name.as_str()).collect();
+        Some((names, picker.selected))
+    }
+
+    /// Moves the project picker's selection by `step`, wrapping around.
+    pub fn shift_project_choice(&mut self, step: isize) {
+        if let Some(picker) = &mut self.project_picker {
+            let len = picker.choices.len() as isize;
+            picker.selected = (picker.selected as isize + step).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Moves the task to the project picked, or just closes the picker when `apply` isn't set.
+    pub async fn exit_move_mode(&mut self, apply: bool) -> AppResult<()> {
+        self.mode = Mode::Normal;
+        let Some(picker) = self.project_picker.take() else {
+            return Ok(());
+        };
+        let Some((project_id, name)) = picker.choices.get(picker.selected).cloned() else {
+            return Ok(());
+        };
+        let unchanged = self.tasks.iter().any(|t| t.id == picker.task && t.project_id == project_id);
+        if apply && !unchanged {
+            self.edit_task(picker.task, &serde_json::json!({}), Some(&project_id)).await?;
+            self.status = Some(format!("Moved to {}", name.trim_start()));
+        }
+        Ok(())
+    }
+
+    /// Selects a task by local ID, clearing the filter and project view if they hide the task.
+    fn select_task(&mut self, id: usize) {
+        let Some(index) = self.tasks.iter().position(|t| t.id == id) else {
+            return;
+        };
+        if !self.visible_indices().contains(&index) {
+            self.filter = None;
+            self.project_view = None;
+        }
+        let position = self.visible_indices().iter().position(|&i| i == index);
+        self.list_state.select(position);
+    }
+
+    /// Leaves Insert or Filter mode without applying the input.
+    pub fn cancel_input(&mut self) {
+        self.mode = Mode::Normal;
+        self.input_buffer.clear();
+    }
+
+    /// Exits Insert mode, saving the input as a new or updated task.
+    pub async fn exit_insert_mode(&mut self) -> AppResult<()> {
+        let input = self.input_buffer.clone();
+        if self.mode == Mode::Filter {
+            // Project names are needed for `#Project` terms; offline the filter just won't match
+            if input.contains('#') && self.projects.is_empty() {
+                let _ = self.sync_projects().await;
+            }
+            // A bad query keeps the previous filter rather than closing the TUI
+            if let Err(e) = self.set_filter(&input) {
+                self.status = Some(format!("Invalid filter: {}", e));
+            }
+        } else if !input.trim().is_empty() {
+            match self.mode {
+                Mode::InsertAdd => {
+                    if let Some(existing) = self.find_duplicate(&input).map(|t| t.id) {
+                        self.duplicate = Some((input, existing));
+                        self.mode = Mode::ConfirmDuplicate;
+                        self.input_buffer.clear();
+                        return Ok(());
+                    }
+                    self.add_task(&input).await?;
+                }
+                Mode::InsertEdit => {
+                    if let Some(id) = self.selected_task().map(|t| t.id) {
+                        let update = TaskUpdate {
+                            content: Some(input),
+                            ..Default::default()
+                        };
+                        self.update_task(id, update).await?;
+                    } else {
+                        self.add_task(&input).await?;
+                    }
+                }
+                Mode::Normal
+                | Mode::EditDescription
+                | Mode::Filter
+                | Mode::ConfirmDuplicate
+                | Mode::Review
+                | Mode::ReviewDue
+                | Mode::Assign
+                | Mode::Place
+                | Mode::Move
+                | Mode::ResolveConflict => {}
+            }
+        }
+        self.mode = Mode::Normal;
+        self.input_buffer.clear();
+        Ok(())
+    }
+
+    /// Handles input in Insert mode.
+    pub fn handle_input(&mut self, c: char) {
+        self.input_buffer.push(c);
+    }
+
+    /// Handles backspace in Insert mode.
+    pub fn handle_backspace(&mut self) {
+        self.input_buffer.pop();
+    }
+
+    /// Gets the current mode.
+    pub fn mode(&self) -> &Mode {
+        &self.mode
+    }
+
+    /// Gets the task list.
+    pub fn tasks(&self) -> &Vec<Task> {
+        &self.tasks
+    }
+
+    /// Finds a task by its Todoist ID or an unambiguous prefix of it, as shown by the CLI.
+    pub fn find_task(&self, id: &str) -> AppResult<&Task> {
+        if let Some(task) = self.tasks.iter().find(|t| t.todoist_id == id) {
+            return Ok(task);
+        }
+        let matches = self
+            .tasks
+            .iter()
+            .filter(|t| !id.is_empty() && t.todoist_id.starts_with(id))
+            .collect::<Vec<_>>();
+        match matches.as_slice() {
+            [task] => Ok(task),
+            [] => Err(anyhow::anyhow!("No task with ID '{}'", id)),
+            _ => Err(anyhow::anyhow!("Task ID '{}' is ambiguous; give more characters", id)),
+        }
+    }
+
+    /// Gets how many characters of the Todoist IDs the CLI shows, enough to tell all tasks apart.
+    pub fn short_id_len(&self) -> usize {
+        unique_prefix_len(self.tasks.iter().map(|t| t.todoist_id.as_str()), SHORT_ID_LEN)
+    }
+
+    /// Returns true while the first-run welcome screen is shown.
+    pub fn welcome(&self) -> bool {
+        self.welcome
+    }
+
+    /// Closes the first-run welcome screen.
+    pub fn dismiss_welcome(&mut self) {
+        self.welcome = false;
+    }
+
+    /// Returns true while the API usage overlay is shown.
+    pub fn api_overlay(&self) -> bool {
+        self.api_overlay
+    }
+
+    /// Shows or hides the API usage overlay.
+    pub fn toggle_api_overlay(&mut self) {
+        self.api_overlay = !self.api_overlay;
+    }
+
+    /// Per-project trends while the stats overlay is shown.
+    pub fn stats_overlay(&self) -> Option<&[ProjectTrend]> {
+        self.stats_overlay.as_deref()
+    }
+
+    /// Shows or hides the stats overlay, charting the completion history as of now.
+    pub async fn toggle_stats_overlay(&mut self) -> AppResult<()> {
+        self.stats_overlay = match self.stats_overlay {
+            Some(_) => None,
+            None => Some(self.project_trends().await?),
+        };
+        Ok(())
+    }
+
+    /// Weekly completions and open tasks per project over the last `trends::WEEKS` weeks, from
+    /// the cache and the completed-task history.
+    pub async fn project_trends(&mut self) -> AppResult<Vec<ProjectTrend>> {
+        // Project names label the charts; offline they fall back to project IDs
+        if self.projects.is_empty() {
+            let _ = self.sync_projects().await;
+        }
+        let history = self.cache.load_history()?;
+        let today = Local::now().date_naive();
+        Ok(trends::project_trends(&self.tasks, &history, &self.projects, today, trends::WEEKS))
+    }
+
+    /// Gets the project list.
+    pub fn projects(&self) -> &Vec<Project> {
+        &self.projects
+    }
+
+    /// Gets the label list, as cached by the last `sync_labels`.
+    pub fn labels(&self) -> &Vec<Label> {
+        &self.labels
+    }
+
+    /// Gets the local cache.
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Gets the transient status message, such as sync progress or errors.
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Gets the user settings.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Gets the mutable list state.
+    pub fn list_state(&mut self) -> &mut ListState {
+        &mut self.list_state
+    }
+
+    /// Applies a filter query, or a saved filter view by name, to the TUI list; an empty query clears it.
+    pub fn set_filter(&mut self, query: &str) -> AppResult<()> {
+        let query = query.trim();
+        self.filter = if query.is_empty() {
+            None
+        } else {
+            let today = Local::now().date_naive();
+            let filter = Filter::parse(self.settings.resolve_filter(query), today)?;
+            Some((query.to_string(), filter))
+        };
+        self.completed_matches = match &self.filter {
+            Some((_, filter)) if self.include_completed => {
+                let today = Local::now().date_naive();
+                let mut history = self.cache.load_history()?;
+                // Tasks still in the list are shown there already
+                history.retain(|task| {
+                    filter.matches(task, &self.projects, today)
+                        && !self.tasks.iter().any(|t| t.todoist_id == task.todoist_id)
+                });
+                history
+            }
+            _ => Vec::new(),
+        };
+        let visible = self.visible_indices().len();
+        self.list_state.select((visible > 0).then_some(0));
+        Ok(())
+    }
+
+    /// Toggles whether filtering also searches the completed-task history.
+    pub fn toggle_include_completed(&mut self) {
+        self.include_completed = !self.include_completed;
+    }
+
+    /// Whether filtering also searches the completed-task history.
+    pub fn include_completed(&self) -> bool {
+        self.include_completed
+    }
+
+    /// Archived completed tasks matching the active filter.
+    pub fn completed_matches(&self) -> &[Task] {
+        &self.completed_matches
+    }
+
+    /// Gets the query of the active TUI filter.
+    pub fn filter_query(&self) -> Option<&str> {
+        self.filter.as_ref().map(|(query, _)| query.as_str())
+    }
+
+    /// Indices into `tasks` of the tasks shown in the TUI, honoring the active filter.
+    fn visible_indices(&self) -> Vec<usize> {
+        if self.filter.is_none() && self.project_view.is_none() {
+            return (0..self.tasks.len()).collect();
+        }
+        let today = Local::now().date_naive();
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| {
+                self.project_view
+                    .as_ref()
+                    .is_none_or(|id| project::is_within(&self.projects, &task.project_id, id))
+            })
+            .filter(|(_, task)| {
+                self.filter
+                    .as_ref()
+                    .is_none_or(|(_, filter)| filter.matches(task, &self.projects, today))
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Active projects as a tree with their depths, as the sidebar and project picker list them.
+    pub fn sidebar_projects(&self) -> Vec<(usize, &Project)> {
+        // Archiving a project archives its sub-projects too, so the depths still line up
+        project::nested(&self.projects)
+            .into_iter()
+            .filter(|(_, p)| !p.is_archived)
+            .collect()
+    }
+
+    /// Rows of the TUI sidebar: "All tasks", the favorite projects, labels and filters, the
+    /// active projects as a tree, and the archived projects while they're shown.
+    pub fn sidebar_rows(&self) -> Vec<SidebarRow<'_>> {
+        let projects = self.sidebar_projects();
+        let mut rows = vec![SidebarRow::AllTasks];
+        let favorites = projects
+            .iter()
+            .filter(|(_, p)| p.is_favorite)
+            .map(|(_, p)| SidebarRow::Project(0, p))
+            .chain(self.labels.iter().filter(|l| l.is_favorite).map(SidebarRow::Label))
+            .chain(self.filters.iter().filter(|f| f.is_favorite).map(SidebarRow::Filter))
+            .collect::<Vec<_>>();
+        if !favorites.is_empty() {
+            rows.push(SidebarRow::Heading("Favorites"));
+            rows.extend(favorites);
+            rows.push(SidebarRow::Heading("Projects"));
+        }
+        rows.extend(projects.into_iter().map(|(depth, p)| SidebarRow::Project(depth, p)));
+        if let Some(archived) = self.archived_shown.as_ref().filter(|a| !a.is_empty()) {
+            rows.push(SidebarRow::Heading("Archived"));
+            rows.extend(project::nested(archived).into_iter().map(|(depth, p)| SidebarRow::Project(depth, p)));
+        }
+        rows
+    }
+
+    /// Index into `sidebar_rows` of the sidebar's selection: the row last picked while it still
+    /// shows the picked project, else the first row that does.
+    pub fn sidebar_selected(&self) -> usize {
+        let rows = self.sidebar_rows();
+        let shows_view = |row: &SidebarRow| match row {
+            SidebarRow::AllTasks => self.project_view.is_none(),
+            SidebarRow::Heading(_) => false,
+            SidebarRow::Project(_, p) => self.project_view.as_deref() == Some(p.id.as_str()),
+            SidebarRow::Label(_) | SidebarRow::Filter(_) => {
+                self.project_view.is_none() && row.query().as_deref() == self.filter_query()
+            }
+        };
+        if rows.get(self.sidebar_row).is_some_and(shows_view) {
+            return self.sidebar_row;
+        }
+        rows.iter().position(shows_view).unwrap_or(0)
+    }
+
+    /// The project picked in the sidebar, or `None` while showing all tasks.
+    pub fn project_view(&self) -> Option<&Project> {
+        let id = self.project_view.as_ref()?;
+        self.projects
+            .iter()
+            .chain(self.archived_shown.iter().flatten())
+            .find(|p| &p.id == id)
+    }
+
+    /// Whether the sidebar has the keyboard focus.
+    pub fn sidebar_focused(&self) -> bool {
+        self.sidebar_focused && self.view == View::List
+    }
+
+    /// Gives the keyboard focus to the sidebar or back to the list; only the list view has one.
+    pub fn focus_sidebar(&mut self, focused: bool) {
+        self.sidebar_focused = focused && self.view == View::List && self.sidebar_rows().len() > 1;
+    }
+
+    /// Moves the sidebar's selection by `step` through "All tasks", the favorites and the
+    /// projects, skipping headings and wrapping around, and shows the picked project's tasks
+    /// from the top. A favorite label or filter filters the list instead, until another row is
+    /// picked.
+    pub fn shift_sidebar(&mut self, step: isize) {
+        let current = self.sidebar_selected();
+        let rows = self.sidebar_rows();
+        let selectable = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| !matches!(row, SidebarRow::Heading(_)))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let position = selectable.iter().position(|&i| i == current).unwrap_or(0) as isize;
+        let next = selectable[(position + step).rem_euclid(selectable.len() as isize) as usize];
+        let view = match &rows[next] {
+            SidebarRow::Project(_, p) => Some(p.id.clone()),
+            _ => None,
+        };
+        let left_query = rows[current].query().filter(|query| Some(query.as_str()) == self.filter_query());
+        let query = rows[next].query();
+        self.sidebar_row = next;
+        self.show_project(view);
+        // A bad saved query shows in the status and leaves the list as it was
+        let result = match (query, left_query) {
+            (Some(query), _) => self.set_filter(&query),
+            (None, Some(_)) => self.set_filter(""),
+            (None, None) => Ok(()),
+        };
+        if let Err(e) = result {
+            self.status = Some(format!("Invalid filter: {}", e));
+        }
+    }
+
+    /// Shows only the tasks of a project and its sub-projects, or all with `None`, from the top.
+    fn show_project(&mut self, project_id: Option<String>) {
+        self.project_view = project_id;
+        if let Some(id) = self.project_view.clone() {
+            self.request_project_load(&id);
+        }
+        let visible = self.visible_indices().len();
+        self.list_state.select((visible > 0).then_some(0));
+        *self.list_state.offset_mut() = 0;
+    }
+
+    /// Shows or hides archived projects at the bottom of the sidebar, fetching them when shown.
+    /// Failing to fetch them, e.g. offline, only shows in the status.
+    pub async fn toggle_archived_projects(&mut self) {
+        if self.archived_shown.take().is_some() {
+            // A hidden archived project can't stay picked
+            if self.project_view.is_some() && self.project_view().is_none() {
+                self.show_project(None);
+            }
+            return;
+        }
+        match self.archived_projects().await {
+            Ok(archived) if archived.is_empty() => self.status = Some("No archived projects".to_string()),
+            Ok(archived) => self.archived_shown = Some(archived),
+            Err(e) => self.status = Some(format!("Loading archived projects failed: {}", e)),
+        }
+    }
+
+    /// Returns true if the selected task's title doesn't fit the list, week view or planner, so it
+    /// should be shown in full elsewhere.
+    pub fn selected_title_cut(&self) -> bool {
+        match self.view {
+            View::List => self.selected_task().is_some_and(|task| self.row_cache.cuts_title(task)),
+            // Day columns, timeline slots and quadrants are narrow, so these always show it
+            View::Week | View::Day | View::Quadrants => self.selected_task().is_some(),
+        }
+    }
+
+    /// Gets the task under the cursor in the current view.
+    pub fn selected_task(&self) -> Option<&Task> {
+        if self.view == View::Week {
+            let day = self.week_days().into_iter().nth(self.week.day())?.1;
+            return day.get(self.week.row()).copied();
+        }
+        if self.view == View::Day {
+            return self.planner_tasks().get(self.planner.row()).copied();
+        }
+        if self.view == View::Quadrants {
+            let quadrant = self.quadrant_tasks().into_iter().nth(self.quadrants.quadrant())?;
+            return quadrant.get(self.quadrants.row()).copied();
+        }
+        let index = *self.visible_indices().get(self.list_state.selected()?)?;
+        self.tasks.get(index)
+    }
+
+    /// Switches between the list and week views; the day planner and quadrants switch to the week.
+    pub fn toggle_view(&mut self) {
+        self.view = match self.view {
+            View::List | View::Day | View::Quadrants => View::Week,
+            View::Week => View::List,
+        };
+    }
+
+    /// Opens the Eisenhower quadrants, or goes back to the list from them.
+    pub fn toggle_quadrants(&mut self) {
+        self.view = match self.view {
+            View::Quadrants => View::List,
+            _ => View::Quadrants,
+        };
+    }
+
+    /// Gets the quadrant view cursor.
+    pub fn quadrant_view(&self) -> &QuadrantView {
+        &self.quadrants
+    }
+
+    /// Visible open tasks in each Eisenhower quadrant, in grid order.
+    pub fn quadrant_tasks(&self) -> [Vec<&Task>; 4] {
+        let visible = self.visible_indices();
+        quadrants::split(visible.iter().map(|&i| &self.tasks[i]), Local::now().date_naive())
+    }
+
+    /// Moves the quadrant view to the quadrant beside the selected one.
+    pub fn shift_quadrant_column(&mut self) {
+        if self.view == View::Quadrants {
+            self.quadrants.shift_column();
+        }
+    }
+
+    /// Opens the day planner, or goes back to the list from it.
+    pub fn toggle_planner(&mut self) {
+        self.view = match self.view {
+            View::Day => View::List,
+            _ => View::Day,
+        };
+    }
+
+    /// Gets the current view.
+    pub fn view(&self) -> View {
+        self.view
+    }
+
+    /// Gets the week view cursor.
+    pub fn week(&self) -> &WeekView {
+        &self.week
+    }
+
+    /// Each day of the displayed week with its visible tasks.
+    pub fn week_days(&self) -> Vec<(NaiveDate, Vec<&Task>)> {
+        let visible = self.visible_indices();
+        self.week
+            .dates()
+            .into_iter()
+            .map(|date| (date, tasks_on(visible.iter().map(|&i| &self.tasks[i]), date)))
+            .collect()
+    }
+
+    /// Moves the week view's selected day, or the day planner's day.
+    pub fn shift_day(&mut self, days: i64) {
+        match self.view {
+            View::Week => self.week.shift_day(days),
+            View::Day => self.planner.shift_day(days),
+            View::List | View::Quadrants => {}
+        }
+    }
+
+    /// Gets the day planner cursor.
+    pub fn planner(&self) -> &DayPlanner {
+        &self.planner
+    }
+
+    /// Visible tasks due on the planned day, timed ones first.
+    pub fn planner_tasks(&self) -> Vec<&Task> {
+        let visible = self.visible_indices();
+        tasks_on(visible.iter().map(|&i| &self.tasks[i]), self.planner.date())
+    }
+
+    /// Picks up the task selected in the day planner to move it to a time slot, starting from
+    /// its current time, or the next free moment for date-only tasks.
+    pub fn enter_place_mode(&mut self) {
+        if self.view != View::Day {
+            return;
+        }
+        let Some(task) = self.selected_task().filter(|t| !t.is_done()) else {
+            return;
+        };
+        let start = match task.due.as_ref().and_then(|d| d.naive_datetime()) {
+            Some(at) => planner::minute_of_day(at.time()),
+            None => planner::placing_start(self.planner.date(), Local::now().naive_local()),
+        };
+        self.planner.start_placing(task.id, start);
+        self.mode = Mode::Place;
+    }
+
+    /// The task being placed in the day planner, with the start it would get in minutes after
+    /// midnight.
+    pub fn placing(&self) -> Option<(&Task, u32)> {
+        let (id, start) = self.planner.placing()?;
+        Some((self.tasks.iter().find(|t| t.id == id)?, start))
+    }
+
+    /// Moves the task being placed by whole time slots.
+    pub fn shift_place(&mut self, slots: i64) {
+        self.planner.shift_placing(slots);
+    }
+
+    /// Puts the task being placed down. When `apply` is set, its due date gets the chosen time
+    /// in Todoist, keeping the planned day.
+    pub async fn exit_place_mode(&mut self, apply: bool) -> AppResult<()> {
+        self.mode = Mode::Normal;
+        let Some((id, start)) = self.planner.stop_placing() else {
+            return Ok(());
+        };
+        let Some(task) = self.tasks.iter().find(|t| t.id == id).filter(|_| apply) else {
+            return Ok(());
+        };
+        let date = self.planner.date();
+        let at = date.and_time(planner::time_of(start));
+        if task.due.as_ref().and_then(|d| d.naive_datetime()) == Some(at) {
+            return Ok(());
+        }
+        let updated = self.api_client.reschedule_task(&task.todoist_id, date, Some(at)).await?;
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            let before = task.clone();
+            task.due = updated.due;
+            let after = task.clone();
+            self.status = Some(format!("Scheduled \"{}\" at {}", after.title, at.format("%H:%M")));
+            self.mark_changed(id);
+            self.record([(Some(before), Some(after))]);
+        }
+        // Follow the task to its place among the day's timed tasks
+        let row = self.planner_tasks().iter().position(|t| t.id == id);
+        self.planner.select_row(row.unwrap_or(0));
+        Ok(())
+    }
+
+    /// Moves the week view to an earlier or later week.
+    pub fn shift_week(&mut self, weeks: i64) {
+        if self.view == View::Week {
+            self.week.shift_week(weeks);
+        }
+    }
+
+    /// Reschedules the task selected in the week view by `days`, keeping its time of day,
+    /// and follows it to its new day.
+    pub async fn move_selected_task(&mut self, days: i64) -> AppResult<()> {
+        if self.view != View::Week {
+            return Ok(());
+        }
+        let Some(task) = self.selected_task() else {
+            return Ok(());
+        };
+        let (id, todoist_id) = (task.id, task.todoist_id.clone());
+        let Some(due) = task.due.as_ref() else {
+            return Ok(());
+        };
+        let shift = |date: NaiveDate| {
+            if days < 0 {
+                date - Days::new(days.unsigned_abs())
+            } else {
+                date + Days::new(days as u64)
+            }
+        };
+        let Some(date) = due.local_date().map(shift) else {
+            return Ok(());
+        };
+        let datetime = due.naive_datetime().map(|at| shift(at.date()).and_time(at.time()));
+        let updated = self.api_client.reschedule_task(&todoist_id, date, datetime).await?;
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            let before = task.clone();
+            task.due = updated.due;
+            let after = task.clone();
+            self.mark_changed(id);
+            self.record([(Some(before), Some(after))]);
+        }
+        self.week.shift_day(days);
+        let row = self
+            .week_days()
+            .into_iter()
+            .nth(self.week.day())
+            .and_then(|(_, day)| day.iter().position(|t| t.id == id));
+        self.week.select_row(row.unwrap_or(0));
+        Ok(())
+    }
+
+    /// Scrolls to keep the selection within `height` rows and returns the first visible index with
+    /// its rows, laid out in `width` characters, compactly for narrow terminals. Each row comes
+    /// with whether its task is flagged as new, whether it's blocked and whether it needs a nudge.
+    pub fn visible_rows(&mut self, height: usize, width: usize, compact: bool) -> (usize, Vec<(&Row, bool, bool, bool)>) {
+        let mut offset = self.list_state.offset();
+        if let Some(selected) = self.list_state.selected() {
+            if selected < offset {
+                offset = selected;
+            } else if height > 0 && selected >= offset + height {
+                offset = selected + 1 - height;
+            }
+        }
+        let visible = self.visible_indices();
+        offset = offset.min(visible.len().saturating_sub(height));
+        *self.list_state.offset_mut() = offset;
+        let end = (offset + height).min(visible.len());
+        let tasks = visible[offset..end].iter().map(|&i| &self.tasks[i]).collect::<Vec<_>>();
+        let today = Local::now().date_naive();
+        let flags = tasks
+            .iter()
+            .map(|t| (self.is_new(t.id), self.is_blocked(t), self.needs_nudge(t)))
+            .collect::<Vec<_>>();
+        let rows = self.row_cache.rows(&tasks, today, width, compact, &self.projects, &self.labels);
+        let rows = rows
+            .into_iter()
+            .zip(flags)
+            .map(|(row, (new, blocked, nudge))| (row, new, blocked, nudge));
+        (offset, rows.collect())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Task changes from the Sync API holding `tasks` and no projects.
+    fn changes(full_sync: bool, tasks: Vec<Task>, removed: &[&str]) -> TaskChanges {
+        TaskChanges {
+            sync_token: format!("token-{}", tasks.len()),
+            full_sync,
+            tasks,
+            removed: removed.iter().map(|id| id.to_string()).collect(),
+            locations: HashMap::new(),
+            projects: None,
+        }
+    }
+
+    fn task(todoist_id: &str, title: &str) -> Task {
+        NewTask::new(title).preview(todoist_id)
+    }
+
+    #[test]
+    fn delta_sync_merges_changes_into_the_current_tasks() {
+        let mut app = App::demo().unwrap();
+        let mut office = task("2", "Call Bob");
+        office.location = Some("Office".to_string());
+        app.apply_task_changes(changes(true, vec![task("1", "Buy milk"), office, task("3", "Old")], &[]))
+            .unwrap();
+        let bob_id = app.tasks.iter().find(|t| t.todoist_id == "2").unwrap().id;
+
+        app.apply_task_changes(changes(false, vec![task("2", "Call Bob back"), task("4", "New")], &["3"]))
+            .unwrap();
+
+        let titles = app.tasks.iter().map(|t| (t.todoist_id.as_str(), t.title.as_str())).collect::<Vec<_>>();
+        assert_eq!(titles, [("1", "Buy milk"), ("2", "Call Bob back"), ("4", "New")]);
+        let bob = app.tasks.iter().find(|t| t.todoist_id == "2").unwrap();
+        // The changed task keeps its local ID and, with no reminder change, its location
+        assert_eq!(bob.id, bob_id);
+        assert_eq!(bob.location.as_deref(), Some("Office"));
+        assert_eq!(app.cache.load_sync_token().unwrap().as_deref(), Some("token-2"));
+        assert_eq!(app.cache.load_tasks().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn full_sync_replaces_the_current_tasks() {
+        let mut app = App::demo().unwrap();
+        app.apply_task_changes(changes(true, vec![task("1", "Buy milk"), task("2", "Call Bob")], &[]))
+            .unwrap();
+        app.apply_task_changes(changes(true, vec![task("2", "Call Bob")], &[])).unwrap();
+        assert_eq!(app.tasks.len(), 1);
+        assert_eq!(app.tasks[0].todoist_id, "2");
+    }
+
+    /// A demo app holding a task edited offline to "Mine" after it was renamed to "Theirs" in
+    /// Todoist, returning the task's Todoist ID.
+    async fn conflicting_edit() -> (App, String) {
+        let mut app = App::demo().unwrap();
+        app.incremental_sync().await.unwrap();
+        let id = app.tasks[0].id;
+        // An online edit first, so the local copy knows when Todoist last changed it
+        let update = |title: &str| TaskUpdate { content: Some(title.to_string()), ..TaskUpdate::default() };
+        app.update_task(id, update("Online")).await.unwrap();
+        let todoist_id = app.tasks[0].todoist_id.clone();
+        app.api_client.update_task(&todoist_id, &update("Theirs")).await.unwrap();
+        // A change already waiting makes new ones queue too, as when offline
+        app.queued = 1;
+        app.update_task(id, update("Mine")).await.unwrap();
+        (app, todoist_id)
+    }
+
+    /// The title Todoist has for a task.
+    async fn remote_title(app: &App, todoist_id: &str) -> String {
+        let tasks = app.api_client.fetch_tasks().await.unwrap();
+        tasks.into_iter().find(|t| t.todoist_id == todoist_id).unwrap().title
+    }
+
+    #[tokio::test]
+    async fn send_queued_holds_edits_to_tasks_changed_in_todoist() {
+        let (mut app, todoist_id) = conflicting_edit().await;
+        app.send_queued().await.unwrap();
+        assert_eq!(remote_title(&app, &todoist_id).await, "Theirs");
+        assert_eq!(app.queued_changes(), 1);
+        assert!(*app.mode() == Mode::ResolveConflict);
+        let (conflict, count) = app.conflict().unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            conflict.differences(),
+            [("Title", "Mine".to_string(), "Theirs".to_string())]
+        );
+        // The next sync doesn't send them either
+        app.send_queued().await.unwrap();
+        assert_eq!(remote_title(&app, &todoist_id).await, "Theirs");
+        assert_eq!(app.cache.load_conflicts().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn keeping_mine_sends_the_held_edits() {
+        let (mut app, todoist_id) = conflicting_edit().await;
+        app.send_queued().await.unwrap();
+        app.resolve_conflict(1, ConflictChoice::KeepMine).await.unwrap();
+        assert_eq!(remote_title(&app, &todoist_id).await, "Mine");
+        assert_eq!(app.queued_changes(), 0);
+        assert!(app.conflict().is_none());
+        assert!(*app.mode() == Mode::Normal);
+    }
+
+    #[tokio::test]
+    async fn keeping_theirs_drops_the_held_edits() {
+        let (mut app, todoist_id) = conflicting_edit().await;
+        app.send_queued().await.unwrap();
+        app.resolve_conflict(1, ConflictChoice::KeepTheirs).await.unwrap();
+        assert_eq!(app.queued_changes(), 0);
+        assert!(app.cache.load_conflicts().unwrap().is_empty());
+        assert_eq!(app.tasks.iter().find(|t| t.todoist_id == todoist_id).unwrap().title, "Theirs");
+        assert_eq!(remote_title(&app, &todoist_id).await, "Theirs");
+    }
+
+    #[tokio::test]
+    async fn edits_to_tasks_unchanged_in_todoist_are_sent() {
+        let mut app = App::demo().unwrap();
+        app.incremental_sync().await.unwrap();
+        let id = app.tasks[0].id;
+        let update = |title: &str| TaskUpdate { content: Some(title.to_string()), ..TaskUpdate::default() };
+        app.update_task(id, update("Online")).await.unwrap();
+        app.queued = 1;
+        app.update_task(id, update("Mine")).await.unwrap();
+        app.send_queued().await.unwrap();
+        let todoist_id = app.tasks[0].todoist_id.clone();
+        assert_eq!(remote_title(&app, &todoist_id).await, "Mine");
+        assert!(app.conflict().is_none());
+    }
+
+    #[tokio::test]
+    async fn favorite_labels_and_filters_filter_the_list_from_the_sidebar() {
+        let mut app = App::demo().unwrap();
+        app.incremental_sync().await.unwrap();
+        app.sync_projects().await.unwrap();
+        app.sync_labels().await.unwrap();
+        app.sync_filters().await.unwrap();
+        let favorites = app
+            .sidebar_rows()
+            .iter()
+            .skip(2)
+            .take_while(|row| !matches!(row, SidebarRow::Heading(_)))
+            .map(|row| match row {
+                SidebarRow::Project(_, p) => p.name.clone(),
+                row => row.query().unwrap(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(favorites, ["Work", "@deep-work", "today & #Work"]);
+
+        app.shift_sidebar(2);
+        assert_eq!(app.filter_query(), Some("@deep-work"));
+        assert!(app.project_view().is_none());
+        app.shift_sidebar(1);
+        assert_eq!(app.filter_query(), Some("today & #Work"));
+        // Moving on to a project drops the favorite's filter
+        app.shift_sidebar(1);
+        assert_eq!(app.filter_query(), None);
+        assert!(app.project_view().is_some());
+    }
+
+    #[tokio::test]
+    async fn send_queued_gives_links_and_follow_ups_the_new_todoist_id() {
+        let mut app = App::demo().unwrap();
+        // A change already waiting makes new ones queue too, as when offline
+        app.queued = 1;
+        let task = app.create_task(&NewTask::new("Added offline")).await.unwrap().unwrap();
+        let temp_id = task.todoist_id.clone();
+        assert!(sync::is_temp_id(&temp_id));
+        app.cache.add_blocker(&temp_id, "100").unwrap();
+        app.cache.add_blocker("200", &temp_id).unwrap();
+        app.blockers = app.cache.load_blockers().unwrap();
+        let since = Utc::now();
+        app.cache.save_waiting_since(&temp_id, &since.to_rfc3339()).unwrap();
+        app.waiting_since.insert(temp_id.clone(), since);
+
+        app.send_queued().await.unwrap();
+
+        let todoist_id = app.tasks.iter().find(|t| t.id == task.id).unwrap().todoist_id.clone();
+        assert!(!sync::is_temp_id(&todoist_id));
+        let blockers = vec![("200".to_string(), todoist_id.clone()), (todoist_id.clone(), "100".to_string())];
+        let mut in_memory = app.blockers.clone();
+        in_memory.sort();
+        let mut cached = app.cache.load_blockers().unwrap();
+        cached.sort();
+        assert_eq!(in_memory, blockers);
+        assert_eq!(cached, blockers);
+        assert!(app.waiting_since.contains_key(&todoist_id));
+        assert!(app.cache.waiting_since().unwrap().contains_key(&todoist_id));
+        assert_eq!(app.queued_changes(), 0);
+    }
+}
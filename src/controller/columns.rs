@@ -0,0 +1,223 @@
+use crate::models::label::Label;
+use crate::models::project::Project;
+use crate::models::task::Task;
+use crate::utils::color::{Rgb, priority_rgb, todoist_color};
+use crate::utils::text::{pad, truncate, width as text_width};
+use chrono::{Local, NaiveDate};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// The title never shrinks below this many characters while other columns can still give way.
+const MIN_TITLE_WIDTH: usize = 12;
+
+/// A column of the TUI task list.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Checkbox,
+    Priority,
+    Due,
+    Project,
+    Labels,
+    Title,
+    /// Time since the task was created, e.g. "3d" or "2w".
+    Age,
+}
+
+/// How due dates are written in the list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateStyle {
+    /// "today 17:00", "in 3 days".
+    Relative,
+    /// "2024-05-04 17:00".
+    Absolute,
+    /// Abbreviated for narrow terminals, e.g. "tmrw" or "-2d".
+    Compact,
+}
+
+/// Columns kept in the compact layout for narrow terminals.
+const COMPACT_COLUMNS: [Column; 4] = [Column::Checkbox, Column::Priority, Column::Title, Column::Due];
+
+/// Columns shown when none are configured.
+pub fn default_columns() -> Vec<Column> {
+    vec![Column::Checkbox, Column::Priority, Column::Title, Column::Due, Column::Project]
+}
+
+/// Drops the columns that don't fit a compact layout, keeping the configured order.
+pub fn compact_columns(columns: &[Column]) -> Vec<Column> {
+    columns
+        .iter()
+        .copied()
+        .filter(|column| COMPACT_COLUMNS.contains(column))
+        .collect()
+}
+
+impl Column {
+    /// Preferred and minimum widths; `None` for the title, which takes the remaining space.
+    fn widths(self) -> Option<(usize, usize)> {
+        match self {
+            Column::Checkbox => Some((3, 3)),
+            Column::Priority => Some((2, 2)),
+            Column::Age => Some((4, 4)),
+            Column::Due => Some((16, 4)),
+            Column::Project => Some((14, 4)),
+            Column::Labels => Some((16, 4)),
+            Column::Title => None,
+        }
+    }
+
+    /// The unpadded cell text for a task.
+    fn text(self, task: &Task, projects: &[Project], today: NaiveDate, dates: DateStyle) -> String {
+        match self {
+            Column::Checkbox => if task.is_done() { "[x]" } else { "[ ]" }.to_string(),
+            // Normal priority is left blank so the urgent ones stand out
+            Column::Priority => match task.priority {
+                2..=4 => format!("p{}", 5 - task.priority),
+                _ => String::new(),
+            },
+            Column::Due => task
+                .due
+                .as_ref()
+                .map(|due| match dates {
+                    DateStyle::Relative => due.display(today, true),
+                    DateStyle::Absolute => due.display(today, false),
+                    DateStyle::Compact => due.display_compact(today),
+                })
+                .unwrap_or_default(),
+            Column::Project => projects
+                .iter()
+                .find(|p| p.id == task.project_id)
+                .map(|p| p.name.clone())
+                .unwrap_or_default(),
+            Column::Labels => task
+                .labels
+                .iter()
+                .map(|label| format!("@{}", label))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Column::Title => task.title.clone(),
+            Column::Age => task
+                .added()
+                .map(|added| age(today, added.with_timezone(&Local).date_naive()))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Formats the days between two dates as a short age, e.g. "5d", "3w", "11mo" or "2y".
+fn age(today: NaiveDate, added: NaiveDate) -> String {
+    match (today - added).num_days().max(0) {
+        days if days < 14 => format!("{}d", days),
+        days if days < 60 => format!("{}w", days / 7),
+        days if days < 365 => format!("{}mo", days / 30),
+        days => format!("{}y", days / 365),
+    }
+}
+
+/// Assigns a width to each column so a row fits in `width` characters. Columns shrink from
+/// the right towards their minimum before the title goes below its own minimum.
+pub fn layout(columns: &[Column], width: usize) -> Vec<usize> {
+    let available = width.saturating_sub(columns.len().saturating_sub(1));
+    let mut widths = columns
+        .iter()
+        .map(|column| column.widths().map_or(0, |(preferred, _)| preferred))
+        .collect::<Vec<_>>();
+    let title = columns.iter().position(|&c| c == Column::Title);
+    let wanted = widths.iter().sum::<usize>() + title.map_or(0, |_| MIN_TITLE_WIDTH);
+    let mut excess = wanted.saturating_sub(available);
+    for (i, column) in columns.iter().enumerate().rev() {
+        if let Some((preferred, min)) = column.widths() {
+            let cut = (preferred - min).min(excess);
+            widths[i] -= cut;
+            excess -= cut;
+        }
+    }
+    if let Some(title) = title {
+        widths[title] = available.saturating_sub(widths.iter().sum());
+    }
+    widths
+}
+
+/// A rendered list row: its text and the byte ranges drawn in a project's or label's color.
+#[derive(Clone, Default, PartialEq)]
+pub struct Row {
+    pub text: String,
+    pub colors: Vec<(Range<usize>, Rgb)>,
+}
+
+/// Text after the title of an assigned task, e.g. " → Ana", when `people` knows the assignee.
+pub fn assignee_tag(task: &Task, people: &HashMap<String, String>) -> Option<String> {
+    let name = people.get(task.responsible_uid.as_ref()?)?;
+    Some(format!(" → {}", name))
+}
+
+/// Renders a task's cells, each truncated with an ellipsis or padded to its width, coloring the
+/// priority in its flag color and the project and each label chip in their Todoist colors. Assigned tasks get the name of the
+/// assignee from `people` after the title, which gives way first.
+#[allow(clippy::too_many_arguments)]
+pub fn render_row(
+    task: &Task,
+    columns: &[Column],
+    widths: &[usize],
+    projects: &[Project],
+    labels: &[Label],
+    people: &HashMap<String, String>,
+    today: NaiveDate,
+    dates: DateStyle,
+) -> Row {
+    let mut row = Row::default();
+    for (i, (column, &width)) in columns.iter().zip(widths).enumerate() {
+        if i > 0 {
+            row.text.push(' ');
+        }
+        let start = row.text.len();
+        let cell = match column {
+            Column::Title => {
+                // The pin is two cells wide, so it and its space take three of the title's cells
+                let (pin, mut room) = match task.location {
+                    Some(_) if width > 3 => ("📍 ", width - 3),
+                    _ => ("", width),
+                };
+                // Keeps a few characters of the title next to the assignee
+                let tag = assignee_tag(task, people).filter(|tag| room >= text_width(tag) + 4);
+                room -= tag.as_deref().map_or(0, text_width);
+                format!("{}{}{}", pin, truncate(&task.title, room), tag.unwrap_or_default())
+            }
+            _ => truncate(&column.text(task, projects, today, dates), width),
+        };
+        row.text.push_str(&pad(&cell, width));
+        match column {
+            Column::Priority => {
+                if let Some(rgb) = priority_rgb(task.priority) {
+                    row.colors.push((start..start + cell.len(), rgb));
+                }
+            }
+            Column::Project => {
+                let project = projects.iter().find(|p| p.id == task.project_id);
+                if let Some(rgb) = project.and_then(|p| todoist_color(&p.color)) {
+                    row.colors.push((start..start + cell.len(), rgb));
+                }
+            }
+            // Chips keep the order of the task's labels, even when the last ones are cut off
+            Column::Labels => {
+                let mut at = start;
+                for (chip, name) in cell.split(' ').zip(&task.labels) {
+                    let label = labels.iter().find(|l| l.name.eq_ignore_ascii_case(name));
+                    if let Some(rgb) = label.and_then(|l| todoist_color(&l.color)) {
+                        row.colors.push((at..at + chip.len(), rgb));
+                    }
+                    at += chip.len() + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    row.text.truncate(row.text.trim_end().len());
+    let len = row.text.len();
+    row.colors.retain_mut(|(range, _)| {
+        range.end = range.end.min(len);
+        range.start < range.end
+    });
+    row
+}
@@ -0,0 +1,36 @@
+use crate::models::task::Task;
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+
+/// Days until a waiting task needs a follow-up, if it has the waiting `label`: `default_days`
+/// for the label itself, or the days it names with a suffix, e.g. 7 for `waiting-7d`.
+pub fn interval(task: &Task, label: &str, default_days: i64) -> Option<i64> {
+    if label.is_empty() {
+        return None;
+    }
+    task.labels.iter().find_map(|l| {
+        if l.eq_ignore_ascii_case(label) {
+            return Some(default_days);
+        }
+        let suffix = l
+            .get(..label.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(label))
+            .and_then(|_| l.get(label.len()..))?;
+        suffix
+            .strip_prefix('-')?
+            .strip_suffix('d')?
+            .parse()
+            .ok()
+            .filter(|days| *days > 0)
+    })
+}
+
+/// When a task not seen waiting before started waiting: adding the label updated it, so its
+/// last update, else its creation, else now.
+pub fn started_waiting(task: &Task) -> DateTime<Utc> {
+    task.updated().or(task.added()).unwrap_or_else(Utc::now)
+}
+
+/// The local day to follow up on a task that started waiting at `since`, `days` later.
+pub fn follow_up_on(since: DateTime<Utc>, days: i64) -> NaiveDate {
+    since.with_timezone(&Local).date_naive() + Days::new(days.max(0) as u64)
+}
@@ -0,0 +1,114 @@
+use crate::controller::quadrants::{QUADRANTS, Quadrant};
+use crate::models::project::Project;
+use crate::models::task::Task;
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// What to group listed tasks by.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// Projects in Todoist's order
+    Project,
+    /// Labels by name; tasks with several labels appear under each
+    Label,
+    /// p1 first
+    Priority,
+    /// Overdue, Today, Tomorrow, Next 7 days, Later and No date
+    Due,
+    /// Eisenhower quadrants: p1-p2 tasks are important, ones due by tomorrow urgent
+    Quadrant,
+}
+
+/// A titled section of tasks.
+pub struct Group<'a> {
+    pub title: String,
+    pub tasks: Vec<&'a Task>,
+}
+
+/// Splits tasks into groups, keeping their order within each group and leaving out empty groups.
+pub fn group<'a>(
+    tasks: &[&'a Task],
+    by: GroupBy,
+    projects: &[Project],
+    today: NaiveDate,
+) -> Vec<Group<'a>> {
+    let mut groups: Vec<Group<'a>> = Vec::new();
+    let mut add = |title: String, task: &'a Task| match groups.iter_mut().find(|g| g.title == title)
+    {
+        Some(group) => group.tasks.push(task),
+        None => groups.push(Group {
+            title,
+            tasks: vec![task],
+        }),
+    };
+    for &task in tasks {
+        match by {
+            GroupBy::Project => {
+                let name = projects
+                    .iter()
+                    .find(|p| p.id == task.project_id)
+                    .map(|p| p.name.clone());
+                add(name.unwrap_or_else(|| "Unknown project".to_string()), task);
+            }
+            GroupBy::Label if task.labels.is_empty() => add("No label".to_string(), task),
+            GroupBy::Label => {
+                for label in &task.labels {
+                    add(format!("@{}", label), task);
+                }
+            }
+            GroupBy::Priority => add(format!("p{}", 5 - task.priority.clamp(1, 4)), task),
+            GroupBy::Due => add(due_bucket(task, today).to_string(), task),
+            GroupBy::Quadrant => add(Quadrant::of(task, today).title().to_string(), task),
+        }
+    }
+    groups.sort_by_key(|g| rank(g, by, projects));
+    groups
+}
+
+/// Orders groups: projects as Todoist lists them, labels by name, priorities from p1, due
+/// buckets from overdue to undated and quadrants from "Do first". Fallback groups go last.
+fn rank(group: &Group, by: GroupBy, projects: &[Project]) -> (usize, String) {
+    match by {
+        GroupBy::Project => {
+            let position = projects.iter().position(|p| p.name == group.title);
+            (position.unwrap_or(usize::MAX), String::new())
+        }
+        GroupBy::Label if group.title == "No label" => (1, String::new()),
+        GroupBy::Label => (0, group.title.to_lowercase()),
+        GroupBy::Priority => (0, group.title.clone()),
+        GroupBy::Due => {
+            let position = DUE_BUCKETS.iter().position(|b| *b == group.title);
+            (position.unwrap_or(usize::MAX), String::new())
+        }
+        GroupBy::Quadrant => {
+            let position = QUADRANTS.iter().position(|q| q.title() == group.title);
+            (position.unwrap_or(usize::MAX), String::new())
+        }
+    }
+}
+
+/// Due date groups in display order.
+const DUE_BUCKETS: [&str; 6] = [
+    "Overdue",
+    "Today",
+    "Tomorrow",
+    "Next 7 days",
+    "Later",
+    "No date",
+];
+
+/// Names the due date group of a task.
+fn due_bucket(task: &Task, today: NaiveDate) -> &'static str {
+    let Some(date) = task.due.as_ref().and_then(|due| due.local_date()) else {
+        return "No date";
+    };
+    match (date - today).num_days() {
+        days if days < 0 => "Overdue",
+        0 => "Today",
+        1 => "Tomorrow",
+        2..=7 => "Next 7 days",
+        _ => "Later",
+    }
+}
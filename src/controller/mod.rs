@@ -1 +1,9 @@
-pub mod app;
\ No newline at end of file
+pub mod app;
+pub mod columns;
+pub mod followups;
+pub mod groups;
+pub mod planner;
+pub mod quadrants;
+pub mod rows;
+pub mod trends;
+pub mod week;
\ No newline at end of file
@@ -0,0 +1,162 @@
+use crate::models::task::Task;
+use chrono::{Days, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+/// Length of a timeline slot in minutes, and the step a task moves by while placing it.
+pub const SLOT_MINUTES: u32 = 30;
+
+/// Length assumed for timed tasks without a duration.
+pub const DEFAULT_DURATION: u32 = 30;
+
+/// Hours the timeline covers at least, from the start of the first to the end of the last.
+const WORKING_HOURS: (u32, u32) = (8, 18);
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Cursor state of the TUI day planner.
+pub struct DayPlanner {
+    date: NaiveDate,
+    /// Selected task among the day's tasks, timed ones first.
+    row: usize,
+    /// Local ID of the task being placed and the start, in minutes after midnight, it would get.
+    placing: Option<(usize, u32)>,
+}
+
+impl DayPlanner {
+    /// Starts on `today` with its first task selected.
+    pub fn new(today: NaiveDate) -> Self {
+        DayPlanner { date: today, row: 0, placing: None }
+    }
+
+    /// The planned date.
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Moves to an earlier or later day.
+    pub fn shift_day(&mut self, days: i64) {
+        self.date = if days < 0 {
+            self.date - Days::new(days.unsigned_abs())
+        } else {
+            self.date + Days::new(days as u64)
+        };
+        self.row = 0;
+    }
+
+    /// Moves the selected row, wrapping around `count` tasks.
+    pub fn shift_row(&mut self, rows: i64, count: usize) {
+        if count > 0 {
+            self.row = (self.row as i64 + rows).rem_euclid(count as i64) as usize;
+        }
+    }
+
+    /// Selects a row directly, e.g. to follow a task that moved.
+    pub fn select_row(&mut self, row: usize) {
+        self.row = row;
+    }
+
+    /// The task being placed and its start in minutes after midnight.
+    pub fn placing(&self) -> Option<(usize, u32)> {
+        self.placing
+    }
+
+    /// Picks up a task to place it, starting at `start` minutes after midnight.
+    pub fn start_placing(&mut self, task: usize, start: u32) {
+        self.placing = Some((task, snap(start).min(MINUTES_PER_DAY - SLOT_MINUTES)));
+    }
+
+    /// Moves the task being placed by whole slots, within the day.
+    pub fn shift_placing(&mut self, slots: i64) {
+        if let Some((_, start)) = &mut self.placing {
+            let moved = *start as i64 + slots * SLOT_MINUTES as i64;
+            *start = moved.clamp(0, (MINUTES_PER_DAY - SLOT_MINUTES) as i64) as u32;
+        }
+    }
+
+    /// Puts the task being placed down, returning it with its chosen start.
+    pub fn stop_placing(&mut self) -> Option<(usize, u32)> {
+        self.placing.take()
+    }
+}
+
+/// A timed task on the timeline.
+pub struct TimeBlock<'a> {
+    pub task: &'a Task,
+    /// Minutes after midnight.
+    pub start: u32,
+    pub minutes: u32,
+}
+
+/// Splits a day's tasks, as ordered by `week::tasks_on`, into blocks for the timed ones and
+/// the date-only rest. A task being placed is shown at its new start.
+pub fn time_blocks<'a>(tasks: &[&'a Task], placing: Option<(usize, u32)>) -> (Vec<TimeBlock<'a>>, Vec<&'a Task>) {
+    let mut blocks = Vec::new();
+    let mut unscheduled = Vec::new();
+    for task in tasks {
+        let time = task.due.as_ref().and_then(|d| d.naive_datetime()).map(|at| at.time());
+        let start = match placing {
+            Some((id, start)) if id == task.id => Some(start),
+            _ => time.map(minute_of_day),
+        };
+        match start {
+            Some(start) => blocks.push(TimeBlock {
+                task,
+                start,
+                minutes: task.duration.unwrap_or(DEFAULT_DURATION).max(1),
+            }),
+            None => unscheduled.push(*task),
+        }
+    }
+    blocks.sort_by_key(|b| b.start);
+    (blocks, unscheduled)
+}
+
+/// Starts of the timeline's slots: working hours, widened to whole hours around any blocks
+/// outside them.
+pub fn slots(blocks: &[TimeBlock]) -> Vec<u32> {
+    let first = blocks.iter().map(|b| b.start / 60).min().unwrap_or(WORKING_HOURS.0);
+    let last = blocks
+        .iter()
+        .map(|b| (b.start + b.minutes).min(MINUTES_PER_DAY).div_ceil(60))
+        .max()
+        .unwrap_or(WORKING_HOURS.1);
+    let (first, last) = (first.min(WORKING_HOURS.0), last.max(WORKING_HOURS.1));
+    (first * 60..last * 60).step_by(SLOT_MINUTES as usize).collect()
+}
+
+/// Where a date-only task starts out while placing it: the next slot when planning today,
+/// else the start of working hours.
+pub fn placing_start(date: NaiveDate, now: NaiveDateTime) -> u32 {
+    if date == now.date() {
+        snap(minute_of_day(now.time())) + SLOT_MINUTES
+    } else {
+        WORKING_HOURS.0 * 60
+    }
+}
+
+/// Rounds minutes after midnight down to the start of their slot.
+pub fn snap(minutes: u32) -> u32 {
+    minutes - minutes % SLOT_MINUTES
+}
+
+/// Minutes after midnight of a time of day.
+pub fn minute_of_day(time: NaiveTime) -> u32 {
+    time.hour() * 60 + time.minute()
+}
+
+/// Time of day `minutes` after midnight.
+pub fn time_of(minutes: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(minutes / 60 % 24, minutes % 60, 0).unwrap_or_default()
+}
+
+/// Formats a length of time, e.g. "45m", "2h" or "1h 30m".
+pub fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
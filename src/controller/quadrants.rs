@@ -0,0 +1,115 @@
+use crate::models::task::Task;
+use chrono::NaiveDate;
+
+/// Days ahead within which a due date or deadline makes a task urgent; 1 includes tomorrow.
+pub const URGENT_DAYS: i64 = 1;
+
+/// A quadrant of the Eisenhower matrix: important tasks are p1 or p2, and urgent ones are due,
+/// or have a deadline, no later than `URGENT_DAYS` from today.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Quadrant {
+    DoFirst,
+    Schedule,
+    Delegate,
+    Drop,
+}
+
+/// Quadrants in grid order: important on top, urgent on the left.
+pub const QUADRANTS: [Quadrant; 4] = [Quadrant::DoFirst, Quadrant::Schedule, Quadrant::Delegate, Quadrant::Drop];
+
+impl Quadrant {
+    /// The quadrant a task falls in as of `today`.
+    pub fn of(task: &Task, today: NaiveDate) -> Self {
+        match (is_important(task), is_urgent(task, today)) {
+            (true, true) => Quadrant::DoFirst,
+            (true, false) => Quadrant::Schedule,
+            (false, true) => Quadrant::Delegate,
+            (false, false) => Quadrant::Drop,
+        }
+    }
+
+    /// Heading with what the quadrant holds, e.g. "Do first: urgent & important".
+    pub fn title(self) -> &'static str {
+        match self {
+            Quadrant::DoFirst => "Do first: urgent & important",
+            Quadrant::Schedule => "Schedule: important, not urgent",
+            Quadrant::Delegate => "Delegate: urgent, not important",
+            Quadrant::Drop => "Drop: neither",
+        }
+    }
+
+    /// Position in `QUADRANTS`.
+    pub fn index(self) -> usize {
+        QUADRANTS.iter().position(|q| *q == self).unwrap_or(0)
+    }
+}
+
+/// Returns true for p1 and p2 tasks.
+pub fn is_important(task: &Task) -> bool {
+    task.priority >= 3
+}
+
+/// Returns true if the task is overdue, or its due date or deadline is within `URGENT_DAYS`.
+pub fn is_urgent(task: &Task, today: NaiveDate) -> bool {
+    let due = task.due.as_ref().and_then(|d| d.local_date());
+    let deadline = task.deadline.as_deref().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    [due, deadline]
+        .into_iter()
+        .flatten()
+        .any(|date| (date - today).num_days() <= URGENT_DAYS)
+}
+
+/// Splits the open tasks into the four quadrants in grid order, keeping their order within each.
+pub fn split<'a>(tasks: impl Iterator<Item = &'a Task>, today: NaiveDate) -> [Vec<&'a Task>; 4] {
+    let mut quadrants: [Vec<&Task>; 4] = Default::default();
+    for task in tasks.filter(|t| !t.is_done()) {
+        quadrants[Quadrant::of(task, today).index()].push(task);
+    }
+    quadrants
+}
+
+/// Cursor state of the TUI quadrant view, starting on the first task to do first.
+#[derive(Default)]
+pub struct QuadrantView {
+    /// Selected quadrant, an index into `QUADRANTS`.
+    quadrant: usize,
+    /// Selected task within the quadrant.
+    row: usize,
+}
+
+impl QuadrantView {
+    pub fn quadrant(&self) -> usize {
+        self.quadrant
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Moves to the quadrant beside the selected one, left or right.
+    pub fn shift_column(&mut self) {
+        self.quadrant ^= 1;
+        self.row = 0;
+    }
+
+    /// Moves the selected row by `rows`, given how many tasks each quadrant has. Moving past
+    /// the last task goes to the quadrant below, and before the first to the one above.
+    pub fn shift_row(&mut self, rows: i64, counts: [usize; 4]) {
+        let row = self.row as i64 + rows;
+        let count = counts[self.quadrant] as i64;
+        if (0..count).contains(&row) {
+            self.row = row as usize;
+        } else if row >= count && self.quadrant < 2 {
+            self.quadrant += 2;
+            self.row = 0;
+        } else if row < 0 && self.quadrant >= 2 {
+            self.quadrant -= 2;
+            self.row = counts[self.quadrant].saturating_sub(1);
+        }
+    }
+
+    /// Selects a row directly, e.g. to follow a task that moved.
+    pub fn select_row(&mut self, row: usize) {
+        self.row = row;
+    }
+}
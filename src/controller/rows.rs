@@ -0,0 +1,171 @@
+use crate::controller::columns::{
+    Column, DateStyle, Row, assignee_tag, compact_columns, layout, render_row,
+};
+use crate::models::label::Label;
+use crate::models::project::Project;
+use crate::models::task::Task;
+use crate::utils::color::{CYAN, RED, Rgb, paint, priority_color, todoist_color};
+use crate::utils::text::width;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+/// A formatted list row and the task it was built from.
+struct CachedRow {
+    task: Task,
+    row: Row,
+}
+
+/// Caches formatted task rows by task id so unchanged tasks aren't re-formatted every frame.
+pub struct RowCache {
+    rows: HashMap<usize, CachedRow>,
+    columns: Vec<Column>,
+    relative_dates: bool,
+    /// Collaborator names by user ID, for the assignee after the title.
+    people: HashMap<String, String>,
+    /// The day the cached due dates were formatted against.
+    today: Option<NaiveDate>,
+    /// Row width and layout the cached rows were built for, with the resulting columns and widths.
+    width: usize,
+    compact: bool,
+    shown: Vec<Column>,
+    widths: Vec<usize>,
+}
+
+impl RowCache {
+    /// Creates an empty cache for rows made of `columns`, formatting due dates relatively when `relative_dates` is set.
+    pub fn new(columns: Vec<Column>, relative_dates: bool) -> Self {
+        RowCache {
+            rows: HashMap::new(),
+            columns,
+            relative_dates,
+            people: HashMap::new(),
+            today: None,
+            width: 0,
+            compact: false,
+            shown: Vec::new(),
+            widths: Vec::new(),
+        }
+    }
+
+    /// Returns the rows for `tasks` laid out in `width` characters, formatting only tasks that are new or have changed.
+    /// The `compact` layout keeps only the essential columns and abbreviates due dates.
+    pub fn rows(
+        &mut self,
+        tasks: &[&Task],
+        today: NaiveDate,
+        width: usize,
+        compact: bool,
+        projects: &[Project],
+        labels: &[Label],
+    ) -> Vec<&Row> {
+        // Relative dates go stale at midnight
+        if self.today != Some(today) {
+            self.rows.clear();
+            self.today = Some(today);
+        }
+        if self.width != width || self.compact != compact || self.shown.is_empty() {
+            self.rows.clear();
+            self.width = width;
+            self.compact = compact;
+            self.shown = if compact {
+                compact_columns(&self.columns)
+            } else {
+                self.columns.clone()
+            };
+            self.widths = layout(&self.shown, width);
+        }
+        let dates = match (compact, self.relative_dates) {
+            (true, _) => DateStyle::Compact,
+            (false, true) => DateStyle::Relative,
+            (false, false) => DateStyle::Absolute,
+        };
+        for task in tasks {
+            let fresh = self.rows.get(&task.id).is_some_and(|row| row.task == **task);
+            if !fresh {
+                let row = render_row(
+                    task,
+                    &self.shown,
+                    &self.widths,
+                    projects,
+                    labels,
+                    &self.people,
+                    today,
+                    dates,
+                );
+                self.rows.insert(
+                    task.id,
+                    CachedRow {
+                        task: (*task).clone(),
+                        row,
+                    },
+                );
+            }
+        }
+        tasks
+            .iter()
+            .map(|task| &self.rows[&task.id].row)
+            .collect()
+    }
+
+    /// Returns true if the current layout cuts off the title of `task`, or has no title column.
+    pub fn cuts_title(&self, task: &Task) -> bool {
+        if self.shown.is_empty() {
+            return false;
+        }
+        let Some(column) = self.shown.iter().position(|&c| c == Column::Title) else {
+            return true;
+        };
+        // The location pin and its space take three columns
+        let pin = if task.location.is_some() { 3 } else { 0 };
+        let tag = assignee_tag(task, &self.people).map_or(0, |tag| width(&tag));
+        width(&task.title) + pin + tag > self.widths[column]
+    }
+
+    /// Sets the names shown for assignees, re-formatting the rows.
+    pub fn set_people(&mut self, people: HashMap<String, String>) {
+        self.people = people;
+        self.rows.clear();
+    }
+
+    /// Drops every cached row, e.g. after the task list, projects or labels are replaced by a sync.
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+}
+
+/// Formats a task for CLI output as `[ ] title p1 #Project (due)`, with the place of a location
+/// reminder after the title. Priorities, the project (in its Todoist color) and overdue dates are
+/// colored when `color` is set;
+/// the project is left out when `projects` doesn't know it.
+pub fn format_row(
+    task: &Task,
+    projects: &[Project],
+    today: NaiveDate,
+    relative_dates: bool,
+    color: bool,
+) -> String {
+    let status = if task.is_done() { "[x]" } else { "[ ]" };
+    let mut row = format!("{} {}", status, task.title);
+    if let Some(place) = &task.location {
+        row.push_str(&format!(" 📍 {}", place));
+    }
+    if let Some(priority_color) = priority_color(task.priority) {
+        let priority = format!("p{}", 5 - task.priority);
+        row.push_str(&format!(" {}", paint(&priority, priority_color, color)));
+    }
+    if let Some(project) = projects.iter().find(|p| p.id == task.project_id) {
+        let project_color = project_color(project);
+        row.push_str(&format!(" {}", paint(&format!("#{}", project.name), &project_color, color)));
+    }
+    if let Some(due) = &task.due {
+        let due_text = format!("({})", due.display(today, relative_dates));
+        let overdue = task.is_overdue(today);
+        row.push_str(&format!(" {}", paint(&due_text, RED, color && overdue)));
+    }
+    row
+}
+
+/// ANSI color of a project in CLI output: its Todoist color, or cyan when it has none.
+pub fn project_color(project: &Project) -> String {
+    todoist_color(&project.color).map_or_else(|| CYAN.to_string(), Rgb::ansi)
+}
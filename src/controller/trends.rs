@@ -0,0 +1,99 @@
+use crate::models::project::Project;
+use crate::models::task::Task;
+use chrono::{Datelike, Days, Local, NaiveDate};
+
+/// Weeks the burndown and velocity charts cover, including the current one.
+pub const WEEKS: usize = 8;
+
+/// Bars of a text sparkline, from lowest to highest.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One week of a project's trend.
+pub struct WeekPoint {
+    /// Monday of the week.
+    pub start: NaiveDate,
+    /// Tasks completed during the week: the velocity.
+    pub completed: usize,
+    /// Tasks open at the end of the week, or now for the current one: the burndown.
+    pub open: usize,
+}
+
+/// Weekly completions and open tasks of one project, oldest week first.
+pub struct ProjectTrend {
+    pub project: String,
+    pub weeks: Vec<WeekPoint>,
+}
+
+/// Builds per-project trends over the last `weeks` weeks from the cached tasks and the
+/// completed-task history. Tasks without a creation time count as open from the start, and
+/// projects with neither completions nor open tasks in the period are left out. Projects not
+/// in `projects` go by their ID, after the known ones.
+pub fn project_trends(
+    tasks: &[Task],
+    history: &[Task],
+    projects: &[Project],
+    today: NaiveDate,
+    weeks: usize,
+) -> Vec<ProjectTrend> {
+    let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let starts = (0..weeks as u64)
+        .rev()
+        .map(|i| monday - Days::new(7 * i))
+        .collect::<Vec<_>>();
+    // A task archived by `cleanup` may still be in the cache until the next sync
+    let all = tasks
+        .iter()
+        .chain(history.iter().filter(|h| !tasks.iter().any(|t| t.todoist_id == h.todoist_id)))
+        .collect::<Vec<_>>();
+    let mut ids = projects.iter().map(|p| &p.id).collect::<Vec<_>>();
+    for task in &all {
+        if !ids.contains(&&task.project_id) {
+            ids.push(&task.project_id);
+        }
+    }
+    let mut trends = Vec::new();
+    for id in ids {
+        let project_tasks = all.iter().filter(|t| &t.project_id == id).collect::<Vec<_>>();
+        let points = starts
+            .iter()
+            .map(|&start| {
+                let end = (start + Days::new(6)).min(today);
+                WeekPoint {
+                    start,
+                    completed: project_tasks
+                        .iter()
+                        .filter(|t| closed_on(t).is_some_and(|day| day >= start && day <= end))
+                        .count(),
+                    open: project_tasks
+                        .iter()
+                        .filter(|t| t.added().is_none_or(|at| at.with_timezone(&Local).date_naive() <= end))
+                        .filter(|t| match closed_on(t) {
+                            Some(day) => day > end,
+                            None => !t.is_done(),
+                        })
+                        .count(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if points.iter().any(|p| p.completed > 0 || p.open > 0) {
+            let name = projects.iter().find(|p| &p.id == id).map_or(id, |p| &p.name);
+            trends.push(ProjectTrend { project: name.clone(), weeks: points });
+        }
+    }
+    trends
+}
+
+/// The local day a task was completed, if it is still done.
+fn closed_on(task: &Task) -> Option<NaiveDate> {
+    task.completed_on().filter(|_| task.is_done())
+}
+
+/// Draws values as a line of bars scaled to the largest one, e.g. "▁▃█▅"; the lowest bar
+/// also stands for zero.
+pub fn sparkline(values: &[usize]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| BARS[v * (BARS.len() - 1) / max.max(1)])
+        .collect()
+}
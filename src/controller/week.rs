@@ -0,0 +1,83 @@
+use crate::models::task::Task;
+use chrono::{Datelike, Days, NaiveDate};
+
+/// Cursor state of the TUI week view.
+pub struct WeekView {
+    /// Monday of the displayed week.
+    start: NaiveDate,
+    /// Selected day, 0 (Monday) to 6 (Sunday).
+    day: usize,
+    /// Selected task within the day.
+    row: usize,
+}
+
+impl WeekView {
+    /// Starts on the week containing `today`, with today selected.
+    pub fn new(today: NaiveDate) -> Self {
+        let day = today.weekday().num_days_from_monday() as usize;
+        WeekView {
+            start: today - Days::new(day as u64),
+            day,
+            row: 0,
+        }
+    }
+
+    /// The seven dates of the displayed week.
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        (0..7).map(|i| self.start + Days::new(i)).collect()
+    }
+
+    /// The selected date.
+    pub fn selected_date(&self) -> NaiveDate {
+        self.start + Days::new(self.day as u64)
+    }
+
+    pub fn day(&self) -> usize {
+        self.day
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Moves the selected day, crossing into neighboring weeks at the edges.
+    pub fn shift_day(&mut self, days: i64) {
+        let target = self.day as i64 + days;
+        let weeks = target.div_euclid(7);
+        self.shift_week(weeks);
+        self.day = target.rem_euclid(7) as usize;
+        self.row = 0;
+    }
+
+    /// Moves the displayed week forwards or backwards, keeping the selected weekday.
+    pub fn shift_week(&mut self, weeks: i64) {
+        let days = weeks.unsigned_abs() * 7;
+        self.start = if weeks < 0 {
+            self.start - Days::new(days)
+        } else {
+            self.start + Days::new(days)
+        };
+        self.row = 0;
+    }
+
+    /// Moves the selected row within the day, wrapping around `count` tasks.
+    pub fn shift_row(&mut self, rows: i64, count: usize) {
+        if count > 0 {
+            self.row = (self.row as i64 + rows).rem_euclid(count as i64) as usize;
+        }
+    }
+
+    /// Selects a row directly, e.g. to follow a task that moved.
+    pub fn select_row(&mut self, row: usize) {
+        self.row = row;
+    }
+}
+
+/// Tasks due on `date`, ordered by time with date-only tasks last.
+pub fn tasks_on<'a>(tasks: impl Iterator<Item = &'a Task>, date: NaiveDate) -> Vec<&'a Task> {
+    let mut day = tasks
+        .filter(|t| t.due.as_ref().and_then(|d| d.local_date()) == Some(date))
+        .collect::<Vec<_>>();
+    day.sort_by_key(|t| t.due.as_ref().and_then(|d| d.sort_key()));
+    day
+}
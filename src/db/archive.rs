@@ -0,0 +1,71 @@
+//! Tasks of projects archived or deleted in Todoist, saved as JSON files before a sync drops
+//! them from the cache, and read back with `archive list` and `archive show`.
+
+use crate::models::task::Task;
+use crate::utils::error::AppResult;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Prefix of archive file names, followed by a local timestamp so they sort oldest first.
+const PREFIX: &str = "project-";
+
+/// A project's tasks as they were in the cache when it went away.
+#[derive(Serialize, Deserialize)]
+pub struct ProjectArchive {
+    pub project_id: String,
+    /// Project name, or its ID when the name wasn't cached.
+    pub project: String,
+    /// When the tasks were saved, in RFC 3339.
+    pub archived_at: String,
+    pub tasks: Vec<Task>,
+}
+
+/// Lists the archive files in `dir`, oldest first; none when the directory doesn't exist yet.
+pub fn list(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut archives = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    archives.retain(|path| {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        name.starts_with(PREFIX) && name.ends_with(".json")
+    });
+    archives.sort();
+    Ok(archives)
+}
+
+/// Writes a project's tasks to a new file in `dir`, named after the time and the project ID,
+/// and returns it.
+pub fn save(dir: &Path, archive: &ProjectArchive) -> AppResult<PathBuf> {
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("{}{}-{}.json", PREFIX, stamp, archive.project_id));
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&path, serde_json::to_string_pretty(archive)?)?;
+    Ok(path)
+}
+
+/// Reads an archive file.
+pub fn load(path: &Path) -> AppResult<ProjectArchive> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Can't read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("{} isn't a project archive: {}", path.display(), e))
+}
+
+/// Finds an archive by file path, or else the newest one of the project with this name or ID.
+pub fn find(dir: &Path, file_or_project: &str) -> AppResult<(PathBuf, ProjectArchive)> {
+    let path = Path::new(file_or_project);
+    if path.is_file() {
+        return Ok((path.to_path_buf(), load(path)?));
+    }
+    for path in list(dir)?.into_iter().rev() {
+        let archive = load(&path)?;
+        if archive.project.eq_ignore_ascii_case(file_or_project) || archive.project_id == file_or_project {
+            return Ok((path, archive));
+        }
+    }
+    anyhow::bail!("No archive of '{}' in {}", file_or_project, dir.display())
+}
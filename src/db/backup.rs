@@ -0,0 +1,83 @@
+//! Rotating snapshots of the cache, taken after syncs and restored with `backup restore`.
+
+use crate::config::settings::{BackupInterval, BackupSettings};
+use crate::db::cache::{CACHE_PATH, Cache};
+use crate::utils::error::AppResult;
+use chrono::Local;
+use rusqlite::{Connection, OpenFlags};
+use std::path::{Path, PathBuf};
+
+/// Prefix of snapshot file names, followed by a local timestamp so they sort oldest first.
+const PREFIX: &str = "tasks-";
+
+/// Lists the snapshots in `dir`, oldest first; none when the directory doesn't exist yet.
+pub fn list(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshots = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    snapshots.retain(|path| {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        name.starts_with(PREFIX) && name.ends_with(".db")
+    });
+    snapshots.sort();
+    Ok(snapshots)
+}
+
+/// Snapshots the cache and deletes the oldest snapshots beyond `keep`. Unless `force` is set,
+/// a daily schedule skips the snapshot when one was already taken today. Returns the new file.
+pub fn snapshot(cache: &Cache, settings: &BackupSettings, force: bool) -> AppResult<Option<PathBuf>> {
+    let now = Local::now();
+    let existing = list(&settings.dir)?;
+    let today = format!("{}{}", PREFIX, now.format("%Y%m%d"));
+    let taken_today = existing
+        .iter()
+        .any(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(&today)));
+    if !force && settings.every == BackupInterval::Day && taken_today {
+        return Ok(None);
+    }
+    let path = settings.dir.join(format!("{}{}.db", PREFIX, now.format("%Y%m%d-%H%M%S")));
+    // Two syncs within a second make the same snapshot
+    if path.exists() {
+        return Ok(None);
+    }
+    std::fs::create_dir_all(&settings.dir)?;
+    cache.backup_to(&path)?;
+
+    let snapshots = list(&settings.dir)?;
+    let excess = snapshots.len().saturating_sub(settings.keep.max(1));
+    for old in &snapshots[..excess] {
+        std::fs::remove_file(old)?;
+    }
+    Ok(Some(path))
+}
+
+/// Replaces the cache with a snapshot after checking it's an intact task cache, first saving
+/// the current cache as a new snapshot, which is returned. The app must not have the cache open.
+pub fn restore(file: &Path, settings: &BackupSettings) -> AppResult<Option<PathBuf>> {
+    let conn = Connection::open_with_flags(file, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| anyhow::anyhow!("Can't open {}: {}", file.display(), e))?;
+    let is_cache = conn
+        .query_row("SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'tasks'", [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .is_ok_and(|count| count > 0);
+    if !is_cache {
+        anyhow::bail!("{} isn't a todoist-cli cache", file.display());
+    }
+    let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+    if integrity != "ok" {
+        anyhow::bail!("{} is damaged: {}", file.display(), integrity);
+    }
+    drop(conn);
+    // Read first, since rotating in the new snapshot may delete the file being restored
+    let contents = std::fs::read(file)?;
+    let saved = match Path::new(CACHE_PATH).exists() {
+        true => snapshot(&Cache::new()?, settings, true)?,
+        false => None,
+    };
+    std::fs::write(CACHE_PATH, contents)?;
+    Ok(saved)
+}
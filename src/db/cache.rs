@@ -1,69 +1,1122 @@
-use crate::models::task::Task;
+use crate::api::metrics::{EndpointStats, Usage};
+use crate::api::sync::SyncCommand;
+use crate::models::conflict::Conflict;
+use crate::models::event::AuditEntry;
+use crate::models::label::Label;
+use crate::models::project::Project;
+use crate::models::review::WeeklyReview;
+use crate::models::saved_filter::SavedFilter;
+use crate::models::task::{Due, Task};
+use crate::models::template::Template;
 use crate::utils::error::AppResult;
-use rusqlite::{Connection, params};
+use chrono::{NaiveDate, Utc};
+use rusqlite::{Connection, OpenFlags, params};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Columns added to the tasks table after the initial schema, with the schema version that
+/// added each and their declarations.
+const TASK_COLUMNS: &[(i32, &str, &str)] = &[
+    (1, "due_date", "TEXT"),
+    (1, "due_datetime", "TEXT"),
+    (1, "due_string", "TEXT"),
+    (1, "due_recurring", "INTEGER NOT NULL DEFAULT 0"),
+    (1, "deadline", "TEXT"),
+    (1, "labels", "TEXT NOT NULL DEFAULT '[]'"),
+    (1, "priority", "INTEGER NOT NULL DEFAULT 1"),
+    (1, "project_id", "TEXT NOT NULL DEFAULT ''"),
+    (2, "parent_id", "TEXT"),
+    (3, "completed_at", "TEXT"),
+    (4, "added_at", "TEXT"),
+    (5, "updated_at", "TEXT"),
+    (6, "location", "TEXT"),
+    (8, "description", "TEXT NOT NULL DEFAULT ''"),
+    (12, "responsible_uid", "TEXT"),
+    (13, "duration", "INTEGER"),
+];
+
+/// Tables added after the initial schema, with the schema version that added each.
+const TABLES: &[(i32, &str)] = &[
+    (
+        1,
+        "CREATE TABLE IF NOT EXISTS notified (
+            todoist_id TEXT NOT NULL,
+            due TEXT NOT NULL,
+            notified_at TEXT NOT NULL,
+            PRIMARY KEY (todoist_id, due)
+        )",
+    ),
+    (
+        1,
+        "CREATE TABLE IF NOT EXISTS linked_issues (
+            todoist_id TEXT PRIMARY KEY,
+            url TEXT NOT NULL
+        )",
+    ),
+    (
+        2,
+        "CREATE TABLE IF NOT EXISTS templates (
+            name TEXT PRIMARY KEY,
+            body TEXT NOT NULL
+        )",
+    ),
+    (
+        3,
+        "CREATE TABLE IF NOT EXISTS history (
+            todoist_id TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            completed_at TEXT,
+            archived_at TEXT NOT NULL,
+            task TEXT NOT NULL
+        )",
+    ),
+    (
+        5,
+        "CREATE TABLE IF NOT EXISTS reviewed (
+            todoist_id TEXT PRIMARY KEY,
+            reviewed_at TEXT NOT NULL
+        )",
+    ),
+    (
+        9,
+        "CREATE TABLE IF NOT EXISTS api_stats (
+            session TEXT NOT NULL,
+            endpoint TEXT NOT NULL,
+            requests INTEGER NOT NULL,
+            errors INTEGER NOT NULL,
+            total_ms INTEGER NOT NULL,
+            PRIMARY KEY (session, endpoint)
+        )",
+    ),
+    (
+        10,
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            at TEXT NOT NULL,
+            todoist_id TEXT NOT NULL,
+            entry TEXT NOT NULL
+        )",
+    ),
+    (
+        11,
+        "CREATE TABLE IF NOT EXISTS remote_changes (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            todoist_id TEXT NOT NULL,
+            entry TEXT NOT NULL,
+            seen INTEGER NOT NULL DEFAULT 0
+        )",
+    ),
+    (
+        14,
+        "CREATE TABLE IF NOT EXISTS weekly_review (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            progress TEXT NOT NULL
+        )",
+    ),
+    (
+        15,
+        "CREATE TABLE IF NOT EXISTS projects (
+            position INTEGER PRIMARY KEY,
+            project TEXT NOT NULL
+        )",
+    ),
+    (
+        16,
+        "CREATE TABLE IF NOT EXISTS blocked_by (
+            todoist_id TEXT NOT NULL,
+            blocker_id TEXT NOT NULL,
+            PRIMARY KEY (todoist_id, blocker_id)
+        )",
+    ),
+    (
+        17,
+        "CREATE TABLE IF NOT EXISTS operations (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            queued_at TEXT NOT NULL,
+            command TEXT NOT NULL
+        )",
+    ),
+    (
+        18,
+        "CREATE TABLE IF NOT EXISTS sync_state (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            sync_token TEXT NOT NULL
+        )",
+    ),
+    (
+        19,
+        "CREATE TABLE IF NOT EXISTS waiting (
+            todoist_id TEXT PRIMARY KEY,
+            since TEXT NOT NULL
+        )",
+    ),
+    (
+        20,
+        "CREATE TABLE IF NOT EXISTS labels (
+            position INTEGER PRIMARY KEY,
+            label TEXT NOT NULL
+        )",
+    ),
+    (
+        20,
+        "CREATE TABLE IF NOT EXISTS daily_goal (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            goal INTEGER NOT NULL,
+            date TEXT NOT NULL,
+            completed INTEGER NOT NULL
+        )",
+    ),
+    (
+        21,
+        "CREATE TABLE IF NOT EXISTS conflicts (
+            todoist_id TEXT PRIMARY KEY,
+            mine TEXT NOT NULL,
+            theirs TEXT NOT NULL
+        )",
+    ),
+    (
+        22,
+        "CREATE TABLE IF NOT EXISTS filters (
+            position INTEGER PRIMARY KEY,
+            filter TEXT NOT NULL
+        )",
+    ),
+];
+
+/// The cache file, in the working directory.
+pub const CACHE_PATH: &str = "tasks.db";
+
+/// Schema version stored in `PRAGMA user_version`; bump when adding migrations, which only run
+/// on caches older than their version.
+pub const SCHEMA_VERSION: i32 = 22;
+
+/// Entries kept in the audit log, and separately of changes found between syncs; older ones
+/// are dropped as new ones are written.
+const AUDIT_ENTRIES_KEPT: i64 = 10_000;
+
+/// API sessions whose stats are kept.
+const API_SESSIONS_KEPT: i64 = 20;
 
 /// SQLite cache for tasks.
 pub struct Cache {
     conn: Connection,
+    /// Whether the database was created by this run rather than opened.
+    created: bool,
 }
 
 impl Cache {
-    /// Initializes the SQLite database, creates the tasks table, and migrates schema if needed.
+    /// Opens `tasks.db` in the working directory.
     pub fn new() -> AppResult<Self> {
-        let conn = Connection::open("tasks.db")?;
+        Self::open(Connection::open(CACHE_PATH)?)
+    }
+
+    /// Creates a cache that lives only as long as this run, for `--demo`.
+    pub fn in_memory() -> AppResult<Self> {
+        Self::open(Connection::open_in_memory()?)
+    }
 
+    /// Initializes the SQLite database, creating or migrating its schema if it is older than
+    /// this release's.
+    fn open(conn: Connection) -> AppResult<Self> {
         // Scope the PRAGMA query to release the borrow
         let columns = {
             let mut stmt = conn.prepare("PRAGMA table_info(tasks)")?;
             stmt.query_map([], |row| row.get::<_, String>(1))?
                 .collect::<Result<Vec<String>, _>>()?
         };
+        let created = columns.is_empty();
+        let version = conn.query_row("PRAGMA user_version", [], |row| row.get::<_, i32>(0))?;
+        // A newer release's cache is used as is rather than stamped with an older version
+        if version < SCHEMA_VERSION {
+            migrate(&conn, version, &columns)?;
+        }
+        Ok(Cache { conn, created })
+    }
 
-        // Now conn is no longer borrowed
-        if columns.contains(&"is_completed".to_string()) && !columns.contains(&"checked".to_string()) {
-            // Migrate: Rename is_completed to checked
-            conn.execute("ALTER TABLE tasks RENAME COLUMN is_completed TO checked", [])?;
-        } else if !columns.contains(&"id".to_string()) {
-            // Create table if it doesn't exist
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS tasks (
-                    id INTEGER PRIMARY KEY,
-                    todoist_id TEXT NOT NULL,
-                    title TEXT NOT NULL,
-                    checked INTEGER NOT NULL
-                )",
-                [],
-            )?;
+    /// Opens `tasks.db` without creating or migrating it, for inspecting it as found; `None`
+    /// when there is no cache yet.
+    pub fn read_only() -> AppResult<Option<Self>> {
+        if !Path::new(CACHE_PATH).exists() {
+            return Ok(None);
         }
+        let conn = Connection::open_with_flags(CACHE_PATH, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Ok(Some(Cache { conn, created: false }))
+    }
+
+    /// Returns true if the database didn't exist before this run, i.e. on first run.
+    pub fn is_new(&self) -> bool {
+        self.created
+    }
 
-        Ok(Cache { conn })
+    /// Whether the cache lives only in memory, as with `--demo`.
+    pub fn is_in_memory(&self) -> bool {
+        self.conn.path().is_none_or(str::is_empty)
+    }
+
+    /// Writes a compacted, consistent copy of the database to a new file at `path`.
+    pub fn backup_to(&self, path: &Path) -> AppResult<()> {
+        self.conn.execute("VACUUM INTO ?1", params![path.to_string_lossy()])?;
+        Ok(())
     }
 
     /// Saves tasks to the database, replacing existing ones.
     pub fn save_tasks(&self, tasks: &[Task]) -> AppResult<()> {
-        self.conn.execute("DELETE FROM tasks", [])?;
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM tasks", [])?;
         for task in tasks {
-            self.conn.execute(
-                "INSERT INTO tasks (id, todoist_id, title, checked) VALUES (?1, ?2, ?3, ?4)",
-                params![task.id, task.todoist_id, task.title, task.checked as i32],
-            )?;
+            insert_task(&tx, task)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Writes only the given rows: inserts or replaces `changed` tasks and deletes the tasks
+    /// with the local IDs in `removed`.
+    pub fn write_tasks(&self, changed: &[&Task], removed: &[usize]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for task in changed {
+            insert_task(&tx, task)?;
+        }
+        for id in removed {
+            tx.execute("DELETE FROM tasks WHERE id = ?1", params![id])?;
         }
+        tx.commit()?;
         Ok(())
     }
 
     /// Loads tasks from the database.
     pub fn load_tasks(&self) -> AppResult<Vec<Task>> {
-        let mut stmt = self.conn.prepare("SELECT id, todoist_id, title, checked FROM tasks")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, todoist_id, title, checked, due_date, due_datetime, due_string, due_recurring, deadline, labels, priority, project_id, parent_id, completed_at, added_at, updated_at, location, description, responsible_uid, duration FROM tasks",
+        )?;
         let tasks = stmt
             .query_map([], |row| {
+                let due = row.get::<_, Option<String>>(4)?.map(|date| -> rusqlite::Result<Due> {
+                    Ok(Due {
+                        date,
+                        datetime: row.get(5)?,
+                        string: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                        is_recurring: row.get::<_, i32>(7)? != 0,
+                    })
+                });
                 Ok(Task {
                     id: row.get(0)?,
                     todoist_id: row.get(1)?,
                     title: row.get(2)?,
                     checked: row.get::<_, i32>(3)? != 0,
+                    due: due.transpose()?,
+                    deadline: row.get(8)?,
+                    labels: serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default(),
+                    priority: row.get(10)?,
+                    project_id: row.get(11)?,
+                    parent_id: row.get(12)?,
+                    completed_at: row.get(13)?,
+                    added_at: row.get(14)?,
+                    updated_at: row.get(15)?,
+                    location: row.get(16)?,
+                    description: row.get(17)?,
+                    responsible_uid: row.get(18)?,
+                    duration: row.get(19)?,
                 })
             })?
             .collect::<Result<Vec<Task>, rusqlite::Error>>()?;
         Ok(tasks)
     }
-}
\ No newline at end of file
+
+    /// Checks whether a notification was already sent for a task at the given due date.
+    pub fn was_notified(&self, todoist_id: &str, due: &str) -> AppResult<bool> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM notified WHERE todoist_id = ?1 AND due = ?2",
+            params![todoist_id, due],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Records that a notification was sent for a task at the given due date.
+    pub fn mark_notified(&self, todoist_id: &str, due: &str) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO notified (todoist_id, due, notified_at) VALUES (?1, ?2, datetime('now'))",
+            params![todoist_id, due],
+        )?;
+        Ok(())
+    }
+
+    /// Saves the API usage of the session started at `session` (RFC 3339, UTC), replacing what was
+    /// saved for it before and dropping the oldest sessions.
+    pub fn save_api_stats(&self, session: &str, stats: &[(String, EndpointStats)]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (endpoint, s) in stats {
+            tx.execute(
+                "INSERT OR REPLACE INTO api_stats (session, endpoint, requests, errors, total_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![session, endpoint, s.requests, s.errors, s.total_ms],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM api_stats WHERE session NOT IN
+                (SELECT DISTINCT session FROM api_stats ORDER BY session DESC LIMIT ?1)",
+            params![API_SESSIONS_KEPT],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Appends entries to the audit log, dropping the oldest beyond what's kept.
+    pub fn log_events(&self, entries: &[AuditEntry]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for entry in entries {
+            tx.execute(
+                "INSERT INTO audit_log (at, todoist_id, entry) VALUES (?1, ?2, ?3)",
+                params![entry.at, entry.todoist_id, serde_json::to_string(entry)?],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM audit_log WHERE seq <= (SELECT MAX(seq) FROM audit_log) - ?1",
+            params![AUDIT_ENTRIES_KEPT],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Loads the newest `limit` audit log entries, oldest first, optionally only those of tasks
+    /// whose Todoist ID starts with `todoist_id`.
+    pub fn load_events(&self, todoist_id: Option<&str>, limit: usize) -> AppResult<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT entry FROM audit_log WHERE ?1 IS NULL OR substr(todoist_id, 1, length(?1)) = ?1
+             ORDER BY seq DESC LIMIT ?2",
+        )?;
+        let mut entries = stmt
+            .query_map(params![todoist_id, limit as i64], |row| row.get::<_, String>(0))?
+            .map(|json| Ok(serde_json::from_str(&json?)?))
+            .collect::<AppResult<Vec<AuditEntry>>>()?;
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Stores changes found between two syncs, dropping the oldest beyond what's kept.
+    pub fn save_remote_changes(&self, entries: &[AuditEntry]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for entry in entries {
+            tx.execute(
+                "INSERT INTO remote_changes (todoist_id, entry) VALUES (?1, ?2)",
+                params![entry.todoist_id, serde_json::to_string(entry)?],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM remote_changes WHERE seq <= (SELECT MAX(seq) FROM remote_changes) - ?1",
+            params![AUDIT_ENTRIES_KEPT],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Loads the newest `limit` changes found between syncs, oldest first: only those not seen
+    /// yet unless `all` is set.
+    pub fn load_remote_changes(&self, all: bool, limit: usize) -> AppResult<Vec<AuditEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT entry FROM remote_changes WHERE ?1 OR seen = 0 ORDER BY seq DESC LIMIT ?2",
+        )?;
+        let mut entries = stmt
+            .query_map(params![all, limit as i64], |row| row.get::<_, String>(0))?
+            .map(|json| Ok(serde_json::from_str(&json?)?))
+            .collect::<AppResult<Vec<AuditEntry>>>()?;
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Marks all changes found between syncs as seen.
+    pub fn mark_remote_changes_seen(&self) -> AppResult<()> {
+        self.conn.execute("UPDATE remote_changes SET seen = 1 WHERE seen = 0", [])?;
+        Ok(())
+    }
+
+    /// Loads the API usage of the latest saved session, with its start time.
+    pub fn last_api_stats(&self) -> AppResult<Option<(String, Usage)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT session, endpoint, requests, errors, total_ms FROM api_stats
+             WHERE session = (SELECT MAX(session) FROM api_stats)",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                let stats = EndpointStats {
+                    requests: row.get(2)?,
+                    errors: row.get(3)?,
+                    total_ms: row.get(4)?,
+                };
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, stats))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some(session) = rows.first().map(|(session, _, _)| session.clone()) else {
+            return Ok(None);
+        };
+        let stats = rows.into_iter().map(|(_, endpoint, s)| (endpoint, s)).collect();
+        Ok(Some((session, stats)))
+    }
+
+    /// Records that a task was looked at in a review, which counts as touching it.
+    pub fn mark_reviewed(&self, todoist_id: &str) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO reviewed (todoist_id, reviewed_at) VALUES (?1, ?2)",
+            params![todoist_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Loads when each reviewed task was last reviewed (RFC 3339), by Todoist ID.
+    pub fn reviewed(&self) -> AppResult<HashMap<String, String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT todoist_id, reviewed_at FROM reviewed")?;
+        let reviewed = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, rusqlite::Error>>()?;
+        Ok(reviewed)
+    }
+
+    /// Queues a change made while Todoist couldn't be reached, to be sent with the next sync.
+    /// `base` is the edited task's `updated_at` before the change, if known.
+    pub fn queue_operation(&self, command: &SyncCommand, base: Option<&str>) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT INTO operations (queued_at, command, base) VALUES (?1, ?2, ?3)",
+            params![Utc::now().to_rfc3339(), serde_json::to_string(command)?, base],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the `updated_at` each queued edit was made on, by sequence number, for the edits
+    /// that have one.
+    pub fn operation_bases(&self) -> AppResult<HashMap<i64, String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT seq, base FROM operations WHERE base IS NOT NULL")?;
+        let bases = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, rusqlite::Error>>()?;
+        Ok(bases)
+    }
+
+    /// Moves the queued edits of a task onto the given `updated_at`, once the user has chosen
+    /// to keep them over the changes made in Todoist.
+    pub fn rebase_operations(&self, todoist_id: &str, base: Option<&str>) -> AppResult<()> {
+        self.conn.execute(
+            "UPDATE operations SET base = ?2 WHERE json_extract(command, '$.args.id') = ?1",
+            params![todoist_id, base],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the queued changes, oldest first, with their sequence numbers.
+    pub fn load_operations(&self) -> AppResult<Vec<(i64, SyncCommand)>> {
+        let mut stmt = self.conn.prepare("SELECT seq, command FROM operations ORDER BY seq")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        let mut operations = Vec::new();
+        for (seq, json) in rows {
+            operations.push((seq, serde_json::from_str(&json)?));
+        }
+        Ok(operations)
+    }
+
+    /// Loads the queued changes, oldest first, with their sequence numbers and when they were
+    /// queued.
+    pub fn list_operations(&self) -> AppResult<Vec<(i64, String, SyncCommand)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT seq, queued_at, command FROM operations ORDER BY seq")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        let mut operations = Vec::new();
+        for (seq, queued_at, json) in rows {
+            operations.push((seq, queued_at, serde_json::from_str(&json)?));
+        }
+        Ok(operations)
+    }
+
+    /// Counts the queued changes.
+    pub fn count_operations(&self) -> AppResult<usize> {
+        Ok(self.conn.query_row("SELECT COUNT(*) FROM operations", [], |row| row.get(0))?)
+    }
+
+    /// Removes queued changes once Todoist has answered them; changes held back by a conflict
+    /// stay queued between them.
+    pub fn remove_operations(&self, seqs: &[i64]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for seq in seqs {
+            tx.execute("DELETE FROM operations WHERE seq = ?1", params![seq])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Removes one queued change without it being sent.
+    pub fn drop_operation(&self, seq: i64) -> AppResult<()> {
+        self.conn.execute("DELETE FROM operations WHERE seq = ?1", params![seq])?;
+        Ok(())
+    }
+
+    /// Replaces the temporary IDs of tasks added offline with the real ones from
+    /// `temp_id_mapping` in the queued changes, the blocked-by links and the waiting tasks, in
+    /// one transaction so a link or follow-up recorded offline never keeps a temporary ID.
+    pub fn remap_temp_ids(&self, temp_id_mapping: &HashMap<String, String>) -> AppResult<()> {
+        if temp_id_mapping.is_empty() {
+            return Ok(());
+        }
+        let operations = self.load_operations()?;
+        let tx = self.conn.unchecked_transaction()?;
+        for (seq, command) in operations {
+            tx.execute(
+                "UPDATE operations SET command = ?1 WHERE seq = ?2",
+                params![serde_json::to_string(&command.with_ids(temp_id_mapping))?, seq],
+            )?;
+        }
+        for (temp_id, todoist_id) in temp_id_mapping {
+            tx.execute(
+                "UPDATE blocked_by SET todoist_id = ?2 WHERE todoist_id = ?1",
+                params![temp_id, todoist_id],
+            )?;
+            tx.execute(
+                "UPDATE blocked_by SET blocker_id = ?2 WHERE blocker_id = ?1",
+                params![temp_id, todoist_id],
+            )?;
+            tx.execute(
+                "UPDATE waiting SET todoist_id = ?2 WHERE todoist_id = ?1",
+                params![temp_id, todoist_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Saves the Sync API token the next incremental sync continues from.
+    pub fn save_sync_token(&self, sync_token: &str) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sync_state (id, sync_token) VALUES (1, ?1)",
+            params![sync_token],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the Sync API token of the last incremental sync, if there was one.
+    pub fn load_sync_token(&self) -> AppResult<Option<String>> {
+        let mut stmt = self.conn.prepare("SELECT sync_token FROM sync_state WHERE id = 1")?;
+        let mut rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.next().transpose()?)
+    }
+
+    /// Forgets the sync token, so the next incremental sync fetches every task again.
+    pub fn clear_sync_token(&self) -> AppResult<()> {
+        self.conn.execute("DELETE FROM sync_state", [])?;
+        Ok(())
+    }
+
+    /// Saves a conflict between a task's queued edits and Todoist, holding the edits back.
+    pub fn save_conflict(&self, conflict: &Conflict) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO conflicts (todoist_id, mine, theirs) VALUES (?1, ?2, ?3)",
+            params![
+                conflict.todoist_id,
+                serde_json::to_string(&conflict.mine)?,
+                serde_json::to_string(&conflict.theirs)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the conflicts waiting for the user to choose a side, oldest first.
+    pub fn load_conflicts(&self) -> AppResult<Vec<Conflict>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT todoist_id, mine, theirs FROM conflicts ORDER BY rowid")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        let mut conflicts = Vec::new();
+        for (todoist_id, mine, theirs) in rows {
+            conflicts.push(Conflict {
+                todoist_id,
+                mine: serde_json::from_str(&mine)?,
+                theirs: serde_json::from_str(&theirs)?,
+            });
+        }
+        Ok(conflicts)
+    }
+
+    /// Forgets a resolved conflict.
+    pub fn remove_conflict(&self, todoist_id: &str) -> AppResult<()> {
+        self.conn
+            .execute("DELETE FROM conflicts WHERE todoist_id = ?1", params![todoist_id])?;
+        Ok(())
+    }
+
+    /// Saves when a task started waiting on someone else, or was last followed up on.
+    pub fn save_waiting_since(&self, todoist_id: &str, since: &str) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO waiting (todoist_id, since) VALUES (?1, ?2)",
+            params![todoist_id, since],
+        )?;
+        Ok(())
+    }
+
+    /// Forgets a task that is no longer waiting.
+    pub fn forget_waiting(&self, todoist_id: &str) -> AppResult<()> {
+        self.conn
+            .execute("DELETE FROM waiting WHERE todoist_id = ?1", params![todoist_id])?;
+        Ok(())
+    }
+
+    /// Gets when each waiting task started waiting or was last followed up on, by Todoist ID.
+    pub fn waiting_since(&self) -> AppResult<HashMap<String, String>> {
+        let mut stmt = self.conn.prepare("SELECT todoist_id, since FROM waiting")?;
+        let waiting = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, rusqlite::Error>>()?;
+        Ok(waiting)
+    }
+
+    /// Records that a task can't start until another one, its blocker, is done.
+    pub fn add_blocker(&self, todoist_id: &str, blocker_id: &str) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO blocked_by (todoist_id, blocker_id) VALUES (?1, ?2)",
+            params![todoist_id, blocker_id],
+        )?;
+        Ok(())
+    }
+
+    /// Removes a blocked-by link.
+    pub fn remove_blocker(&self, todoist_id: &str, blocker_id: &str) -> AppResult<()> {
+        self.conn.execute(
+            "DELETE FROM blocked_by WHERE todoist_id = ?1 AND blocker_id = ?2",
+            params![todoist_id, blocker_id],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the blocked-by links as (blocked task, blocker) pairs of Todoist IDs.
+    pub fn load_blockers(&self) -> AppResult<Vec<(String, String)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT todoist_id, blocker_id FROM blocked_by ORDER BY rowid")?;
+        let links = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        Ok(links)
+    }
+
+    /// Links a task to the issue URL it was created from.
+    pub fn link_issue(&self, todoist_id: &str, url: &str) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO linked_issues (todoist_id, url) VALUES (?1, ?2)",
+            params![todoist_id, url],
+        )?;
+        Ok(())
+    }
+
+    /// Loads all task/issue links as `(todoist_id, url)` pairs.
+    pub fn linked_issues(&self) -> AppResult<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare("SELECT todoist_id, url FROM linked_issues")?;
+        let links = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(links)
+    }
+
+    /// Removes a task's issue link.
+    pub fn unlink_issue(&self, todoist_id: &str) -> AppResult<()> {
+        self.conn
+            .execute("DELETE FROM linked_issues WHERE todoist_id = ?1", params![todoist_id])?;
+        Ok(())
+    }
+
+    /// Moves completed tasks into the history table, storing each task as JSON.
+    pub fn archive_tasks(&self, tasks: &[Task]) -> AppResult<()> {
+        for task in tasks {
+            self.conn.execute(
+                "INSERT OR REPLACE INTO history (todoist_id, title, completed_at, archived_at, task)
+                 VALUES (?1, ?2, ?3, datetime('now'), ?4)",
+                params![task.todoist_id, task.title, task.completed_at, serde_json::to_string(task)?],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Loads the archived completed tasks, most recently completed first.
+    pub fn load_history(&self) -> AppResult<Vec<Task>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT task FROM history ORDER BY completed_at DESC")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        let mut tasks = Vec::new();
+        for json in rows {
+            tasks.push(serde_json::from_str(&json)?);
+        }
+        Ok(tasks)
+    }
+
+    /// Saves the project list, replacing the previous one and keeping its order.
+    pub fn save_projects(&self, projects: &[Project]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM projects", [])?;
+        for (position, project) in projects.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO projects (position, project) VALUES (?1, ?2)",
+                params![position as i64, serde_json::to_string(project)?],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Loads the project list saved by the last project sync.
+    pub fn load_projects(&self) -> AppResult<Vec<Project>> {
+        let mut stmt = self.conn.prepare("SELECT project FROM projects ORDER BY position")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        let mut projects = Vec::new();
+        for json in rows {
+            projects.push(serde_json::from_str(&json)?);
+        }
+        Ok(projects)
+    }
+
+    /// Saves the label list, replacing the previous one and keeping its order.
+    pub fn save_labels(&self, labels: &[Label]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM labels", [])?;
+        for (position, label) in labels.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO labels (position, label) VALUES (?1, ?2)",
+                params![position as i64, serde_json::to_string(label)?],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Loads the label list saved by the last label sync.
+    pub fn load_labels(&self) -> AppResult<Vec<Label>> {
+        let mut stmt = self.conn.prepare("SELECT label FROM labels ORDER BY position")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        let mut labels = Vec::new();
+        for json in rows {
+            labels.push(serde_json::from_str(&json)?);
+        }
+        Ok(labels)
+    }
+
+    /// Saves the filters saved in Todoist, replacing the previous ones and keeping their order.
+    pub fn save_filters(&self, filters: &[SavedFilter]) -> AppResult<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM filters", [])?;
+        for (position, filter) in filters.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO filters (position, filter) VALUES (?1, ?2)",
+                params![position as i64, serde_json::to_string(filter)?],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Loads the filters saved by the last filter sync.
+    pub fn load_filters(&self) -> AppResult<Vec<SavedFilter>> {
+        let mut stmt = self.conn.prepare("SELECT filter FROM filters ORDER BY position")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, rusqlite::Error>>()?;
+        let mut filters = Vec::new();
+        for json in rows {
+            filters.push(serde_json::from_str(&json)?);
+        }
+        Ok(filters)
+    }
+
+    /// Saves the daily goal, 0 when none is set, with the tasks completed on `date`.
+    pub fn save_daily_goal(&self, goal: usize, date: NaiveDate, completed: usize) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO daily_goal (id, goal, date, completed) VALUES (1, ?1, ?2, ?3)",
+            params![goal as i64, date.to_string(), completed as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the daily goal saved by the last stats sync, with the day it counted completions for.
+    pub fn load_daily_goal(&self) -> AppResult<Option<(usize, NaiveDate, usize)>> {
+        let mut stmt = self.conn.prepare("SELECT goal, date, completed FROM daily_goal WHERE id = 1")?;
+        let mut rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        let Some((goal, date, completed)) = rows.next().transpose()? else {
+            return Ok(None);
+        };
+        Ok(Some((goal as usize, date.parse()?, completed as usize)))
+    }
+
+    /// Saves how far the weekly review got, replacing the previous progress.
+    pub fn save_weekly_review(&self, review: &WeeklyReview) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO weekly_review (id, progress) VALUES (1, ?1)",
+            params![serde_json::to_string(review)?],
+        )?;
+        Ok(())
+    }
+
+    /// Loads the progress of an unfinished weekly review.
+    pub fn load_weekly_review(&self) -> AppResult<Option<WeeklyReview>> {
+        let mut stmt = self.conn.prepare("SELECT progress FROM weekly_review WHERE id = 1")?;
+        let mut rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        match rows.next() {
+            Some(json) => Ok(Some(serde_json::from_str(&json?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Forgets the weekly review's progress once it's finished or restarted.
+    pub fn clear_weekly_review(&self) -> AppResult<()> {
+        self.conn.execute("DELETE FROM weekly_review", [])?;
+        Ok(())
+    }
+
+    /// Saves a task template under a name, replacing any existing one.
+    pub fn save_template(&self, name: &str, template: &Template) -> AppResult<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO templates (name, body) VALUES (?1, ?2)",
+            params![name, serde_json::to_string(template)?],
+        )?;
+        Ok(())
+    }
+
+    /// Loads a task template by name.
+    pub fn load_template(&self, name: &str) -> AppResult<Option<Template>> {
+        let mut stmt = self.conn.prepare("SELECT body FROM templates WHERE name = ?1")?;
+        let mut rows = stmt.query_map(params![name], |row| row.get::<_, String>(0))?;
+        match rows.next() {
+            Some(body) => Ok(Some(serde_json::from_str(&body?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the schema version recorded in the database.
+    pub fn schema_version(&self) -> AppResult<i32> {
+        Ok(self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    /// Runs SQLite's integrity check, returning any problems found.
+    pub fn integrity_problems(&self) -> AppResult<Vec<String>> {
+        let mut stmt = self.conn.prepare("PRAGMA integrity_check")?;
+        let results = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(results.into_iter().filter(|r| r != "ok").collect())
+    }
+}
+
+/// Runs the migrations newer than `version`, the schema version the database was last opened
+/// with, given the tasks table's current `columns`, then stamps it with `SCHEMA_VERSION`.
+fn migrate(conn: &Connection, version: i32, columns: &[String]) -> AppResult<()> {
+    let tx = conn.unchecked_transaction()?;
+    if version < 1 {
+        if columns.iter().any(|c| c == "is_completed") && !columns.iter().any(|c| c == "checked") {
+            // Migrate: Rename is_completed to checked
+            tx.execute("ALTER TABLE tasks RENAME COLUMN is_completed TO checked", [])?;
+        } else if !columns.iter().any(|c| c == "id") {
+            tx.execute(
+                "CREATE TABLE IF NOT EXISTS tasks (
+                    id INTEGER PRIMARY KEY,
+                    todoist_id TEXT NOT NULL,
+                    title TEXT NOT NULL,
+                    checked INTEGER NOT NULL
+                )",
+                [],
+            )?;
+        }
+    }
+    // Caches from before versioning may already have some of the columns
+    for (since, name, decl) in TASK_COLUMNS {
+        if version < *since && !columns.iter().any(|c| c == name) {
+            tx.execute(&format!("ALTER TABLE tasks ADD COLUMN {} {}", name, decl), [])?;
+        }
+    }
+    for (since, sql) in TABLES {
+        if version < *since {
+            tx.execute(sql, [])?;
+        }
+    }
+    if version < 21 {
+        // The task's `updated_at` before a queued edit, to tell whether Todoist changed it since
+        tx.execute("ALTER TABLE operations ADD COLUMN base TEXT", [])?;
+    }
+    if version < 7 {
+        // Migrate: Rename is_completed to checked in tasks archived before the column rename
+        tx.execute(
+            "UPDATE history
+             SET task = json_set(
+                 json_remove(task, '$.is_completed'),
+                 '$.checked',
+                 json(CASE WHEN json_extract(task, '$.is_completed') THEN 'true' ELSE 'false' END)
+             )
+             WHERE json_type(task, '$.is_completed') IS NOT NULL",
+            [],
+        )?;
+    }
+    tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Inserts a task row, replacing any row with the same local ID.
+fn insert_task(conn: &Connection, task: &Task) -> AppResult<()> {
+    let due = task.due.as_ref();
+    // Cached so syncing a large account doesn't re-prepare the statement for every row
+    conn.prepare_cached(
+        "INSERT OR REPLACE INTO tasks (id, todoist_id, title, checked, due_date, due_datetime, due_string, due_recurring, deadline, labels, priority, project_id, parent_id, completed_at, added_at, updated_at, location, description, responsible_uid, duration)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+    )?
+    .execute(params![
+        task.id,
+        task.todoist_id,
+        task.title,
+        task.is_done() as i32,
+        due.map(|d| &d.date),
+        due.and_then(|d| d.datetime.as_ref()),
+        due.map(|d| &d.string),
+        due.is_some_and(|d| d.is_recurring) as i32,
+        task.deadline,
+        serde_json::to_string(&task.labels)?,
+        task.priority,
+        task.project_id,
+        task.parent_id,
+        task.completed_at,
+        task.added_at,
+        task.updated_at,
+        task.location,
+        task.description,
+        task.responsible_uid,
+        task.duration,
+    ])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task::NewTask;
+    use serde_json::json;
+
+    /// The columns of `table`.
+    fn columns(cache: &Cache, table: &str) -> Vec<String> {
+        let mut stmt = cache.conn.prepare(&format!("PRAGMA table_info({})", table)).unwrap();
+        stmt.query_map([], |row| row.get(1)).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    fn user_version(cache: &Cache) -> i32 {
+        cache.conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn new_cache_gets_the_current_schema() {
+        let cache = Cache::in_memory().unwrap();
+        assert!(cache.created);
+        assert_eq!(user_version(&cache), SCHEMA_VERSION);
+        let columns = columns(&cache, "tasks");
+        for (_, name, _) in TASK_COLUMNS {
+            assert!(columns.iter().any(|c| c == name), "missing column {}", name);
+        }
+        assert!(cache.load_tasks().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unversioned_cache_is_migrated_keeping_its_tasks() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE tasks (id INTEGER PRIMARY KEY, todoist_id TEXT NOT NULL, title TEXT NOT NULL, is_completed INTEGER NOT NULL);
+             INSERT INTO tasks VALUES (1, '100', 'Old task', 1);",
+        )
+        .unwrap();
+        let cache = Cache::open(conn).unwrap();
+        assert!(!cache.created);
+        assert_eq!(user_version(&cache), SCHEMA_VERSION);
+        assert!(!columns(&cache, "tasks").iter().any(|c| c == "is_completed"));
+        let tasks = cache.load_tasks().unwrap();
+        assert_eq!(tasks[0].title, "Old task");
+        assert!(tasks[0].is_done());
+    }
+
+    #[test]
+    fn newer_cache_is_left_as_is() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE tasks (id INTEGER PRIMARY KEY, todoist_id TEXT NOT NULL, title TEXT NOT NULL, checked INTEGER NOT NULL);
+             PRAGMA user_version = {};",
+            SCHEMA_VERSION + 1
+        ))
+        .unwrap();
+        let cache = Cache::open(conn).unwrap();
+        assert_eq!(user_version(&cache), SCHEMA_VERSION + 1);
+        assert_eq!(columns(&cache, "tasks").len(), 4);
+    }
+
+    #[test]
+    fn archived_tasks_get_checked_in_place_of_is_completed() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE tasks (id INTEGER PRIMARY KEY, todoist_id TEXT NOT NULL, title TEXT NOT NULL, checked INTEGER NOT NULL);
+             CREATE TABLE history (todoist_id TEXT PRIMARY KEY, title TEXT NOT NULL, completed_at TEXT, archived_at TEXT NOT NULL, task TEXT NOT NULL);
+             PRAGMA user_version = 6;",
+        )
+        .unwrap();
+        let mut task = serde_json::to_value(NewTask::new("Done").preview("100")).unwrap();
+        task["is_completed"] = json!(true);
+        task.as_object_mut().unwrap().remove("checked");
+        conn.execute(
+            "INSERT INTO history VALUES ('100', 'Done', NULL, datetime('now'), ?1)",
+            params![task.to_string()],
+        )
+        .unwrap();
+        let cache = Cache::open(conn).unwrap();
+        let (checked, is_completed) = cache
+            .conn
+            .query_row(
+                "SELECT json_extract(task, '$.checked'), json_type(task, '$.is_completed') FROM history",
+                [],
+                |row| Ok((row.get::<_, bool>(0)?, row.get::<_, Option<String>>(1)?)),
+            )
+            .unwrap();
+        assert!(checked);
+        assert_eq!(is_completed, None);
+        assert!(cache.load_history().unwrap()[0].is_done());
+    }
+
+    #[test]
+    fn remap_temp_ids_rewrites_the_queue_links_and_follow_ups() {
+        let cache = Cache::in_memory().unwrap();
+        let temp_id = uuid::Uuid::new_v4().to_string();
+        cache
+            .queue_operation(&SyncCommand::item_update(&temp_id, json!({ "content": "Renamed" })), None)
+            .unwrap();
+        cache.add_blocker(&temp_id, "100").unwrap();
+        cache.add_blocker("200", &temp_id).unwrap();
+        cache.save_waiting_since(&temp_id, "2024-05-15T09:00:00Z").unwrap();
+
+        cache
+            .remap_temp_ids(&HashMap::from([(temp_id.clone(), "300".to_string())]))
+            .unwrap();
+
+        let operations = cache.load_operations().unwrap();
+        assert_eq!(operations[0].1.args["id"], "300");
+        assert_eq!(
+            cache.load_blockers().unwrap(),
+            [("300".to_string(), "100".to_string()), ("200".to_string(), "300".to_string())]
+        );
+        let waiting = cache.waiting_since().unwrap();
+        assert_eq!(waiting.get("300").map(String::as_str), Some("2024-05-15T09:00:00Z"));
+        assert!(!waiting.contains_key(&temp_id));
+    }
+}
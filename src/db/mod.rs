@@ -1 +1,3 @@
+pub mod archive;
+pub mod backup;
 pub mod cache;
\ No newline at end of file
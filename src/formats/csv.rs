@@ -0,0 +1,116 @@
+use crate::controller::trends::ProjectTrend;
+use crate::models::task::{Due, Task};
+
+/// Columns of Todoist's project import template, in order.
+const HEADER: [&str; 14] = [
+    "TYPE",
+    "CONTENT",
+    "DESCRIPTION",
+    "PRIORITY",
+    "INDENT",
+    "AUTHOR",
+    "RESPONSIBLE",
+    "DATE",
+    "DATE_LANG",
+    "TIMEZONE",
+    "DURATION",
+    "DURATION_UNIT",
+    "DEADLINE",
+    "DEADLINE_LANG",
+];
+
+/// Renders open tasks as a CSV file that Todoist can import into a project. Subtasks follow
+/// their parent one indent level deeper, and labels are appended to the content as `@label`.
+pub fn export(tasks: &[Task]) -> String {
+    let mut out = format!("{}\n", HEADER.join(","));
+    let open = tasks.iter().filter(|t| !t.is_done()).collect::<Vec<_>>();
+    // Subtasks whose parent isn't exported become top-level tasks
+    let roots = open.iter().filter(|t| {
+        t.parent_id
+            .as_ref()
+            .is_none_or(|parent| !open.iter().any(|p| &p.todoist_id == parent))
+    });
+    for task in roots {
+        write_task(&mut out, task, &open, 1);
+    }
+    out
+}
+
+/// Renders per-project trends as one row per project and week, oldest week first, for charting
+/// velocity and burndown elsewhere.
+pub fn export_trends(trends: &[ProjectTrend]) -> String {
+    let mut out = "project,week_start,completed,open\n".to_string();
+    for trend in trends {
+        for week in &trend.weeks {
+            out.push_str(&format!(
+                "{},{},{},{}\n",
+                escape(&trend.project),
+                week.start,
+                week.completed,
+                week.open
+            ));
+        }
+    }
+    out
+}
+
+/// Writes a task row followed by its subtasks.
+fn write_task(out: &mut String, task: &Task, tasks: &[&Task], indent: usize) {
+    let mut content = task.title.clone();
+    for label in &task.labels {
+        content.push_str(&format!(" @{}", label));
+    }
+    // Todoist shows priorities as p1 (urgent) to p4, while the API counts up to 4 for urgent
+    let priority = (5 - task.priority.clamp(1, 4)).to_string();
+    let date = task.due.as_ref().map_or(String::new(), due_text);
+    let date_lang = if date.is_empty() { "" } else { "en" };
+    let deadline = task.deadline.clone().unwrap_or_default();
+    let deadline_lang = if deadline.is_empty() { "" } else { "en" };
+    let level = indent.to_string();
+    let row = [
+        "task",
+        &content,
+        &task.description,
+        &priority,
+        &level,
+        "",
+        "",
+        &date,
+        date_lang,
+        "",
+        "",
+        "",
+        &deadline,
+        deadline_lang,
+    ];
+    out.push_str(&row.map(escape).join(","));
+    out.push('\n');
+    for child in tasks
+        .iter()
+        .filter(|t| t.parent_id.as_deref() == Some(task.todoist_id.as_str()))
+    {
+        write_task(out, child, tasks, indent + 1);
+    }
+}
+
+/// Gets the DATE column of a due date: the due string for recurring tasks, so "every sat" stays
+/// recurring, and otherwise the absolute local date and time, since strings like "today" would
+/// move to the day of the import.
+fn due_text(due: &Due) -> String {
+    if due.is_recurring && !due.string.is_empty() {
+        return due.string.clone();
+    }
+    match due.naive_datetime() {
+        Some(at) => at.format("%Y-%m-%d %H:%M").to_string(),
+        None => due.date.clone(),
+    }
+}
+
+/// Quotes a field when it contains a comma, quote or line break.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,85 @@
+use crate::models::project::Project;
+use crate::models::task::Task;
+use chrono::NaiveDate;
+
+/// Selects open tasks that are overdue or due on `today`, ordered by due time.
+pub fn agenda(tasks: &[Task], today: NaiveDate) -> Vec<&Task> {
+    let mut agenda = tasks
+        .iter()
+        .filter(|t| {
+            !t.is_done()
+                && t.due
+                    .as_ref()
+                    .and_then(|d| d.naive_date())
+                    .is_some_and(|d| d <= today)
+        })
+        .collect::<Vec<_>>();
+    agenda.sort_by_key(|t| t.due.as_ref().and_then(|d| d.sort_key()));
+    agenda
+}
+
+/// Renders the agenda as plain text.
+pub fn plaintext(agenda: &[&Task], projects: &[Project], today: NaiveDate) -> String {
+    let mut out = format!("Agenda for {}\n\n", today.format("%A, %B %-d"));
+    if agenda.is_empty() {
+        out.push_str("Nothing due today.\n");
+    }
+    for task in agenda {
+        out.push_str(&format!("- {}\n", line(task, projects, today)));
+    }
+    out
+}
+
+/// Renders the agenda as a small HTML document.
+pub fn html(agenda: &[&Task], projects: &[Project], today: NaiveDate) -> String {
+    let mut out = format!(
+        "<html><body>\n<h2>Agenda for {}</h2>\n",
+        today.format("%A, %B %-d")
+    );
+    if agenda.is_empty() {
+        out.push_str("<p>Nothing due today.</p>\n");
+    } else {
+        out.push_str("<ul>\n");
+        for task in agenda {
+            let style = if task.is_overdue(today) {
+                " style=\"color:#d1453b\""
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<li{}>{}</li>\n",
+                style,
+                escape(&line(task, projects, today))
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+/// Formats a task as "title (time, project)" with an overdue marker.
+fn line(task: &Task, projects: &[Project], today: NaiveDate) -> String {
+    let mut details = Vec::new();
+    if task.is_overdue(today) {
+        details.push("overdue".to_string());
+    }
+    if let Some(at) = task.due.as_ref().and_then(|d| d.naive_datetime()) {
+        details.push(at.format("%H:%M").to_string());
+    }
+    if let Some(project) = projects.iter().find(|p| p.id == task.project_id) {
+        details.push(project.name.clone());
+    }
+    if details.is_empty() {
+        task.title.clone()
+    } else {
+        format!("{} ({})", task.title, details.join(", "))
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
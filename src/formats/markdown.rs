@@ -0,0 +1,79 @@
+use crate::models::project::Project;
+use crate::models::task::Task;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Renders a markdown status report of completed and overdue tasks for a date range.
+/// Overdue dates are shown relative to `today` when `relative_dates` is set.
+pub fn report(
+    completed: &[Task],
+    open: &[Task],
+    projects: &[Project],
+    since: NaiveDate,
+    today: NaiveDate,
+    relative_dates: bool,
+) -> String {
+    let mut out = format!(
+        "# Todoist report: {} to {}\n\n",
+        since.format("%Y-%m-%d"),
+        today.format("%Y-%m-%d")
+    );
+
+    out.push_str("## Completed\n\n");
+    if completed.is_empty() {
+        out.push_str("_Nothing completed._\n\n");
+    }
+    let mut by_project: BTreeMap<&str, Vec<&Task>> = BTreeMap::new();
+    for task in completed {
+        by_project
+            .entry(project_name(projects, &task.project_id))
+            .or_default()
+            .push(task);
+    }
+    for (project, tasks) in &by_project {
+        out.push_str(&format!("### {} ({})\n\n", project, tasks.len()));
+        for task in tasks {
+            out.push_str(&format!("- [x] {}\n", task.title));
+        }
+        out.push('\n');
+    }
+
+    let overdue = open
+        .iter()
+        .filter(|t| t.is_overdue(today))
+        .collect::<Vec<_>>();
+    out.push_str("## Overdue\n\n");
+    if overdue.is_empty() {
+        out.push_str("_Nothing overdue._\n");
+    }
+    for task in &overdue {
+        let due = match &task.due {
+            Some(due) if relative_dates => due.display(today, true),
+            Some(due) => format!("due {}", due.display(today, false)),
+            None => String::new(),
+        };
+        out.push_str(&format!(
+            "- [ ] {} ({}, {})\n",
+            task.title,
+            due,
+            project_name(projects, &task.project_id)
+        ));
+    }
+
+    let open_count = open.iter().filter(|t| !t.is_done()).count();
+    out.push_str("\n## Stats\n\n");
+    out.push_str(&format!("- Completed: {}\n", completed.len()));
+    out.push_str(&format!("- Projects with progress: {}\n", by_project.len()));
+    out.push_str(&format!("- Open: {}\n", open_count));
+    out.push_str(&format!("- Overdue: {}\n", overdue.len()));
+    out
+}
+
+/// Looks up a project's name, falling back to a placeholder for unknown IDs.
+fn project_name<'a>(projects: &'a [Project], id: &str) -> &'a str {
+    projects
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| p.name.as_str())
+        .unwrap_or("Unknown project")
+}
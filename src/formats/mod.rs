@@ -0,0 +1,26 @@
+use crate::models::task::NewTask;
+
+pub mod csv;
+pub mod digest;
+pub mod markdown;
+pub mod org;
+pub mod taskwarrior;
+pub mod todotxt;
+
+/// A task read from an import file, with its project referenced by name.
+pub struct ParsedTask {
+    pub task: NewTask,
+    pub project: Option<String>,
+    /// Identifier of the task in the source system, used in the import report.
+    pub source: Option<String>,
+}
+
+impl From<NewTask> for ParsedTask {
+    fn from(task: NewTask) -> Self {
+        ParsedTask {
+            task,
+            project: None,
+            source: None,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::models::task::{Due, NewTask, Task};
+use chrono::{NaiveDate, NaiveTime};
+
+/// Renders tasks as org-mode headlines with TODO keywords, planning timestamps, and tags.
+pub fn export(tasks: &[Task]) -> String {
+    let mut out = String::from("#+TITLE: Todoist\n\n");
+    for task in tasks {
+        let keyword = if task.is_done() { "DONE" } else { "TODO" };
+        out.push_str(&format!("* {} {}", keyword, task.title));
+        let tags = task
+            .labels
+            .iter()
+            .map(|l| sanitize_tag(l))
+            .collect::<Vec<_>>();
+        if !tags.is_empty() {
+            out.push_str(&format!(" :{}:", tags.join(":")));
+        }
+        out.push('\n');
+
+        let mut planning = Vec::new();
+        if let Some(due) = &task.due
+            && let Some(stamp) = due_timestamp(due)
+        {
+            planning.push(format!("SCHEDULED: {}", stamp));
+        }
+        if let Some(date) = task.deadline.as_deref().and_then(parse_date) {
+            planning.push(format!("DEADLINE: <{}>", date.format("%Y-%m-%d %a")));
+        }
+        if !planning.is_empty() {
+            out.push_str(&format!("  {}\n", planning.join(" ")));
+        }
+    }
+    out
+}
+
+/// Parses the TODO headlines of an org document into tasks to create.
+pub fn parse(input: &str) -> Vec<NewTask> {
+    let mut tasks: Vec<NewTask> = Vec::new();
+    let mut in_todo = false;
+    for line in input.lines() {
+        if line.starts_with('*') {
+            in_todo = false;
+            let headline = line.trim_start_matches('*');
+            if !headline.starts_with(' ') {
+                continue;
+            }
+            if let Some(rest) = headline.trim_start().strip_prefix("TODO ") {
+                tasks.push(parse_headline(rest));
+                in_todo = true;
+            }
+        } else if in_todo && let Some(task) = tasks.last_mut() {
+            apply_planning(task, line);
+        }
+    }
+    tasks
+}
+
+/// Builds a task from the text after the TODO keyword.
+fn parse_headline(rest: &str) -> NewTask {
+    let mut title = rest.trim();
+    if let Some(stripped) = title.strip_prefix("[#")
+        && let Some((_, after)) = stripped.split_once(']')
+    {
+        title = after.trim_start();
+    }
+    let mut labels = Vec::new();
+    if let Some((head, last)) = title.rsplit_once(char::is_whitespace)
+        && last.len() > 1
+        && last.starts_with(':')
+        && last.ends_with(':')
+    {
+        labels = last
+            .trim_matches(':')
+            .split(':')
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect();
+        title = head.trim_end();
+    }
+    NewTask {
+        labels,
+        ..NewTask::new(title)
+    }
+}
+
+/// Applies SCHEDULED/DEADLINE timestamps from a planning line to the task.
+fn apply_planning(task: &mut NewTask, line: &str) {
+    for (keyword, deadline) in [("SCHEDULED:", false), ("DEADLINE:", true)] {
+        let Some((_, after)) = line.split_once(keyword) else {
+            continue;
+        };
+        let Some((date, time)) = parse_timestamp(after) else {
+            continue;
+        };
+        let date = date.format("%Y-%m-%d").to_string();
+        if deadline {
+            task.deadline_date = Some(date);
+        } else if let Some(time) = time {
+            task.due_string = Some(format!("{} {}", date, time.format("%H:%M")));
+        } else {
+            task.due_date = Some(date);
+        }
+    }
+}
+
+/// Parses the first `<YYYY-MM-DD Day [HH:MM]>` timestamp in the text.
+fn parse_timestamp(text: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let start = text.find(['<', '['])?;
+    let end = text[start..].find(['>', ']'])? + start;
+    let mut parts = text[start + 1..end].split_whitespace();
+    let date = parse_date(parts.next()?)?;
+    let time = parts.find_map(|p| NaiveTime::parse_from_str(p, "%H:%M").ok());
+    Some((date, time))
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Formats a due date as an active org timestamp.
+fn due_timestamp(due: &Due) -> Option<String> {
+    let date = due.naive_date()?;
+    Some(match due.naive_datetime() {
+        Some(datetime) => format!("<{}>", datetime.format("%Y-%m-%d %a %H:%M")),
+        None => format!("<{}>", date.format("%Y-%m-%d %a")),
+    })
+}
+
+/// Replaces characters org does not allow in tags.
+fn sanitize_tag(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "_@#%".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_todo_headlines_only() {
+        let tasks = parse("#+TITLE: Notes\n* Heading\n** TODO Buy milk\n* DONE Call Sam\n*bold* text\n* TODO Second\n");
+        let titles = tasks.iter().map(|t| t.content.as_str()).collect::<Vec<_>>();
+        assert_eq!(titles, ["Buy milk", "Second"]);
+    }
+
+    #[test]
+    fn strips_priority_cookie_and_tags() {
+        let tasks = parse("* TODO [#A] Write report   :work:deep_work:\n");
+        assert_eq!(tasks[0].content, "Write report");
+        assert_eq!(tasks[0].labels, ["work", "deep_work"]);
+    }
+
+    #[test]
+    fn reads_planning_lines() {
+        let tasks = parse(
+            "* TODO Dentist\n  SCHEDULED: <2024-05-20 Mon 14:30> DEADLINE: <2024-05-22 Wed>\n\
+             * TODO Taxes\n  SCHEDULED: <2024-06-01 Sat>\n\
+             * Notes\n  SCHEDULED: <2024-07-01 Mon>\n",
+        );
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].due_string.as_deref(), Some("2024-05-20 14:30"));
+        assert_eq!(tasks[0].deadline_date.as_deref(), Some("2024-05-22"));
+        assert_eq!(tasks[1].due_date.as_deref(), Some("2024-06-01"));
+        assert_eq!(tasks[1].due_string, None);
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let mut task = NewTask {
+            labels: vec!["deep work".to_string()],
+            due_date: Some("2024-05-20".to_string()),
+            deadline_date: Some("2024-05-22".to_string()),
+            ..NewTask::new("Write report")
+        }
+        .preview("abc");
+        let out = export(std::slice::from_ref(&task));
+        assert!(out.contains("* TODO Write report :deep_work:\n  SCHEDULED: <2024-05-20 Mon> DEADLINE: <2024-05-22 Wed>\n"));
+        let parsed = parse(&out);
+        assert_eq!(parsed[0].content, "Write report");
+        assert_eq!(parsed[0].due_date.as_deref(), Some("2024-05-20"));
+
+        task.checked = true;
+        assert!(export(&[task]).contains("* DONE Write report"));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::formats::ParsedTask;
+use crate::models::task::NewTask;
+use crate::utils::error::AppResult;
+use chrono::{Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::Deserialize;
+
+/// A task as emitted by `task export`.
+#[derive(Deserialize)]
+struct TaskwarriorTask {
+    uuid: String,
+    description: String,
+    status: String,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Deserialize)]
+struct Annotation {
+    description: String,
+}
+
+/// Parses `task export` JSON, keeping pending and waiting tasks.
+pub fn parse(input: &str) -> AppResult<Vec<ParsedTask>> {
+    let exported: Vec<TaskwarriorTask> = serde_json::from_str(input)
+        .map_err(|e| anyhow::anyhow!("Failed to parse Taskwarrior export: {}", e))?;
+    Ok(exported
+        .into_iter()
+        .filter(|t| t.status == "pending" || t.status == "waiting")
+        .map(convert)
+        .collect())
+}
+
+/// Maps a Taskwarrior task onto Todoist fields.
+fn convert(tw: TaskwarriorTask) -> ParsedTask {
+    let mut task = NewTask {
+        labels: tw.tags,
+        priority: tw.priority.as_deref().map(|p| match p {
+            "H" => 4,
+            "M" => 3,
+            "L" => 2,
+            _ => 1,
+        }),
+        ..NewTask::new(&tw.description)
+    };
+    if !tw.annotations.is_empty() {
+        let notes = tw
+            .annotations
+            .into_iter()
+            .map(|a| a.description)
+            .collect::<Vec<_>>();
+        task.description = Some(notes.join("\n"));
+    }
+    if let Some(due) = tw.due.as_deref().and_then(parse_due) {
+        // Taskwarrior stores date-only dues as local midnight
+        let local = Local.from_utc_datetime(&due);
+        if local.time() == NaiveTime::MIN {
+            task.due_date = Some(local.format("%Y-%m-%d").to_string());
+        } else {
+            task.due_datetime = Some(Utc.from_utc_datetime(&due).to_rfc3339());
+        }
+    }
+    ParsedTask {
+        task,
+        project: tw.project,
+        source: Some(tw.uuid),
+    }
+}
+
+/// Parses Taskwarrior's compact UTC timestamp, e.g. `20240105T230000Z`.
+fn parse_due(due: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(due, "%Y%m%dT%H%M%SZ").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    const EXPORT: &str = r#"[
+        {"uuid": "a1", "description": "Write report", "status": "pending", "project": "Work",
+         "tags": ["deep-work"], "priority": "H",
+         "annotations": [{"entry": "20240501T090000Z", "description": "Use the Q1 numbers"},
+                         {"entry": "20240502T090000Z", "description": "Ask Priya"}]},
+        {"uuid": "a2", "description": "Old task", "status": "completed"},
+        {"uuid": "a3", "description": "Deleted", "status": "deleted"},
+        {"uuid": "a4", "description": "Someday", "status": "waiting", "priority": "L"}
+    ]"#;
+
+    #[test]
+    fn keeps_pending_and_waiting_tasks() {
+        let parsed = parse(EXPORT).unwrap();
+        let sources = parsed.iter().map(|p| p.source.as_deref().unwrap()).collect::<Vec<_>>();
+        assert_eq!(sources, ["a1", "a4"]);
+    }
+
+    #[test]
+    fn maps_fields() {
+        let parsed = parse(EXPORT).unwrap();
+        let task = &parsed[0].task;
+        assert_eq!(task.content, "Write report");
+        assert_eq!(task.priority, Some(4));
+        assert_eq!(task.labels, ["deep-work"]);
+        assert_eq!(task.description.as_deref(), Some("Use the Q1 numbers\nAsk Priya"));
+        assert_eq!(parsed[0].project.as_deref(), Some("Work"));
+        assert_eq!(parsed[1].task.priority, Some(2));
+        assert_eq!(parsed[1].project, None);
+    }
+
+    #[test]
+    fn sets_a_due_date_or_time() {
+        let parsed = parse(r#"[{"uuid": "a", "description": "Due", "status": "pending", "due": "20240105T143000Z"}]"#).unwrap();
+        let task = &parsed[0].task;
+        assert!(task.due_date.is_some() || task.due_datetime.is_some());
+        assert_eq!(parse_due("20240105T143000Z"), NaiveDate::from_ymd_opt(2024, 1, 5).unwrap().and_hms_opt(14, 30, 0));
+        assert_eq!(parse_due("2024-01-05"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_json() {
+        assert!(parse("not json").is_err());
+        assert!(parse(r#"[{"uuid": "a"}]"#).is_err());
+    }
+}
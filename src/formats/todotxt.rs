@@ -0,0 +1,213 @@
+use crate::formats::ParsedTask;
+use crate::models::project::Project;
+use crate::models::task::{NewTask, Task};
+use crate::utils::error::AppResult;
+use chrono::NaiveDate;
+
+/// Renders tasks as todo.txt lines with priorities, `+project`, `@context`, and `due:` tags.
+pub fn export(tasks: &[Task], projects: &[Project]) -> String {
+    let mut out = String::new();
+    for task in tasks {
+        out.push_str(&export_line(task, projects));
+        out.push('\n');
+    }
+    out
+}
+
+/// Renders a single task as a todo.txt line. Completed tasks keep their priority as a `pri:`
+/// tag, as todo.txt doesn't allow one after the `x`.
+pub fn export_line(task: &Task, projects: &[Project]) -> String {
+    let mut parts = Vec::new();
+    let priority = priority_letter(task.priority);
+    if task.is_done() {
+        parts.push("x".to_string());
+    } else if let Some(letter) = priority {
+        parts.push(format!("({})", letter));
+    }
+    parts.push(task.title.clone());
+    if let Some(project) = projects.iter().find(|p| p.id == task.project_id) {
+        parts.push(format!("+{}", project.name.replace(' ', "_")));
+    }
+    for label in &task.labels {
+        parts.push(format!("@{}", label.replace(' ', "_")));
+    }
+    if let Some(due) = &task.due {
+        parts.push(format!("due:{}", due.date));
+    }
+    if let Some(letter) = priority.filter(|_| task.is_done()) {
+        parts.push(format!("pri:{}", letter));
+    }
+    parts.join(" ")
+}
+
+/// Parses the open tasks of a todo.txt file; completed `x` lines and lines without a title are
+/// skipped.
+pub fn parse(input: &str) -> AppResult<Vec<ParsedTask>> {
+    let mut tasks = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("x ") {
+            continue;
+        }
+        let parsed = parse_line(line).map_err(|e| anyhow::anyhow!("Line {}: {}", i + 1, e))?;
+        if !parsed.task.content.is_empty() {
+            tasks.push(parsed);
+        }
+    }
+    Ok(tasks)
+}
+
+/// Parses a single todo.txt line of an open task. A `pri:` tag sets the priority like a
+/// leading `(A)`.
+pub fn parse_line(line: &str) -> AppResult<ParsedTask> {
+    let mut rest = line;
+    let mut priority = None;
+    let bytes = rest.as_bytes();
+    if bytes.len() >= 4
+        && bytes[0] == b'('
+        && bytes[1].is_ascii_uppercase()
+        && &bytes[2..4] == b") "
+    {
+        priority = Some(priority_from_letter(bytes[1] as char));
+        rest = &rest[4..];
+    }
+    // Skip the optional creation date
+    if let Some((first, after)) = rest.split_once(' ')
+        && NaiveDate::parse_from_str(first, "%Y-%m-%d").is_ok()
+    {
+        rest = after;
+    }
+
+    let mut words = Vec::new();
+    let mut task = NewTask {
+        priority,
+        ..Default::default()
+    };
+    let mut project = None;
+    for word in rest.split_whitespace() {
+        if let Some(name) = word.strip_prefix('+').filter(|n| !n.is_empty()) {
+            project = Some(name.replace('_', " "));
+        } else if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+            task.labels.push(context.to_string());
+        } else if let Some(date) = word.strip_prefix("due:") {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid due date '{}', expected YYYY-MM-DD", date))?;
+            task.due_date = Some(date.to_string());
+        } else if let Some(letter) = word
+            .strip_prefix("pri:")
+            .and_then(|p| p.parse::<char>().ok())
+            .filter(char::is_ascii_uppercase)
+        {
+            task.priority = Some(priority_from_letter(letter));
+        } else {
+            words.push(word);
+        }
+    }
+    task.content = words.join(" ");
+    Ok(ParsedTask {
+        task,
+        project,
+        source: None,
+    })
+}
+
+/// Maps a Todoist API priority to a todo.txt priority letter.
+fn priority_letter(priority: u8) -> Option<char> {
+    match priority {
+        4 => Some('A'),
+        3 => Some('B'),
+        2 => Some('C'),
+        _ => None,
+    }
+}
+
+/// Maps a todo.txt priority letter to a Todoist API priority.
+fn priority_from_letter(letter: char) -> u8 {
+    match letter {
+        'A' => 4,
+        'B' => 3,
+        'C' => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_priority_project_contexts_and_due() {
+        let parsed = parse_line("(A) 2024-05-01 Call Sam +Side_project @calls due:2024-05-20").unwrap();
+        assert_eq!(parsed.task.content, "Call Sam");
+        assert_eq!(parsed.task.priority, Some(4));
+        assert_eq!(parsed.project.as_deref(), Some("Side project"));
+        assert_eq!(parsed.task.labels, ["calls"]);
+        assert_eq!(parsed.task.due_date.as_deref(), Some("2024-05-20"));
+    }
+
+    #[test]
+    fn priority_needs_the_exact_form() {
+        assert_eq!(parse_line("(a) lowercase").unwrap().task.priority, None);
+        assert_eq!(parse_line("(A)no space").unwrap().task.priority, None);
+        assert_eq!(parse_line("(D) low").unwrap().task.priority, Some(1));
+        assert_eq!(parse_line("Urgent pri:A").unwrap().task.priority, Some(4));
+        assert_eq!(parse_line("Not a tag pri:a").unwrap().task.content, "Not a tag pri:a");
+    }
+
+    #[test]
+    fn skips_blank_and_completed_lines() {
+        let parsed = parse("Buy milk\n\n  \nx 2024-05-01 Done already\n+Home @errands\nxylophone lesson\n").unwrap();
+        let titles = parsed.iter().map(|p| p.task.content.as_str()).collect::<Vec<_>>();
+        assert_eq!(titles, ["Buy milk", "xylophone lesson"]);
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let projects = [Project {
+            id: "1".to_string(),
+            name: "Side project".to_string(),
+            is_archived: false,
+            is_favorite: false,
+            is_shared: false,
+            color: String::new(),
+            parent_id: None,
+        }];
+        let task = NewTask {
+            priority: Some(3),
+            project_id: Some("1".to_string()),
+            labels: vec!["calls".to_string()],
+            due_date: Some("2024-05-20".to_string()),
+            ..NewTask::new("Call Sam")
+        }
+        .preview("abc");
+        let line = export_line(&task, &projects);
+        assert_eq!(line, "(B) Call Sam +Side_project @calls due:2024-05-20");
+        let parsed = parse_line(&line).unwrap();
+        assert_eq!(parsed.task.content, "Call Sam");
+        assert_eq!(parsed.task.priority, Some(3));
+        assert_eq!(parsed.project.as_deref(), Some("Side project"));
+    }
+
+    #[test]
+    fn completed_tasks_keep_their_priority_as_a_tag() {
+        let mut task = NewTask {
+            priority: Some(4),
+            ..NewTask::new("Call Sam")
+        }
+        .preview("abc");
+        task.checked = true;
+        let line = export_line(&task, &[]);
+        assert_eq!(line, "x Call Sam pri:A");
+        let parsed = parse_line(line.strip_prefix("x ").unwrap()).unwrap();
+        assert_eq!(parsed.task.content, "Call Sam");
+        assert_eq!(parsed.task.priority, Some(4));
+    }
+
+    #[test]
+    fn rejects_invalid_due_dates() {
+        assert!(parse_line("Call Sam due:tomorrow").is_err());
+        assert!(parse_line("Call Sam due:2024-02-30").is_err());
+        let error = parse("Buy milk\nCall Sam due:soon\n").err().unwrap();
+        assert!(error.to_string().starts_with("Line 2:"), "{}", error);
+    }
+}
@@ -0,0 +1,11 @@
+//! The app behind the `todoist-cli` binary, as a library so benchmarks can drive it directly.
+
+pub mod api;
+pub mod cli;
+pub mod config;
+pub mod controller;
+pub mod db;
+pub mod formats;
+pub mod models;
+pub mod utils;
+pub mod views;
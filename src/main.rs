@@ -1,150 +1,1337 @@
-use crate::controller::app::{App, Mode};
-use crate::utils::error::AppResult;
+use todoist_cli::controller::app::{
+    App, ConflictChoice, DuplicateChoice, Mode, ProjectLoad, REMINDER_INTERVAL, ReviewAction, SPINNER_INTERVAL,
+    SidebarRow, View,
+};
+use todoist_cli::models::task::Task;
+use todoist_cli::controller::columns::{Column, Row};
+use todoist_cli::controller::planner::{self, TimeBlock};
+use todoist_cli::controller::quadrants::QUADRANTS;
+use todoist_cli::controller::trends::{self, ProjectTrend};
+use todoist_cli::utils::error::AppResult;
+use todoist_cli::utils::dates::relative_date;
+use todoist_cli::utils::recurrence::parse_recurrence;
+use todoist_cli::utils::text::{truncate, width, wrap};
 use clap::Parser;
+use futures::StreamExt;
 use crossterm::{
-    event::{self, KeyCode, KeyEvent},
+    cursor::MoveTo,
+    event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    text::{Line, Span},
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Clear, List, ListItem, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::io;
+use std::time::Instant;
 
-mod api;
-mod cli;
-mod controller;
-mod db;
-mod models;
-mod utils;
+use todoist_cli::views::{pane, project_list, terminal_color};
+use todoist_cli::{api, cli};
 
 use cli::commands::{Cli, Commands, process_command};
 
+/// Terminals narrower than this get the compact layout.
+const COMPACT_WIDTH: u16 = 80;
+
+/// Height of the description editor, borders included.
+const DESCRIPTION_HEIGHT: u16 = 10;
+
+/// Most lines the details pane below the list grows to.
+const MAX_DETAIL_LINES: usize = 4;
+
+/// Most activity toasts shown at once; older ones wait out their time hidden.
+const MAX_TOASTS: usize = 3;
+
 /// Renders the TUI based on the app state.
 fn render(f: &mut Frame, app: &mut App) {
+    let compact = f.area().width < COMPACT_WIDTH;
+    let editing_line = matches!(app.mode(), Mode::InsertAdd | Mode::InsertEdit | Mode::Filter | Mode::ReviewDue);
+    let mut details = detail_lines(app, f.area().width.saturating_sub(2) as usize);
+    details.truncate(MAX_DETAIL_LINES);
+    let preview = due_preview(app);
+    let bottom_height = if *app.mode() == Mode::EditDescription {
+        DESCRIPTION_HEIGHT
+    } else if preview.is_some() {
+        4
+    } else if editing_line || details.is_empty() {
+        3
+    } else {
+        details.len() as u16 + 2
+    };
     let chunks = ratatui::layout::Layout::default()
         .direction(ratatui::layout::Direction::Vertical)
         .constraints([
             ratatui::layout::Constraint::Min(0),
-            ratatui::layout::Constraint::Length(3),
+            ratatui::layout::Constraint::Length(bottom_height),
+            ratatui::layout::Constraint::Length(if app.plain_ui() { 1 } else { 0 }),
         ])
-        .split(f.size());
+        .split(f.area());
 
     let mode_str = match app.mode() {
         Mode::Normal => "Normal",
         Mode::InsertAdd => "Insert (Add)",
         Mode::InsertEdit => "Insert (Edit)",
+        Mode::EditDescription => "Description",
+        Mode::Filter => "Filter",
+        Mode::ConfirmDuplicate => "Duplicate?",
+        Mode::Review | Mode::ReviewDue => "Review",
+        Mode::Assign => "Assign",
+        Mode::Place => "Place",
+        Mode::Move => "Move",
+        Mode::ResolveConflict => "Conflict",
     };
     let selected = app.list_state().selected();
-    let items = app
-        .tasks()
-        .iter()
-        .enumerate()
-        .map(|(i, task)| {
-            let prefix = if Some(i) == selected { "> " } else { "  " };
-            let status = if task.checked { "[x]" } else { "[ ]" };
-            ListItem::new(format!("{} {} {}", prefix, status, task.title))
-        })
-        .collect::<Vec<_>>();
-    let list = List::new(items)
-        .block(
-            Block::default()
-                .title(format!("Todoist CLI Task Manager [Mode: {}]", mode_str))
-                .borders(Borders::ALL),
+    let loading_project = app
+        .selected_task()
+        .is_some_and(|task| app.project_load(&task.project_id) == Some(ProjectLoad::Loading));
+    let mode_str = if loading_project {
+        format!("{}, loading project...", mode_str)
+    } else {
+        mode_str.to_string()
+    };
+    let mut title = if compact {
+        format!("[{}]", mode_str)
+    } else {
+        format!("Todoist CLI Task Manager [Mode: {}]", mode_str)
+    };
+    if let Some((done, goal)) = app.daily_progress() {
+        title.push_str(&format!(" {}/{} today", done, goal));
+    }
+    if let Some(status) = app.status() {
+        title.push_str(&format!(" {}", status));
+    }
+    if let Some(progress) = app.progress() {
+        title.push_str(&format!(" {}", progress));
+    }
+    if let Some(query) = app.filter_query() {
+        title.push_str(&format!(" [Filter: {}]", query));
+    }
+    if app.queued_changes() > 0 {
+        title.push_str(&format!(" [{} queued]", app.queued_changes()));
+    }
+    if app.view() == View::Week {
+        render_week(f, app, chunks[0], title, compact);
+    } else if app.view() == View::Day {
+        render_planner(f, app, chunks[0], title, compact);
+    } else if app.view() == View::Quadrants {
+        render_quadrants(f, app, chunks[0], title, compact);
+    } else {
+        // Wide layouts get the project sidebar on the left once projects are known
+        let sidebar = if compact { 0 } else { project_list::width(app) };
+        let columns = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints([
+                ratatui::layout::Constraint::Length(sidebar),
+                ratatui::layout::Constraint::Min(0),
+            ])
+            .split(chunks[0]);
+        if sidebar > 0 {
+            project_list::render(f, app, columns[0]);
+        }
+        // Completed matches from the history get a read-only pane below the list
+        let completed = app.completed_matches().len() as u16;
+        let panes = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                ratatui::layout::Constraint::Min(3),
+                ratatui::layout::Constraint::Length(if completed > 0 { completed.min(6) + 2 } else { 0 }),
+            ])
+            .split(columns[1]);
+        let block = pane(app, title);
+        let plain = app.plain_ui();
+        // Only the rows inside the borders are built; the rest of the list is never formatted
+        // Borders take two columns and the selection prefix three more
+        let (offset, rows) = app.visible_rows(
+            panes[0].height.saturating_sub(2) as usize,
+            panes[0].width.saturating_sub(5) as usize,
+            compact,
         );
-    f.render_stateful_widget(list, chunks[0], app.list_state());
+        let items = rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, (row, is_new, blocked, nudge))| {
+                let is_selected = Some(offset + i) == selected;
+                // Tasks that just arrived in a sync are flagged rather than slipped in unnoticed
+                let prefix = match (is_selected, is_new) {
+                    (true, _) => "> ",
+                    (false, true) => "+ ",
+                    (false, false) => "  ",
+                };
+                // Waiting tasks whose follow-up day has come get a nudge mark
+                let mark = match (nudge, plain) {
+                    (false, _) => Span::raw(" "),
+                    (true, true) => Span::raw("!"),
+                    (true, false) => Span::styled("!", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                };
+                let mut spans = vec![Span::raw(prefix), mark];
+                spans.extend(row_spans(row, plain));
+                let item = ListItem::new(Line::from(spans));
+                if is_selected && plain {
+                    item.style(Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD))
+                } else if is_new && !plain {
+                    item.style(Style::default().add_modifier(Modifier::BOLD))
+                } else if blocked && !plain {
+                    // Blocked tasks can't be started yet, so they recede until their blocker is done
+                    item.style(Style::default().add_modifier(Modifier::DIM))
+                } else {
+                    item
+                }
+            })
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            let hint = Paragraph::new(empty_list_hint(app))
+                .wrap(Wrap { trim: false })
+                .block(block);
+            f.render_widget(hint, panes[0]);
+        } else {
+            f.render_widget(List::new(items).block(block), panes[0]);
+        }
+        if completed > 0 {
+            let items = app
+                .completed_matches()
+                .iter()
+                .map(|task| match task.completed_on() {
+                    Some(date) => ListItem::new(format!("[x] {} (completed {})", task.title, date)),
+                    None => ListItem::new(format!("[x] {}", task.title)),
+                })
+                .collect::<Vec<_>>();
+            let title = format!("Completed ({})", completed);
+            f.render_widget(
+                List::new(items).block(pane(app, title)),
+                panes[1],
+            );
+        }
+    }
 
-    if matches!(app.mode(), Mode::InsertAdd | Mode::InsertEdit) {
-        let input_block = Block::default().title("Title").borders(Borders::ALL);
-        let input = Paragraph::new(app.input_buffer.as_str()).block(input_block);
+    if *app.mode() == Mode::EditDescription {
+        render_description_editor(f, app, chunks[1]);
+    } else if editing_line {
+        let label = match app.mode() {
+            Mode::ReviewDue => "New due date, e.g. next fri (Esc: back)",
+            Mode::Filter if app.include_completed() => "Filter (empty clears, Tab: skip completed)",
+            Mode::Filter => "Filter (empty clears, Tab: include completed)",
+            _ => "Title",
+        };
+        let input_block = pane(app, label);
+        let mut text = app.input_buffer.clone();
+        if let Some(preview) = &preview {
+            text.push('\n');
+            text.push_str(preview);
+        }
+        let input = Paragraph::new(text).block(input_block);
         f.set_cursor_position((
             chunks[1].x + 2 + app.input_buffer.len() as u16,
             chunks[1].y + 1,
         ));
         f.render_widget(input, chunks[1]);
+    } else if !details.is_empty() {
+        let details = Paragraph::new(details.join("\n"))
+            .block(pane(app, "Details (e: edit description)"));
+        f.render_widget(details, chunks[1]);
+    }
+
+    if app.welcome() {
+        let text = "Welcome to todoist-cli!\n\n\
+            Your Todoist tasks sync in when the app starts, and the local cache keeps them \
+            available offline.\n\n\
+            a add a task   / filter   w week view   s sync   q quit\n\n\
+            Press any key to get started.";
+        let area = centered(f.area(), 64, 11);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(pane(app, "Welcome")),
+            area,
+        );
+    } else if let Some((conflict, count)) = app.conflict().filter(|_| *app.mode() == Mode::ResolveConflict) {
+        // Mine on the left, Todoist's on the right, one row per field that differs
+        let area = centered(f.area(), 80, conflict.differences().len() as u16 + 6);
+        let column = (area.width.saturating_sub(16) / 2) as usize;
+        let mut lines = vec![Line::from(format!("{:<12}{:<w$}  {}", "", "Mine", "Todoist", w = column))];
+        for (field, mine, theirs) in conflict.differences() {
+            lines.push(Line::from(format!(
+                "{:<12}{:<w$}  {}",
+                field,
+                truncate(&mine, column),
+                truncate(&theirs, column),
+                w = column
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("[m] keep mine   [t] keep Todoist's   [Esc] decide later"));
+        let title = format!("Edited offline and in Todoist: {} ({} left)", conflict.mine.title, count);
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(pane(app, title)), area);
+    } else if let Some((title, existing)) = app.duplicate() {
+        let text = format!(
+            "\"{}\" looks like an existing task:\n\n  {}\n\n[j] jump to it   [a] add anyway   [Esc] cancel",
+            title, existing.title
+        );
+        let area = centered(f.area(), 60, 7);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(pane(app, "Possible duplicate")),
+            area,
+        );
+    } else if let Some(task) = app.review_task() {
+        let text = format!(
+            "{}\n\n[k] keep   [r] reschedule   [d] delete   [p] deprioritize   [Esc] stop",
+            cli::review::describe(task)
+        );
+        let area = centered(f.area(), 80, 7);
+        f.render_widget(Clear, area);
+        let title = format!("Review ({} left)", app.review_remaining());
+        f.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(pane(app, title)),
+            area,
+        );
+    } else if let Some((names, selected)) = app.assign_choices() {
+        let lines = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Line::from(format!("{}{}", if i == selected { "> " } else { "  " }, name)))
+            .collect::<Vec<_>>();
+        let area = centered(f.area(), 40, lines.len() as u16 + 2);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(pane(app, "Assign to (Enter: assign, Esc: cancel)")),
+            area,
+        );
+    } else if let Some((names, selected)) = app.project_choices() {
+        // Scrolled so the selected project stays in view when they don't all fit
+        let rows = (f.area().height.saturating_sub(4) as usize).max(1);
+        let lines = names
+            .iter()
+            .enumerate()
+            .skip((selected + 1).saturating_sub(rows))
+            .take(rows)
+            .map(|(i, name)| Line::from(format!("{}{}", if i == selected { "> " } else { "  " }, name)))
+            .collect::<Vec<_>>();
+        let width = names.iter().map(|name| width(name)).max().unwrap_or(0) as u16 + 4;
+        let area = centered(f.area(), width.max(40), lines.len() as u16 + 2);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(pane(app, "Move to (Enter: move, Esc: cancel)")),
+            area,
+        );
+    } else if let Some(task) = app.reminder() {
+        let text = format!(
+            "{}\n\n{}\n\n[g] go to task   [Esc] dismiss",
+            task.title,
+            reminder_message(task)
+        );
+        let area = centered(f.area(), 50, 7);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(pane(app, "Reminder")),
+            area,
+        );
+    }
+
+    render_toasts(f, app);
+
+    if app.api_overlay() {
+        render_api_overlay(f, app);
+    }
+
+    if let Some(trends) = app.stats_overlay() {
+        render_stats_overlay(f, app, trends);
+    }
+
+    if app.plain_ui() {
+        f.render_widget(Paragraph::new(announcement(app)), chunks[2]);
+        // Screen readers follow the cursor, so park it on the status line unless typing
+        if *app.mode() != Mode::EditDescription && !editing_line {
+            f.set_cursor_position((chunks[2].x, chunks[2].y));
+        }
+    }
+}
+
+/// Splits a list row into spans, drawing projects and labels in their Todoist colors. Plain UI
+/// keeps the terminal's own colors for contrast.
+fn row_spans(row: &Row, plain: bool) -> Vec<Span<'_>> {
+    if plain {
+        return vec![Span::raw(row.text.as_str())];
+    }
+    let mut spans = Vec::new();
+    let mut at = 0;
+    for (range, rgb) in &row.colors {
+        spans.push(Span::raw(&row.text[at..range.start]));
+        spans.push(Span::styled(&row.text[range.clone()], Style::default().fg(terminal_color(*rgb))));
+        at = range.end;
+    }
+    spans.push(Span::raw(&row.text[at..]));
+    spans
+}
+
+/// Describes the current state in one line for `--plain-ui`: the open popup, or the mode and
+/// selected task, followed by any status message.
+fn announcement(app: &App) -> String {
+    let mut text = if app.welcome() {
+        "Welcome to todoist-cli. Press any key to get started.".to_string()
+    } else if let Some((conflict, count)) = app.conflict().filter(|_| *app.mode() == Mode::ResolveConflict) {
+        let differences = conflict
+            .differences()
+            .into_iter()
+            .map(|(field, mine, theirs)| format!("{}: mine \"{}\", Todoist's \"{}\"", field, mine, theirs))
+            .collect::<Vec<_>>();
+        format!(
+            "\"{}\" was edited offline and in Todoist, {} left. {}. m: keep mine, t: keep Todoist's, Esc: decide later.",
+            conflict.mine.title,
+            count,
+            differences.join("; ")
+        )
+    } else if let Some((title, existing)) = app.duplicate() {
+        format!(
+            "\"{}\" looks like existing task \"{}\". j: jump to it, a: add anyway, Esc: cancel.",
+            title, existing.title
+        )
+    } else if let Some(task) = app.review_task() {
+        format!("Review, {} left: {}.", app.review_remaining(), cli::review::describe(task))
+    } else if let Some((names, selected)) = app.assign_choices() {
+        format!("Assign to: {}. j/k: choose, Enter: assign, Esc: cancel.", names[selected])
+    } else if let Some((names, selected)) = app.project_choices() {
+        format!("Move to: {}. j/k: choose, Enter: move, Esc: cancel.", names[selected].trim_start())
+    } else if app.sidebar_focused() {
+        let rows = app.sidebar_rows();
+        let shown = match rows.get(app.sidebar_selected()) {
+            Some(SidebarRow::Project(_, p)) => p.name.clone(),
+            Some(SidebarRow::Label(l)) => format!("@{}", l.name),
+            Some(SidebarRow::Filter(f)) => f.name.clone(),
+            _ => "All tasks".to_string(),
+        };
+        format!("Projects: {}. j/k: choose, A: show or hide archived, l: back to the list.", shown)
+    } else if let Some((task, start)) = app.placing() {
+        format!(
+            "Placing {} at {}. j/k: move half an hour, J/K: an hour, Enter: schedule, Esc: cancel.",
+            task.title,
+            planner::time_of(start).format("%H:%M")
+        )
+    } else if let Some(task) = app.reminder() {
+        format!("Reminder: {}. {}. g: go to task, Esc: dismiss.", task.title, reminder_message(task))
+    } else {
+        let mode = match app.mode() {
+            Mode::Normal => "Normal",
+            Mode::InsertAdd => "Adding a task",
+            Mode::InsertEdit => "Editing the title",
+            Mode::EditDescription => "Editing the description",
+            Mode::Filter => "Filtering",
+            Mode::ConfirmDuplicate => "Possible duplicate",
+            Mode::Review | Mode::ReviewDue => "Review",
+            Mode::Assign => "Assigning",
+            Mode::Place => "Placing",
+            Mode::Move => "Moving",
+            Mode::ResolveConflict => "Resolving a conflict",
+        };
+        match app.selected_task() {
+            Some(task) => {
+                let mut selected = format!("{}. {}", mode, task.title);
+                if let Some(due) = &task.due {
+                    let today = chrono::Local::now().date_naive();
+                    selected.push_str(&format!(", {}", due.display(today, app.settings().relative_dates)));
+                }
+                if task.is_done() {
+                    selected.push_str(", done");
+                }
+                if app.is_new(task.id) {
+                    selected.push_str(", new");
+                }
+                if app.is_blocked(task) {
+                    selected.push_str(", blocked");
+                }
+                if app.needs_nudge(task) {
+                    selected.push_str(", needs a follow-up");
+                }
+                selected + "."
+            }
+            None => format!("{}. No task selected.", mode),
+        }
+    };
+    if let Some(status) = app.status() {
+        text.push_str(&format!(" {}", status));
+    }
+    for toast in app.toasts() {
+        text.push_str(&format!(" {}.", toast));
+    }
+    text
+}
+
+/// Draws collaborator activity in the top-right corner, newest at the bottom.
+fn render_toasts(f: &mut Frame, app: &App) {
+    let toasts = app.toasts().collect::<Vec<_>>();
+    let shown = &toasts[toasts.len().saturating_sub(MAX_TOASTS)..];
+    let Some(longest) = shown.iter().map(|t| width(t)).max() else {
+        return;
+    };
+    // Below the list's title, at most half the screen wide
+    let area = f.area();
+    let toast_width = (longest as u16 + 2).clamp(20, (area.width / 2).max(20)).min(area.width);
+    let height = (shown.len() as u16 + 2).min(area.height.saturating_sub(1));
+    let area = Rect::new(area.right() - toast_width, area.y + 1, toast_width, height);
+    let lines = shown
+        .iter()
+        .map(|t| Line::from(truncate(t, toast_width.saturating_sub(2) as usize)))
+        .collect::<Vec<_>>();
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(pane(app, "Activity")), area);
+}
+
+/// Previews the next dates of a recurring due date being typed, e.g. while rescheduling.
+fn due_preview(app: &App) -> Option<String> {
+    if *app.mode() != Mode::ReviewDue {
+        return None;
+    }
+    let today = chrono::Local::now().date_naive();
+    let recurrence = parse_recurrence(&app.input_buffer, today)?;
+    let dates = recurrence.occurrences(5).iter().map(|date| date.format("%a %b %-d")).collect::<Vec<_>>();
+    Some(format!("Next: {}", dates.join(", ")))
+}
+
+/// Renders this session's request counts and latencies per endpoint, for diagnosing rate limits.
+fn render_api_overlay(f: &mut Frame, app: &App) {
+    let stats = app.api_stats();
+    let lines = if stats.is_empty() {
+        vec!["No API requests yet".to_string()]
+    } else {
+        api::metrics::format_table(&stats)
+    };
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0) as u16 + 2;
+    let area = centered(f.area(), width.max(30), lines.len() as u16 + 2);
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines.join("\n"))
+            .block(pane(app, "API usage (D: close)")),
+        area,
+    );
+}
+
+/// Renders per-project velocity (tasks completed per week) and burndown (tasks open at the end
+/// of each week) as sparklines over the last few weeks, oldest first.
+fn render_stats_overlay(f: &mut Frame, app: &App, trends: &[ProjectTrend]) {
+    let name_width = trends.iter().map(|t| width(&t.project)).max().unwrap_or(0).max(7);
+    let mut lines = vec![Line::from(format!(
+        "{:<name_width$}  {:<w$}  {:<w$}",
+        "Project",
+        "Done/week",
+        "Open",
+        w = trends::WEEKS + 5,
+    ))];
+    if trends.is_empty() {
+        lines.push(Line::from("No tasks yet"));
+    }
+    for trend in trends {
+        let done = trend.weeks.iter().map(|w| w.completed).collect::<Vec<_>>();
+        let open = trend.weeks.iter().map(|w| w.open).collect::<Vec<_>>();
+        let name = format!("{}{}", trend.project, " ".repeat(name_width - width(&trend.project)));
+        lines.push(Line::from(vec![
+            Span::raw(format!("{}  ", name)),
+            Span::styled(trends::sparkline(&done), Style::default().fg(Color::Green)),
+            Span::raw(format!(" {:<4}  ", done.last().unwrap_or(&0))),
+            Span::styled(trends::sparkline(&open), Style::default().fg(Color::Yellow)),
+            Span::raw(format!(" {}", open.last().unwrap_or(&0))),
+        ]));
+    }
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let area = centered(f.area(), content_width.max(30) + 2, lines.len() as u16 + 2);
+    f.render_widget(Clear, area);
+    let title = format!("Last {} weeks (S: close)", trends::WEEKS);
+    f.render_widget(Paragraph::new(lines).block(pane(app, title)), area);
+}
+
+/// Renders the multi-line description editor, scrolled so the line being typed stays in view.
+fn render_description_editor(f: &mut Frame, app: &App, area: Rect) {
+    let block = pane(app, "Description (Enter: new line, Ctrl+S: save, Esc: cancel)");
+    let inner = block.inner(area);
+    // `split` rather than `lines` so a trailing newline puts the cursor on a fresh line
+    let lines = app.input_buffer.split('\n').collect::<Vec<_>>();
+    let scroll = lines.len().saturating_sub(inner.height.max(1) as usize) as u16;
+    let last = lines.last().map_or(0, |line| line.chars().count()) as u16;
+    f.set_cursor_position((
+        inner.x + last.min(inner.width.saturating_sub(1)),
+        inner.y + (lines.len() as u16 - 1 - scroll),
+    ));
+    f.render_widget(
+        Paragraph::new(app.input_buffer.as_str())
+            .block(block)
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+/// Lines for the pane below the list, wrapped to `width` columns: the selected task's full title
+/// when the list cuts it off, the first line of its description, its location reminder, what
+/// it's blocked by and when to follow up if it's waiting on someone.
+fn detail_lines(app: &App, width: usize) -> Vec<String> {
+    let Some(task) = app.selected_task() else {
+        return Vec::new();
+    };
+    let mut details = Vec::new();
+    if app.selected_title_cut() {
+        details.push(task.title.clone());
+    }
+    if let Some(first_line) = task.description.lines().find(|line| !line.trim().is_empty()) {
+        let more = if task.description.trim().lines().count() > 1 { " …" } else { "" };
+        details.push(format!("{}{}", first_line.trim(), more));
+    }
+    // Location reminders can only be managed in Todoist itself
+    if let Some(place) = &task.location {
+        details.push(format!("📍 Reminder at {}", place));
+    }
+    let blockers = app.blockers_of(task);
+    if !blockers.is_empty() {
+        details.push(format!("Blocked by: {}", blockers.join(", ")));
+    }
+    if let Some(date) = app.followup_on(task) {
+        let today = chrono::Local::now().date_naive();
+        details.push(format!("Follow up: {}", relative_date(date, today)));
+    }
+    details.iter().flat_map(|detail| wrap(detail, width)).collect()
+}
+
+/// Explains an empty task list and what to do about it.
+fn empty_list_hint(app: &App) -> &'static str {
+    if app.filter_query().is_some() {
+        "No tasks match this filter.\n\nPress / to change it, or submit an empty filter to clear it."
+    } else if app.progress().is_some() {
+        "Loading tasks…"
+    } else {
+        "No tasks yet.\n\nPress a to add one, or s to sync with Todoist."
+    }
+}
+
+/// Describes when a reminded task is due, e.g. "Due at 15:00".
+fn reminder_message(task: &Task) -> String {
+    task.due
+        .as_ref()
+        .and_then(|due| due.naive_datetime())
+        .map(|at| format!("Due at {}", at.format("%H:%M")))
+        .unwrap_or_default()
+}
+
+/// Renders the Eisenhower matrix as a 2×2 grid, important tasks on top and urgent ones on the
+/// left, highlighting the selected quadrant and task. Compact layouts show only the selected
+/// quadrant.
+fn render_quadrants(f: &mut Frame, app: &App, area: Rect, title: String, compact: bool) {
+    let outer = pane(app, title);
+    let inner = outer.inner(area);
+    f.render_widget(outer, area);
+    let halves = |area: Rect, direction| {
+        ratatui::layout::Layout::default()
+            .direction(direction)
+            .constraints([ratatui::layout::Constraint::Ratio(1, 2); 2])
+            .split(area)
+    };
+    let cells = if compact {
+        vec![inner; 4]
+    } else {
+        halves(inner, ratatui::layout::Direction::Vertical)
+            .iter()
+            .flat_map(|row| halves(*row, ratatui::layout::Direction::Horizontal).to_vec())
+            .collect()
+    };
+    let quadrants = app.quadrant_tasks();
+    if quadrants.iter().all(|tasks| tasks.is_empty()) {
+        let hint = "No open tasks to sort.\n\nPress a to add one, or E for the list.";
+        f.render_widget(Paragraph::new(hint).wrap(Wrap { trim: false }), inner);
+        return;
+    }
+    let today = chrono::Local::now().date_naive();
+    let view = app.quadrant_view();
+    for (i, tasks) in quadrants.into_iter().enumerate() {
+        let selected_quadrant = i == view.quadrant();
+        if compact && !selected_quadrant {
+            continue;
+        }
+        let cell = cells[i];
+        // Scrolled so the selected task stays in view
+        let height = cell.height.saturating_sub(2).max(1) as usize;
+        let skip = if selected_quadrant { (view.row() + 1).saturating_sub(height) } else { 0 };
+        let items = tasks
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .map(|(row, task)| {
+                let prefix = if selected_quadrant && row == view.row() { ">" } else { " " };
+                let due = task
+                    .due
+                    .as_ref()
+                    .and_then(|d| d.local_date())
+                    .map(|date| format!(" · {}", relative_date(date, today)))
+                    .unwrap_or_default();
+                let row = format!("{} {}{}", prefix, task.title, due);
+                // Borders take two columns
+                ListItem::new(truncate(&row, cell.width.saturating_sub(2) as usize))
+            })
+            .collect::<Vec<_>>();
+        let mut quadrant_title = format!("{} ({})", QUADRANTS[i].title(), tasks.len());
+        if compact {
+            quadrant_title.push_str(&format!(" {}/4", i + 1));
+        }
+        let style = if selected_quadrant {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        f.render_widget(List::new(items).block(pane(app, quadrant_title).border_style(style)), cell);
+    }
+}
+
+/// Renders seven day columns for the week view, highlighting the selected day and task.
+fn render_week(f: &mut Frame, app: &App, area: Rect, title: String, compact: bool) {
+    let outer = pane(app, title);
+    let inner = outer.inner(area);
+    f.render_widget(outer, area);
+    // Compact layouts only have room for the selected day
+    let shown = if compact { 1 } else { 7 };
+    let columns = ratatui::layout::Layout::default()
+        .direction(ratatui::layout::Direction::Horizontal)
+        .constraints(vec![ratatui::layout::Constraint::Ratio(1, shown); shown as usize])
+        .split(inner);
+    let today = chrono::Local::now().date_naive();
+    let week = app.week();
+    let week_days = app.week_days();
+    if week_days.iter().all(|(_, tasks)| tasks.is_empty()) {
+        let hint = "Nothing due this week.\n\n\
+            Press < or > to see other weeks, a to add a task on the selected day, or w for the list.";
+        f.render_widget(Paragraph::new(hint).wrap(Wrap { trim: false }), inner);
+        return;
+    }
+    let days = week_days
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !compact || *i == week.day());
+    for (column, (i, (date, tasks))) in days.enumerate() {
+        let selected_day = i == week.day();
+        let items = tasks
+            .iter()
+            .enumerate()
+            .map(|(row, task)| {
+                let prefix = if selected_day && row == week.row() {
+                    ">"
+                } else if app.is_new(task.id) {
+                    "+"
+                } else {
+                    " "
+                };
+                let time = task
+                    .due
+                    .as_ref()
+                    .and_then(|d| d.naive_datetime())
+                    .map(|at| format!("{} ", at.format("%H:%M")))
+                    .unwrap_or_default();
+                let check = if task.is_done() { "x " } else { "" };
+                let row = format!("{}{}{}{}", prefix, check, time, task.title);
+                // Borders take two columns
+                ListItem::new(truncate(&row, columns[column].width.saturating_sub(2) as usize))
+            })
+            .collect::<Vec<_>>();
+        let mut day_title = date.format("%a %d").to_string();
+        if date == today {
+            day_title.push_str(" *");
+        }
+        if compact {
+            day_title.push_str(&format!(" ({}/7)", i + 1));
+        }
+        let style = if selected_day {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let list = List::new(items).block(
+            pane(app, day_title).border_style(style),
+        );
+        f.render_widget(list, columns[column]);
+    }
+}
+
+/// Renders the day planner: timed tasks as blocks on a timeline of half-hour slots, with the
+/// date-only tasks beside it (below on narrow terminals) waiting to be placed.
+fn render_planner(f: &mut Frame, app: &App, area: Rect, title: String, compact: bool) {
+    let outer = pane(app, title);
+    let inner = outer.inner(area);
+    f.render_widget(outer, area);
+    let date = app.planner().date();
+    let tasks = app.planner_tasks();
+    if tasks.is_empty() {
+        let hint = format!(
+            "Nothing due on {}.\n\n\
+            Press h or l for other days, a to add a task on this day, or P for the list.",
+            date.format("%a %-d %b")
+        );
+        f.render_widget(Paragraph::new(hint).wrap(Wrap { trim: false }), inner);
+        return;
+    }
+    let selected = app.selected_task().map(|t| t.id);
+    let placing = app.planner().placing();
+    let (blocks, unscheduled) = planner::time_blocks(&tasks, placing);
+    let side = if unscheduled.is_empty() {
+        ratatui::layout::Constraint::Length(0)
+    } else if compact {
+        ratatui::layout::Constraint::Length((unscheduled.len() as u16 + 2).min(inner.height / 3))
+    } else {
+        ratatui::layout::Constraint::Percentage(35)
+    };
+    let panes = ratatui::layout::Layout::default()
+        .direction(if compact {
+            ratatui::layout::Direction::Vertical
+        } else {
+            ratatui::layout::Direction::Horizontal
+        })
+        .constraints([ratatui::layout::Constraint::Min(0), side])
+        .split(inner);
+
+    let now = chrono::Local::now().naive_local();
+    let now_slot = (date == now.date()).then(|| planner::snap(planner::minute_of_day(now.time())));
+    let slots = planner::slots(&blocks);
+    // Borders, the time label with the current-time marker, and the bar take nine columns
+    let text_width = panes[0].width.saturating_sub(2 + 9) as usize;
+    let lines = slots
+        .iter()
+        .map(|&slot| {
+            let label = if slot.is_multiple_of(60) {
+                planner::time_of(slot).format("%H:%M").to_string()
+            } else {
+                format!("  :{:02}", slot % 60)
+            };
+            let marker = if now_slot == Some(slot) { "*" } else { " " };
+            let covered = blocks.iter().any(|b| b.start <= slot && slot < b.start + b.minutes);
+            let starting = blocks
+                .iter()
+                .filter(|b| planner::snap(b.start) == slot)
+                .map(|b| block_label(b, selected))
+                .collect::<Vec<_>>();
+            let bar = if covered || !starting.is_empty() { "┃" } else { "│" };
+            let text = truncate(&starting.join("; "), text_width);
+            let line = format!("{}{} {} {}", label, marker, bar, text);
+            if placing.is_some_and(|(id, start)| planner::snap(start) == slot && Some(id) == selected) {
+                Line::styled(line, Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                Line::raw(line)
+            }
+        })
+        .collect::<Vec<_>>();
+    // Keep the task being placed or selected in view, else the current time
+    let focus = placing
+        .map(|(_, start)| start)
+        .or_else(|| blocks.iter().find(|b| Some(b.task.id) == selected).map(|b| b.start))
+        .or(now_slot)
+        .and_then(|start| slots.iter().position(|&slot| slot == planner::snap(start)))
+        .unwrap_or(0);
+    let height = panes[0].height.saturating_sub(2) as usize;
+    let offset = focus.saturating_sub(height / 3).min(slots.len().saturating_sub(height));
+    let mut day_title = date.format("%a %d %b").to_string();
+    if now_slot.is_some() {
+        day_title.push_str(" *");
+    }
+    f.render_widget(
+        Paragraph::new(lines).scroll((offset as u16, 0)).block(pane(app, day_title)),
+        panes[0],
+    );
+
+    let items = unscheduled
+        .iter()
+        .map(|task| {
+            let prefix = if Some(task.id) == selected { ">" } else { " " };
+            let check = if task.is_done() { "x " } else { "" };
+            let row = format!("{}{}{}", prefix, check, task.title);
+            let item = ListItem::new(truncate(&row, panes[1].width.saturating_sub(2) as usize));
+            if app.is_blocked(task) && !app.plain_ui() {
+                item.style(Style::default().add_modifier(Modifier::DIM))
+            } else {
+                item
+            }
+        })
+        .collect::<Vec<_>>();
+    f.render_widget(List::new(items).block(pane(app, "Unscheduled (Enter: place)")), panes[1]);
+}
+
+/// A timeline entry: the task's title with its length, marked when selected or done, and its
+/// exact start when that's between slots.
+fn block_label(block: &TimeBlock, selected: Option<usize>) -> String {
+    let mut label = String::new();
+    if Some(block.task.id) == selected {
+        label.push('>');
+    }
+    if block.task.is_done() {
+        label.push_str("x ");
     }
+    if planner::snap(block.start) != block.start {
+        label.push_str(&format!("{} ", planner::time_of(block.start).format("%H:%M")));
+    }
+    format!("{}{} ({})", label, block.task.title, planner::format_minutes(block.minutes))
+}
+
+/// A rectangle of at most `width` x `height` centered in `area`.
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
 }
 
 /// Runs the TUI application.
-async fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> AppResult<()> {
+async fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    offline: bool,
+) -> AppResult<()> {
+    let mut events = EventStream::new();
+    let mut reminder_check = Instant::now();
+    // Collaborator activity is only checked when configured, since each check is an API call
+    let activity_interval = app
+        .settings()
+        .activity
+        .as_ref()
+        .filter(|_| !offline)
+        .map(|a| std::time::Duration::from_secs(a.interval.max(1)));
+    let mut activity_check = activity_interval.map(|_| Instant::now());
+    if !offline {
+        app.enable_periodic_sync();
+    }
+    // Only draw when something may have changed; ratatui then writes just the changed cells
+    let mut redraw = true;
     loop {
-        terminal.draw(|f| render(f, app))?;
-
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let event::Event::Key(KeyEvent { code, .. }) = event::read()? {
-                match app.mode() {
-                    Mode::Normal => match code {
-                        KeyCode::Char('q') => break,
-                        KeyCode::Char('j') => app.next(),
-                        KeyCode::Char('k') => app.previous(),
-                        KeyCode::Char('i') => app.enter_insert_edit_mode(),
-                        KeyCode::Char('a') => app.enter_insert_add_mode(),
-                        KeyCode::Char('d') => {
-                            if let Some(i) = app.list_state().selected() {
-                                if let Some(task) = app.tasks().get(i) {
-                                    app.delete_task(task.id).await?;
-                                }
-                            }
-                            // Force redraw after deletion
-                            terminal.clear()?;
-                        }
-                        KeyCode::Char('t') => {
-                            app.toggle_task().await?;
-                            terminal.clear()?;
-                        }
-                        _ => {}
-                    },
-                    Mode::InsertAdd | Mode::InsertEdit => match code {
-                        KeyCode::Enter => {
-                            app.exit_insert_mode().await?;
-                            // Compute new index before mutable borrow
-                            let new_index = if !app.tasks().is_empty() {
-                                Some(app.tasks().len() - 1)
-                            } else {
-                                None
-                            };
-                            app.list_state().select(new_index);
-                            terminal.clear()?;
-                        }
-                        KeyCode::Esc => {
-                            app.exit_insert_mode().await?;
-                            terminal.clear()?;
-                        }
-                        KeyCode::Char(c) => app.handle_input(c),
-                        KeyCode::Backspace => app.handle_backspace(),
-                        _ => {}
-                    },
+        if redraw {
+            terminal.draw(|f| render(f, app))?;
+        }
+        redraw = true;
+
+        // Sleep until input arrives, a background fetch finishes, pending changes are due to be saved,
+        // a periodic sync is due, or the spinner needs its next frame
+        let flush_at = app.flush_deadline();
+        let sync_at = app.sync_deadline();
+        let fade_at = app.fade_deadline();
+        let spinning = app.progress().is_some();
+        let key = tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(KeyEvent { code, modifiers, .. }))) => (code, modifiers),
+                // Resizes and other events only need a redraw
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => break,
+            },
+            update = app.wait_for_background() => {
+                app.apply_background(update).await?;
+                continue;
+            }
+            _ = async {
+                match flush_at {
+                    Some(at) => tokio::time::sleep_until(at.into()).await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                app.flush_cache()?;
+                redraw = false;
+                continue;
+            }
+            _ = async {
+                match sync_at {
+                    Some(at) => tokio::time::sleep_until(at.into()).await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                app.start_background_sync();
+                continue;
+            }
+            _ = tokio::time::sleep_until(reminder_check.into()) => {
+                reminder_check = Instant::now() + REMINDER_INTERVAL;
+                let fired = app.fire_reminders()?;
+                redraw = !fired.is_empty();
+                for id in fired {
+                    if let Some(task) = app.tasks().iter().find(|t| t.id == id) {
+                        // The popup still shows when there is no notification daemon
+                        let _ = cli::notify::send(task, &reminder_message(task));
+                    }
+                }
+                continue;
+            }
+            _ = async {
+                match activity_check {
+                    Some(at) => tokio::time::sleep_until(at.into()).await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                activity_check = activity_interval.map(|interval| Instant::now() + interval);
+                redraw = app.check_activity().await;
+                continue;
+            }
+            _ = async {
+                match fade_at {
+                    Some(at) => tokio::time::sleep_until(at.into()).await,
+                    None => std::future::pending().await,
                 }
+            } => {
+                app.fade();
+                continue;
             }
+            // Keep the progress spinner moving
+            _ = async {
+                if spinning {
+                    tokio::time::sleep(SPINNER_INTERVAL).await
+                } else {
+                    std::future::pending().await
+                }
+            } => continue,
+        };
+
+        let (code, modifiers) = key;
+        match app.mode() {
+            Mode::Normal => match code {
+                _ if app.welcome() && code != KeyCode::Char('q') => app.dismiss_welcome(),
+                KeyCode::Char('g') if app.reminder().is_some() => app.jump_to_reminder(),
+                KeyCode::Esc => app.dismiss_reminder(),
+                KeyCode::Char('q') => break,
+                KeyCode::Char('j') if app.sidebar_focused() => app.shift_sidebar(1),
+                KeyCode::Char('k') if app.sidebar_focused() => app.shift_sidebar(-1),
+                KeyCode::Enter if app.sidebar_focused() => app.focus_sidebar(false),
+                KeyCode::Char('A') if app.sidebar_focused() => app.toggle_archived_projects().await,
+                KeyCode::Tab => app.focus_sidebar(!app.sidebar_focused()),
+                KeyCode::Char('h') if app.view() == View::List => app.focus_sidebar(true),
+                KeyCode::Char('l') if app.view() == View::List => app.focus_sidebar(false),
+                KeyCode::Char('h') | KeyCode::Char('l') if app.view() == View::Quadrants => app.shift_quadrant_column(),
+                KeyCode::Char('j') => app.next(),
+                KeyCode::Char('k') => app.previous(),
+                KeyCode::Char('i') => app.enter_insert_edit_mode(),
+                KeyCode::Char('e') => app.enter_description_mode(),
+                KeyCode::Char('D') => app.toggle_api_overlay(),
+                KeyCode::Char('S') => {
+                    let result = app.toggle_stats_overlay().await;
+                    app.report_failure("Loading stats", result);
+                }
+                KeyCode::Char('a') => app.enter_insert_add_mode(),
+                KeyCode::Char('d') => {
+                    if let Some(id) = app.selected_task().map(|t| t.id) {
+                        let result = app.delete_task(id).await;
+                        app.report_failure("Deleting", result);
+                    }
+                }
+                KeyCode::Char('t') => {
+                    let result = app.toggle_task().await;
+                    app.report_failure("Updating the task", result);
+                }
+                KeyCode::Char('p') => {
+                    if let Some(project_id) = app.selected_task().map(|t| t.project_id.clone()) {
+                        app.request_project_load(&project_id);
+                    }
+                }
+                KeyCode::Char('/') => app.enter_filter_mode(),
+                KeyCode::Char('w') => app.toggle_view(),
+                KeyCode::Char('P') => app.toggle_planner(),
+                KeyCode::Char('E') => app.toggle_quadrants(),
+                KeyCode::Enter if app.view() == View::Day => app.enter_place_mode(),
+                KeyCode::Char('R') => {
+                    let result = app.enter_review_mode();
+                    app.report_failure("Starting the review", result);
+                }
+                KeyCode::Char('r') => {
+                    app.select_random();
+                }
+                KeyCode::Char('o') => app.open_selected_in_browser(),
+                KeyCode::Char('y') => app.copy_selected(false),
+                KeyCode::Char('Y') => app.copy_selected(true),
+                KeyCode::Char('s') => app.start_background_sync(),
+                KeyCode::Char('h') => app.shift_day(-1),
+                KeyCode::Char('l') => app.shift_day(1),
+                KeyCode::Char('<') => app.shift_week(-1),
+                KeyCode::Char('>') => app.shift_week(1),
+                KeyCode::Char('m') => app.assign_selected_to_me().await,
+                KeyCode::Char('M') => app.enter_assign_mode().await,
+                KeyCode::Char('#') => app.enter_move_mode().await,
+                KeyCode::Char('H') => {
+                    let result = app.move_selected_task(-1).await;
+                    app.report_failure("Rescheduling", result);
+                }
+                KeyCode::Char('L') => {
+                    let result = app.move_selected_task(1).await;
+                    app.report_failure("Rescheduling", result);
+                }
+                _ => redraw = false,
+            },
+            Mode::InsertAdd | Mode::InsertEdit => match code {
+                KeyCode::Enter => {
+                    let result = app.exit_insert_mode().await;
+                    app.report_failure("Saving", result);
+                    // Keep the selection while the duplicate prompt is open
+                    if *app.mode() == Mode::Normal {
+                        app.select_last();
+                    }
+                }
+                KeyCode::Esc => {
+                    let result = app.exit_insert_mode().await;
+                    app.report_failure("Saving", result);
+                }
+                KeyCode::Char(c) => app.handle_input(c),
+                KeyCode::Backspace => app.handle_backspace(),
+                _ => redraw = false,
+            },
+            Mode::EditDescription => match code {
+                KeyCode::Char('s') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    let result = app.save_description().await;
+                    app.report_failure("Saving the description", result);
+                }
+                // Shift+Enter arrives as a plain Enter in most terminals, so both add a line
+                KeyCode::Enter => app.handle_input('\n'),
+                KeyCode::Esc => app.cancel_input(),
+                KeyCode::Char(c) => app.handle_input(c),
+                KeyCode::Backspace => app.handle_backspace(),
+                _ => redraw = false,
+            },
+            Mode::ResolveConflict => match code {
+                KeyCode::Char('m') => {
+                    let result = app.resolve_conflict(1, ConflictChoice::KeepMine).await;
+                    app.report_failure("Keeping your edit", result);
+                }
+                KeyCode::Char('t') => {
+                    let result = app.resolve_conflict(1, ConflictChoice::KeepTheirs).await;
+                    app.report_failure("Keeping Todoist's copy", result);
+                }
+                KeyCode::Esc => {
+                    let result = app.resolve_conflict(1, ConflictChoice::Later).await;
+                    app.report_failure("Closing the conflict", result);
+                }
+                _ => redraw = false,
+            },
+            Mode::ConfirmDuplicate => match code {
+                KeyCode::Char('j') | KeyCode::Enter => {
+                    let result = app.resolve_duplicate(DuplicateChoice::Jump).await;
+                    app.report_failure("Jumping to the task", result);
+                }
+                KeyCode::Char('a') => {
+                    let result = app.resolve_duplicate(DuplicateChoice::AddAnyway).await;
+                    app.report_failure("Adding", result);
+                }
+                KeyCode::Esc => {
+                    let result = app.resolve_duplicate(DuplicateChoice::Cancel).await;
+                    app.report_failure("Cancelling", result);
+                }
+                _ => redraw = false,
+            },
+            Mode::Filter => match code {
+                KeyCode::Enter => {
+                    let result = app.exit_insert_mode().await;
+                    app.report_failure("Saving", result);
+                }
+                KeyCode::Esc => app.cancel_input(),
+                KeyCode::Tab => app.toggle_include_completed(),
+                KeyCode::Char(c) => app.handle_input(c),
+                KeyCode::Backspace => app.handle_backspace(),
+                _ => redraw = false,
+            },
+            Mode::Review => match code {
+                KeyCode::Char('k') => {
+                    let result = app.review(ReviewAction::Keep).await;
+                    app.report_failure("Keeping", result);
+                }
+                KeyCode::Char('r') => app.enter_review_due_mode(),
+                KeyCode::Char('d') => {
+                    let result = app.review(ReviewAction::Delete).await;
+                    app.report_failure("Deleting", result);
+                }
+                KeyCode::Char('p') => {
+                    let result = app.review(ReviewAction::Deprioritize).await;
+                    app.report_failure("Deprioritizing", result);
+                }
+                KeyCode::Esc => app.end_review(),
+                _ => redraw = false,
+            },
+            Mode::ReviewDue => match code {
+                KeyCode::Enter if !app.input_buffer.trim().is_empty() => {
+                    let due = app.input_buffer.trim().to_string();
+                    let result = app.review(ReviewAction::Reschedule(due)).await;
+                    app.report_failure("Rescheduling", result);
+                }
+                KeyCode::Esc => app.cancel_review_due(),
+                KeyCode::Char(c) => app.handle_input(c),
+                KeyCode::Backspace => app.handle_backspace(),
+                _ => redraw = false,
+            },
+            Mode::Assign => match code {
+                KeyCode::Char('j') | KeyCode::Down => app.shift_assign_choice(1),
+                KeyCode::Char('k') | KeyCode::Up => app.shift_assign_choice(-1),
+                KeyCode::Enter => {
+                    let result = app.exit_assign_mode(true).await;
+                    app.report_failure("Assigning", result);
+                }
+                KeyCode::Esc => {
+                    let result = app.exit_assign_mode(false).await;
+                    app.report_failure("Closing the picker", result);
+                }
+                _ => redraw = false,
+            },
+            Mode::Move => match code {
+                KeyCode::Char('j') | KeyCode::Down => app.shift_project_choice(1),
+                KeyCode::Char('k') | KeyCode::Up => app.shift_project_choice(-1),
+                KeyCode::Enter => {
+                    let result = app.exit_move_mode(true).await;
+                    app.report_failure("Moving", result);
+                }
+                KeyCode::Esc => {
+                    let result = app.exit_move_mode(false).await;
+                    app.report_failure("Closing the picker", result);
+                }
+                _ => redraw = false,
+            },
+            Mode::Place => match code {
+                KeyCode::Char('j') | KeyCode::Down => app.shift_place(1),
+                KeyCode::Char('k') | KeyCode::Up => app.shift_place(-1),
+                KeyCode::Char('J') => app.shift_place(2),
+                KeyCode::Char('K') => app.shift_place(-2),
+                KeyCode::Enter => {
+                    let result = app.exit_place_mode(true).await;
+                    app.report_failure("Scheduling", result);
+                }
+                KeyCode::Esc => {
+                    let result = app.exit_place_mode(false).await;
+                    app.report_failure("Closing the planner", result);
+                }
+                _ => redraw = false,
+            },
         }
     }
     Ok(())
 }
 
+/// Warns about task fields the API sent that this release doesn't know, once per run.
+fn warn_unknown_fields() {
+    let fields = api::client::unknown_task_fields();
+    if !fields.is_empty() {
+        eprintln!(
+            "Todoist sent task fields this version doesn't know ({}); consider upgrading todoist-cli",
+            fields.join(", ")
+        );
+    }
+}
+
 #[tokio::main]
 async fn main() -> AppResult<()> {
     let cli = Cli::parse();
-    let token = std::env::var("TODOIST_TOKEN").expect("TODOIST_TOKEN env var required");
-    let mut app = App::new(token)?;
+    // These run without a token or a working app state
+    match &cli.command {
+        Some(Commands::Man { out_dir }) => return cli::man::generate(out_dir.as_deref()),
+        Some(Commands::DuePreview { due, count }) => return cli::due_preview::run(due, *count),
+        Some(Commands::Doctor) => return cli::doctor::run().await,
+        // Restoring replaces the cache, so it can't be open
+        Some(Commands::Backup { action }) => return cli::backup::run(action),
+        Some(Commands::Archive { action }) => return cli::archive::run(action, cli.color.enabled()),
+        Some(Commands::SelfUpdate { check }) => return cli::self_update::run(*check).await,
+        // Run from hooks in other repositories, where a cache doesn't belong
+        Some(Commands::Git { action }) => return cli::git::run(action).await,
+        _ => {}
+    }
+    // Demo mode and replayed fixtures don't need a real token
+    let mut app = if cli.demo {
+        App::demo()?
+    } else {
+        let token = match std::env::var("TODOIST_TOKEN") {
+            Ok(token) => token,
+            Err(_) if std::env::var_os("TODOIST_REPLAY").is_some() => String::new(),
+            Err(_) => panic!("TODOIST_TOKEN env var required"),
+        };
+        App::new(token)?
+    };
+    if let Some(path) = &cli.debug_http {
+        app.enable_http_log(path)?;
+    }
+    if cli.plain_ui {
+        app.enable_plain_ui();
+    }
 
-    app.sync_tasks().await?;
+    if cli.command.is_some() {
+        app.enable_progress_bars();
+        app.set_surface("cli");
+    }
+    // The demo's cache starts out empty, so it always syncs
+    let sync_on_start = cli.demo || (!cli.offline && !cli.no_sync && app.settings().sync_on_start);
+    match &cli.command {
+        Some(command) if sync_on_start && (cli.demo || !command.skips_sync()) => {
+            // Offline, commands work from the cache and queue their changes for the next sync
+            if let Err(e) = app.incremental_sync().await {
+                if !api::client::is_offline(&e) {
+                    return Err(e);
+                }
+                app.report_offline();
+            }
+        }
+        None if sync_on_start => match app.incremental_sync().await {
+            // Offline, the TUI opens from the cache and queues changes for the next sync
+            Err(e) if api::client::is_offline(&e) => app.report_offline(),
+            result => {
+                result?;
+                // Project names are only needed when the list shows them
+                if app.settings().columns.contains(&Column::Project) {
+                    app.sync_projects().await?;
+                }
+                // Label colors and favorites are cosmetic, so chips go uncolored and the sidebar
+                // without favorite labels and filters when they can't be fetched
+                let _ = app.sync_labels().await;
+                let _ = app.sync_filters().await;
+                // The goal counter is left out when the stats can't be fetched, e.g. on REST v2
+                let _ = app.sync_daily_goal().await;
+                // Assigned tasks go without their assignee's name when collaborators can't be fetched
+                let _ = app.sync_collaborators().await;
+            }
+        },
+        _ => {}
+    }
 
     if let Some(command) = cli.command {
-        process_command(&mut app, &command).await?;
-        return Ok(());
+        // Summarizes priority escalations made during the sync
+        if let Some(status) = app.status() {
+            eprintln!("{}", status);
+        }
+        let status = app.status().map(str::to_string);
+        let result = process_command(&mut app, &command, cli.color.enabled()).await;
+        // ...and what the command reported, e.g. tasks freed by completing their blocker
+        if let Some(reported) = app.status().filter(|s| Some(*s) != status.as_deref()) {
+            eprintln!("{}", reported);
+        }
+        app.flush_cache()?;
+        app.save_api_stats()?;
+        warn_unknown_fields();
+        return result;
+    }
+
+    if !sync_on_start && !cli.offline {
+        app.start_background_sync();
     }
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    if !cli.no_alt_screen {
+        execute!(stdout, EnterAlternateScreen)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = run_app(&mut terminal, &mut app).await;
+    let result = run_app(&mut terminal, &mut app, cli.offline).await;
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    if cli.no_alt_screen {
+        // Leave a clean prompt rather than the last frame
+        terminal.clear()?;
+        terminal.show_cursor()?;
+        execute!(terminal.backend_mut(), MoveTo(0, 0))?;
+    } else {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
+    app.flush_cache()?;
+    app.save_api_stats()?;
+    warn_unknown_fields();
     result
 }
\ No newline at end of file
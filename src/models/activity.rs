@@ -0,0 +1,46 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// An event from Todoist's activity log, as `activities` reports it.
+#[derive(Clone, Deserialize)]
+pub struct Activity {
+    pub id: String,
+    /// Kind of object the event is about, e.g. "item" for tasks.
+    pub object_type: String,
+    pub object_id: String,
+    /// What happened, e.g. "added", "updated" or "completed".
+    pub event_type: String,
+    pub event_date: String,
+    #[serde(default)]
+    pub parent_project_id: Option<String>,
+    /// Collaborator who made the change.
+    #[serde(default)]
+    pub initiator_id: Option<String>,
+    /// Event details, such as the task's content and, for updates, previous values.
+    #[serde(default)]
+    pub extra_data: Value,
+}
+
+impl Activity {
+    /// Describes a task event made by another collaborator, e.g. "Ana completed Ship it" or
+    /// "Ana assigned you Review PR"; `None` for events of `me` and ones not worth a notification.
+    pub fn describe(&self, me: &str, initiator: Option<&str>) -> Option<String> {
+        let initiator_id = self.initiator_id.as_deref()?;
+        if self.object_type != "item" || initiator_id == me {
+            return None;
+        }
+        let extra = |key: &str| self.extra_data[key].as_str();
+        let verb = match self.event_type.as_str() {
+            "added" => "added",
+            "completed" => "completed",
+            "uncompleted" => "reopened",
+            "deleted" => "deleted",
+            "updated" if extra("responsible_uid") == Some(me) && extra("last_responsible_uid") != Some(me) => {
+                "assigned you"
+            }
+            _ => return None,
+        };
+        let who = initiator.unwrap_or("Someone");
+        Some(format!("{} {} {}", who, verb, extra("content").unwrap_or("a task")))
+    }
+}
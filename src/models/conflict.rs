@@ -0,0 +1,32 @@
+use crate::models::task::Task;
+use serde::{Deserialize, Serialize};
+
+/// A task edited offline that was also changed in Todoist before the edit could be sent. Its
+/// queued changes are held back until one side is chosen.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Conflict {
+    pub todoist_id: String,
+    /// The local copy, with the queued edits applied.
+    pub mine: Task,
+    /// The copy in Todoist when the conflict was found.
+    pub theirs: Task,
+}
+
+impl Conflict {
+    /// The fields that differ between the two copies, as (field, mine, theirs), for showing
+    /// them side by side.
+    pub fn differences(&self) -> Vec<(&'static str, String, String)> {
+        let due = |task: &Task| task.due.as_ref().map(|d| d.string.clone()).unwrap_or_default();
+        // Todoist's priority 4 is shown as p1
+        let priority = |task: &Task| format!("p{}", 5 - task.priority);
+        let fields = [
+            ("Title", self.mine.title.clone(), self.theirs.title.clone()),
+            ("Description", self.mine.description.clone(), self.theirs.description.clone()),
+            ("Due", due(&self.mine), due(&self.theirs)),
+            ("Priority", priority(&self.mine), priority(&self.theirs)),
+            ("Labels", self.mine.labels.join(", "), self.theirs.labels.join(", ")),
+            ("Done", self.mine.is_done().to_string(), self.theirs.is_done().to_string()),
+        ];
+        fields.into_iter().filter(|(_, mine, theirs)| mine != theirs).collect()
+    }
+}
@@ -0,0 +1,173 @@
+use crate::models::task::Task;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Task fields that change without the user touching them, left out of `Edited`.
+const UNTRACKED_FIELDS: [&str; 6] = ["id", "checked", "added_at", "updated_at", "completed_at", "location"];
+
+/// What a local action did to a task.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskEvent {
+    Added,
+    /// Changed the named fields, e.g. `due` or `labels`.
+    Edited { fields: Vec<String> },
+    Completed,
+    Reopened,
+    Deleted,
+    /// Left the active tasks elsewhere; a sync can't tell completing from deleting.
+    Closed,
+}
+
+impl TaskEvent {
+    /// Past-tense verb for the event, e.g. "completed".
+    pub fn verb(&self) -> &'static str {
+        match self {
+            TaskEvent::Added => "added",
+            TaskEvent::Edited { fields } if fields == &["due"] => "rescheduled",
+            TaskEvent::Edited { .. } => "edited",
+            TaskEvent::Completed => "completed",
+            TaskEvent::Reopened => "reopened",
+            TaskEvent::Deleted => "deleted",
+            TaskEvent::Closed => "completed or deleted",
+        }
+    }
+}
+
+/// A task change made from this app, with the task as it was before and after.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the change was made (RFC 3339, UTC).
+    pub at: String,
+    /// Who made the change, e.g. "joel via tui".
+    pub actor: String,
+    pub todoist_id: String,
+    pub event: TaskEvent,
+    pub before: Option<Task>,
+    pub after: Option<Task>,
+}
+
+impl AuditEntry {
+    /// Describes the change from `before` to `after`; `None` for the task missing on both sides
+    /// or for nothing but timestamps changing.
+    pub fn new(actor: &str, before: Option<Task>, after: Option<Task>) -> Option<Self> {
+        let event = match (&before, &after) {
+            (None, None) => return None,
+            (None, Some(_)) => TaskEvent::Added,
+            (Some(_), None) => TaskEvent::Deleted,
+            (Some(old), Some(new)) if !old.is_done() && new.is_done() => TaskEvent::Completed,
+            (Some(old), Some(new)) if old.is_done() && !new.is_done() => TaskEvent::Reopened,
+            (Some(old), Some(new)) => {
+                let fields = changed_fields(old, new);
+                if fields.is_empty() {
+                    return None;
+                }
+                TaskEvent::Edited { fields }
+            }
+        };
+        let todoist_id = before.as_ref().or(after.as_ref())?.todoist_id.clone();
+        Some(AuditEntry {
+            at: Utc::now().to_rfc3339(),
+            actor: actor.to_string(),
+            todoist_id,
+            event,
+            before,
+            after,
+        })
+    }
+
+    /// Describes a change found between two syncs, made elsewhere by an unknown actor.
+    pub fn remote(before: Option<Task>, after: Option<Task>) -> Option<Self> {
+        let mut entry = Self::new("remote", before, after)?;
+        if entry.event == TaskEvent::Deleted {
+            entry.event = TaskEvent::Closed;
+        }
+        Some(entry)
+    }
+
+    /// Title of the task, as it was after the change or, for deletions, before it.
+    pub fn title(&self) -> &str {
+        self.after.as_ref().or(self.before.as_ref()).map_or("", |t| t.title.as_str())
+    }
+
+    /// One line per changed field with its previous and new value, for edits.
+    pub fn changes(&self) -> Vec<String> {
+        let TaskEvent::Edited { fields } = &self.event else {
+            return Vec::new();
+        };
+        let (before, after) = (fields_of(self.before.as_ref()), fields_of(self.after.as_ref()));
+        fields
+            .iter()
+            .map(|field| {
+                let (old, new) = (show(field, &before[field]), show(field, &after[field]));
+                format!("{}: {} → {}", field, old, new)
+            })
+            .collect()
+    }
+}
+
+/// Pairs up the tasks of two syncs as before/after versions for the changes made in between:
+/// new tasks, open tasks that are gone, and tasks Todoist modified. Tasks completed here are
+/// left out, since going away (or moving to their next date, when recurring) is expected.
+pub fn sync_diff(old: &[Task], new: &[Task]) -> Vec<(Option<Task>, Option<Task>)> {
+    let old_by_id = old.iter().map(|t| (t.todoist_id.as_str(), t)).collect::<HashMap<_, _>>();
+    let new_ids = new.iter().map(|t| t.todoist_id.as_str()).collect::<HashSet<_>>();
+    let mut pairs = Vec::new();
+    for task in new {
+        match old_by_id.get(task.todoist_id.as_str()) {
+            None => pairs.push((None, Some(task.clone()))),
+            // Todoist bumps the timestamp on every change, so unchanged tasks are skipped cheaply
+            Some(previous) if !previous.is_done() && previous.updated_at != task.updated_at => {
+                pairs.push((Some((*previous).clone()), Some(task.clone())));
+            }
+            Some(_) => {}
+        }
+    }
+    for task in old {
+        if !task.is_done() && !new_ids.contains(task.todoist_id.as_str()) {
+            pairs.push((Some(task.clone()), None));
+        }
+    }
+    pairs
+}
+
+/// Names of the user-editable fields that differ between two versions of a task.
+fn changed_fields(old: &Task, new: &Task) -> Vec<String> {
+    let (old, new) = (fields_of(Some(old)), fields_of(Some(new)));
+    let Some(old) = old.as_object() else {
+        return Vec::new();
+    };
+    old.iter()
+        .filter(|(key, value)| !UNTRACKED_FIELDS.contains(&key.as_str()) && new[key.as_str()] != **value)
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+fn fields_of(task: Option<&Task>) -> Value {
+    task.and_then(|t| serde_json::to_value(t).ok()).unwrap_or_default()
+}
+
+/// Shows a field value compactly: strings unquoted, due dates by their string, priorities as
+/// p1..p4, none as "–".
+fn show(field: &str, value: &Value) -> String {
+    match value {
+        Value::Number(n) if field == "priority" => format!("p{}", 5 - n.as_u64().unwrap_or(1).min(4)),
+        Value::Null => "–".to_string(),
+        Value::String(s) if s.is_empty() => "–".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Object(due) if due.contains_key("string") => match &due["string"] {
+            Value::String(s) if !s.is_empty() => s.clone(),
+            _ => show(field, &due["date"]),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// The local user name, for the audit log's actor.
+pub fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// A personal Todoist label.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Label {
+    pub id: String,
+    pub name: String,
+    /// Todoist color name, e.g. "berry_red".
+    #[serde(default)]
+    pub color: String,
+    #[serde(default)]
+    pub is_favorite: bool,
+}
@@ -1 +1,10 @@
-pub mod task;
\ No newline at end of file
+pub mod activity;
+pub mod conflict;
+pub mod event;
+pub mod label;
+pub mod project;
+pub mod review;
+pub mod saved_filter;
+pub mod task;
+pub mod template;
+pub mod user;
\ No newline at end of file
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents a Todoist project.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub is_archived: bool,
+    #[serde(default)]
+    pub is_favorite: bool,
+    /// Whether the project has collaborators besides its owner.
+    #[serde(default)]
+    pub is_shared: bool,
+    /// Todoist color name, e.g. "berry_red".
+    #[serde(default)]
+    pub color: String,
+    /// ID of the parent project for sub-projects.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+}
+
+/// Finds a project by name, ignoring case, or else by ID.
+pub fn find<'a>(projects: &'a [Project], name_or_id: &str) -> Option<&'a Project> {
+    projects
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name_or_id))
+        .or_else(|| projects.iter().find(|p| p.id == name_or_id))
+}
+
+/// Orders projects as a tree, each followed by its sub-projects, paired with their depth.
+/// Projects whose parent isn't listed are top-level; siblings keep their order.
+pub fn nested(projects: &[Project]) -> Vec<(usize, &Project)> {
+    let is_listed = |id: &str| projects.iter().any(|p| p.id == id);
+    let mut stack = projects
+        .iter()
+        .filter(|p| p.parent_id.as_deref().is_none_or(|parent| !is_listed(parent)))
+        .rev()
+        .map(|p| (0, p))
+        .collect::<Vec<_>>();
+    let mut ordered = Vec::with_capacity(projects.len());
+    while let Some((depth, project)) = stack.pop() {
+        ordered.push((depth, project));
+        let children = projects
+            .iter()
+            .filter(|p| p.parent_id.as_deref() == Some(project.id.as_str()));
+        stack.extend(children.rev().map(|child| (depth + 1, child)));
+    }
+    ordered
+}
+
+/// Returns true if the project is `ancestor_id` or one of its sub-projects at any depth.
+pub fn is_within(projects: &[Project], project_id: &str, ancestor_id: &str) -> bool {
+    let mut id = project_id;
+    // Bounded by the project count in case of a parent cycle
+    for _ in 0..=projects.len() {
+        if id == ancestor_id {
+            return true;
+        }
+        match projects.iter().find(|p| p.id == id).and_then(|p| p.parent_id.as_deref()) {
+            Some(parent) => id = parent,
+            None => return false,
+        }
+    }
+    false
+}
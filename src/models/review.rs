@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Progress through a weekly review, saved after every answer so an interrupted review can be
+/// picked up where it stopped.
+#[derive(Serialize, Deserialize)]
+pub struct WeeklyReview {
+    /// When the review was started (RFC 3339).
+    pub started_at: String,
+    /// Index of the current step.
+    pub step: usize,
+    /// Todoist IDs of the tasks already dealt with in the current step.
+    #[serde(default)]
+    pub handled: Vec<String>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// A filter saved in Todoist, e.g. "Work today" for `today & #Work`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedFilter {
+    pub id: String,
+    pub name: String,
+    /// Todoist filter query.
+    pub query: String,
+    /// Todoist color name, e.g. "berry_red".
+    #[serde(default)]
+    pub color: String,
+    #[serde(default)]
+    pub is_favorite: bool,
+}
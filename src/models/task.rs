@@ -1,22 +1,451 @@
+use crate::utils::dates::{ParsedDue, parse_due, relative_date};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Due date of a task, mirroring the Todoist `due` object.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Due {
+    /// Calendar date in `YYYY-MM-DD` form.
+    pub date: String,
+    /// Date and time when the task is due at a specific time.
+    pub datetime: Option<String>,
+    /// Human-readable due string, e.g. "every monday".
+    pub string: String,
+    pub is_recurring: bool,
+}
+
+impl Due {
+    /// The due date a request's `due_date` or `due_datetime` (RFC 3339) sets, as Todoist reports
+    /// it back; `None` when neither is given.
+    pub fn from_request(date: Option<&str>, datetime: Option<&str>) -> Option<Self> {
+        if let Some(datetime) = datetime {
+            let at = DateTime::parse_from_rfc3339(datetime).ok()?.with_timezone(&Utc);
+            return Some(Due {
+                date: at.format("%Y-%m-%d").to_string(),
+                datetime: Some(at.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+                string: datetime.to_string(),
+                is_recurring: false,
+            });
+        }
+        date.map(|date| Due {
+            date: date.to_string(),
+            datetime: None,
+            string: date.to_string(),
+            is_recurring: false,
+        })
+    }
+
+    /// Parses the due date as a calendar date.
+    pub fn naive_date(&self) -> Option<NaiveDate> {
+        // Split by hand: this runs for every task whenever the list is filtered or sorted, and
+        // `parse_from_str` is several times slower
+        let mut parts = self.date.splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        NaiveDate::from_ymd_opt(year, month, day)
+    }
+
+    /// Returns the moment used to order tasks by due date; date-only tasks sort at the end of their day.
+    pub fn sort_key(&self) -> Option<NaiveDateTime> {
+        self.naive_datetime()
+            .or_else(|| self.naive_date()?.and_hms_opt(23, 59, 59))
+    }
+
+    /// Parses the due datetime as local time; UTC values are converted, floating values kept as-is.
+    pub fn naive_datetime(&self) -> Option<NaiveDateTime> {
+        let datetime = self.datetime.as_deref()?;
+        match datetime.strip_suffix('Z') {
+            Some(utc) => {
+                let utc = NaiveDateTime::parse_from_str(utc, "%Y-%m-%dT%H:%M:%S").ok()?;
+                Some(DateTime::<Local>::from(utc.and_utc()).naive_local())
+            }
+            None => NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S").ok(),
+        }
+    }
+
+    /// The local calendar day the task is due, taking the time zone of timed dues into account.
+    pub fn local_date(&self) -> Option<NaiveDate> {
+        self.naive_datetime()
+            .map(|at| at.date())
+            .or_else(|| self.naive_date())
+    }
+
+    /// Formats the due date for display, either relative to `today` ("today 17:00", "2 days overdue")
+    /// or as an absolute date. Times are only shown for today and tomorrow in relative mode.
+    pub fn display(&self, today: NaiveDate, relative: bool) -> String {
+        let at = self.naive_datetime();
+        let Some(date) = self.local_date() else {
+            return self.string.clone();
+        };
+        if !relative {
+            return match at {
+                Some(at) => at.format("%Y-%m-%d %H:%M").to_string(),
+                None => date.format("%Y-%m-%d").to_string(),
+            };
+        }
+        let day = relative_date(date, today);
+        match at {
+            Some(at) if (0..=1).contains(&(date - today).num_days()) => {
+                format!("{} {}", day, at.format("%H:%M"))
+            }
+            _ => day,
+        }
+    }
+
+    /// Formats the due date in at most 11 characters for narrow terminals, e.g. "tod 17:00",
+    /// "tmrw", "-2d", "Fri" within the coming week, or "Oct 21".
+    pub fn display_compact(&self, today: NaiveDate) -> String {
+        let Some(date) = self.local_date() else {
+            return self.string.clone();
+        };
+        let day = match (date - today).num_days() {
+            0 => "tod".to_string(),
+            1 => "tmrw".to_string(),
+            days if days < 0 => format!("{}d", days),
+            days if days < 7 => date.format("%a").to_string(),
+            _ => date.format("%b %d").to_string(),
+        };
+        match self.naive_datetime() {
+            Some(at) if (0..=1).contains(&(date - today).num_days()) => {
+                format!("{} {}", day, at.format("%H:%M"))
+            }
+            _ => day,
+        }
+    }
+}
+
 /// Represents a Todoist task with minimal fields for local CRUD.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub id: usize,
     pub todoist_id: String,
     pub title: String,
+    /// Notes below the title; may span several lines.
+    #[serde(default)]
+    pub description: String,
+    /// Whether the task is completed; older caches and exports called this `is_completed`.
+    #[serde(alias = "is_completed")]
     pub checked: bool,
+    #[serde(default)]
+    pub due: Option<Due>,
+    /// Deadline date in `YYYY-MM-DD` form.
+    #[serde(default)]
+    pub deadline: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// API priority from 1 (normal) to 4 (urgent); shown to users as p4..p1.
+    #[serde(default = "default_priority")]
+    pub priority: u8,
+    #[serde(default)]
+    pub project_id: String,
+    /// Todoist ID of the parent task for subtasks.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Creation timestamp (RFC 3339).
+    #[serde(default)]
+    pub added_at: Option<String>,
+    /// Last modification timestamp (RFC 3339).
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Completion timestamp, set for tasks fetched from completion history or closed locally.
+    #[serde(default)]
+    pub completed_at: Option<String>,
+    /// Place name of a location-based reminder, e.g. "Home"; kept for display, not editable.
+    #[serde(default)]
+    pub location: Option<String>,
+    /// User ID of the collaborator the task is assigned to, in shared projects.
+    #[serde(default)]
+    pub responsible_uid: Option<String>,
+    /// Planned length in minutes, e.g. for blocking out time in the day planner.
+    #[serde(default)]
+    pub duration: Option<u32>,
+}
+
+fn default_priority() -> u8 {
+    1
 }
 
 impl Task {
-    /// Creates a new task with the given local ID, title, and completion status.
-    pub fn new(id: usize, title: &str, checked: bool) -> Self {
+    /// Gets the first `len` characters of the Todoist ID, or all of it when shorter.
+    pub fn short_id(&self, len: usize) -> &str {
+        self.todoist_id
+            .char_indices()
+            .nth(len)
+            .map_or(self.todoist_id.as_str(), |(end, _)| &self.todoist_id[..end])
+    }
+
+    /// Link to the task in the Todoist web app.
+    pub fn web_url(&self) -> String {
+        format!("https://app.todoist.com/app/task/{}", self.todoist_id)
+    }
+
+    /// When the task was created, if known.
+    pub fn added(&self) -> Option<DateTime<Utc>> {
+        let added_at = DateTime::parse_from_rfc3339(self.added_at.as_deref()?).ok()?;
+        Some(added_at.to_utc())
+    }
+
+    /// When the task was last modified, falling back to its creation time.
+    pub fn updated(&self) -> Option<DateTime<Utc>> {
+        match DateTime::parse_from_rfc3339(self.updated_at.as_deref().unwrap_or_default()) {
+            Ok(updated_at) => Some(updated_at.to_utc()),
+            Err(_) => self.added(),
+        }
+    }
+
+    /// The local day the task was completed, if it has a completion timestamp.
+    pub fn completed_on(&self) -> Option<NaiveDate> {
+        let completed_at = DateTime::parse_from_rfc3339(self.completed_at.as_deref()?).ok()?;
+        Some(completed_at.with_timezone(&Local).date_naive())
+    }
+
+    /// Returns true if the task is completed.
+    pub fn is_done(&self) -> bool {
+        self.checked
+    }
+
+    /// Returns true if the task is open and its due date is before today.
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.is_done()
+            && self
+                .due
+                .as_ref()
+                .and_then(Due::naive_date)
+                .is_some_and(|date| date < today)
+    }
+}
+
+/// Fields for creating a task through the Todoist API.
+#[derive(Clone, Default, Serialize)]
+pub struct NewTask {
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_string: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_datetime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline_date: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+}
+
+impl NewTask {
+    /// Creates a task payload with only a title.
+    pub fn new(content: &str) -> Self {
+        NewTask {
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The task as Todoist will create it, for showing it before Todoist has it, e.g. while
+    /// offline. A due date only Todoist can parse is left out until then.
+    pub fn preview(&self, todoist_id: &str) -> Task {
         Task {
-            id,
-            todoist_id: "".to_string(),
-            title: title.to_string(),
-            checked,
+            id: 0,
+            todoist_id: todoist_id.to_string(),
+            title: self.content.clone(),
+            description: self.description.clone().unwrap_or_default(),
+            checked: false,
+            due: Due::from_request(self.due_date.as_deref(), self.due_datetime.as_deref()),
+            deadline: self.deadline_date.clone(),
+            labels: self.labels.clone(),
+            priority: self.priority.unwrap_or_else(default_priority),
+            project_id: self.project_id.clone().unwrap_or_default(),
+            parent_id: self.parent_id.clone(),
+            added_at: Some(Utc::now().to_rfc3339()),
+            updated_at: None,
+            completed_at: None,
+            location: None,
+            responsible_uid: None,
+            duration: None,
+        }
+    }
+
+    /// Sets the due date from natural language, resolved locally when possible and
+    /// otherwise sent as a `due_string` for Todoist to parse.
+    pub fn set_due(&mut self, input: &str) {
+        match parse_due(input, Local::now().date_naive()) {
+            Some(ParsedDue::Date(date)) => self.due_date = Some(date.format("%Y-%m-%d").to_string()),
+            Some(ParsedDue::DateTime(datetime)) => match Local.from_local_datetime(&datetime).earliest() {
+                Some(local) => self.due_datetime = Some(local.with_timezone(&Utc).to_rfc3339()),
+                None => self.due_string = Some(input.to_string()),
+            },
+            None => self.due_string = Some(input.to_string()),
+        }
+    }
+}
+
+/// Fields for a partial task update; unset fields are left out of the request and keep their values.
+#[derive(Clone, Default, Serialize)]
+pub struct TaskUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_string: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_datetime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// Collaborator to assign the task to; `Some(None)` unassigns it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee_id: Option<Option<String>>,
+}
+
+impl TaskUpdate {
+    /// Sets the due date from natural language, as for new tasks.
+    pub fn set_due(&mut self, input: &str) {
+        let mut due = NewTask::default();
+        due.set_due(input);
+        self.due_string = due.due_string;
+        self.due_date = due.due_date;
+        self.due_datetime = due.due_datetime;
+    }
+
+    /// Applies the update to a local copy of the task, e.g. while offline; a due string only
+    /// Todoist can parse is left for the next sync.
+    pub fn apply_to(&self, task: &mut Task) {
+        if let Some(content) = &self.content {
+            task.title = content.clone();
+        }
+        if let Some(description) = &self.description {
+            task.description = description.clone();
+        }
+        if let Some(priority) = self.priority {
+            task.priority = priority;
+        }
+        if let Some(checked) = self.checked {
+            task.checked = checked;
+            task.completed_at = checked.then(|| Utc::now().to_rfc3339());
+        }
+        if let Some(due) = Due::from_request(self.due_date.as_deref(), self.due_datetime.as_deref()) {
+            task.due = Some(due);
+        } else if self.due_string.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("no date")) {
+            task.due = None;
         }
+        if let Some(assignee) = &self.assignee_id {
+            task.responsible_uid = assignee.clone();
+        }
+        task.updated_at = Some(Utc::now().to_rfc3339());
     }
-}
\ No newline at end of file
+
+    /// Drops the fields that already match `task`, so only real changes are sent.
+    pub fn changes_from(mut self, task: &Task) -> Self {
+        if self.content.as_deref() == Some(task.title.as_str()) {
+            self.content = None;
+        }
+        if self.description.as_deref() == Some(task.description.as_str()) {
+            self.description = None;
+        }
+        if self.checked == Some(task.is_done()) {
+            self.checked = None;
+        }
+        if self.priority == Some(task.priority) {
+            self.priority = None;
+        }
+        if self.assignee_id.as_ref() == Some(&task.responsible_uid) {
+            self.assignee_id = None;
+        }
+        self
+    }
+
+    /// Whether the update changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_none()
+            && self.checked.is_none()
+            && self.description.is_none()
+            && self.due_string.is_none()
+            && self.due_date.is_none()
+            && self.due_datetime.is_none()
+            && self.priority.is_none()
+            && self.assignee_id.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task() -> Task {
+        let mut task = NewTask::new("Buy milk").preview("100");
+        task.priority = 2;
+        task.description = "Oat".to_string();
+        task
+    }
+
+    #[test]
+    fn changes_from_drops_fields_that_already_match() {
+        let update = TaskUpdate {
+            content: Some("Buy milk".to_string()),
+            description: Some("Soy".to_string()),
+            checked: Some(false),
+            priority: Some(2),
+            assignee_id: Some(None),
+            ..TaskUpdate::default()
+        }
+        .changes_from(&task());
+        assert_eq!(update.content, None);
+        assert_eq!(update.description.as_deref(), Some("Soy"));
+        assert_eq!(update.checked, None);
+        assert_eq!(update.priority, None);
+        assert_eq!(update.assignee_id, None);
+        assert!(!update.is_empty());
+    }
+
+    #[test]
+    fn changes_from_leaves_nothing_when_all_fields_match() {
+        let update = TaskUpdate {
+            content: Some("Buy milk".to_string()),
+            priority: Some(2),
+            ..TaskUpdate::default()
+        }
+        .changes_from(&task());
+        assert!(update.is_empty());
+    }
+
+    #[test]
+    fn apply_to_changes_only_the_set_fields() {
+        let mut task = task();
+        TaskUpdate {
+            priority: Some(4),
+            checked: Some(true),
+            due_date: Some("2024-05-20".to_string()),
+            ..TaskUpdate::default()
+        }
+        .apply_to(&mut task);
+        assert_eq!(task.title, "Buy milk");
+        assert_eq!(task.description, "Oat");
+        assert_eq!(task.priority, 4);
+        assert!(task.is_done());
+        assert!(task.completed_at.is_some());
+        assert_eq!(task.due.as_ref().map(|d| d.date.as_str()), Some("2024-05-20"));
+        assert!(task.updated_at.is_some());
+    }
+
+    #[test]
+    fn apply_to_clears_the_due_date_for_no_date() {
+        let mut task = task();
+        task.due = Due::from_request(Some("2024-05-20"), None);
+        let mut update = TaskUpdate::default();
+        update.set_due("no date");
+        update.apply_to(&mut task);
+        assert!(task.due.is_none());
+    }
+}
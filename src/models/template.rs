@@ -0,0 +1,43 @@
+use crate::models::task::{NewTask, Task};
+use serde::{Deserialize, Serialize};
+
+/// Placeholder in template titles replaced by the date the template is applied.
+pub const DATE_PLACEHOLDER: &str = "{date}";
+
+/// A reusable task blueprint, with its subtasks nested below it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Template {
+    /// Title pattern; `{date}` expands to the date the template is applied.
+    pub title: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub priority: Option<u8>,
+    #[serde(default)]
+    pub subtasks: Vec<Template>,
+}
+
+impl Template {
+    /// Builds a template from a task and its descendants, turning `date` in titles into `{date}`.
+    pub fn from_task(task: &Task, tasks: &[Task], date: &str) -> Self {
+        Template {
+            title: task.title.replace(date, DATE_PLACEHOLDER),
+            labels: task.labels.clone(),
+            priority: (task.priority > 1).then_some(task.priority),
+            subtasks: tasks
+                .iter()
+                .filter(|t| t.parent_id.as_deref() == Some(task.todoist_id.as_str()))
+                .map(|t| Template::from_task(t, tasks, date))
+                .collect(),
+        }
+    }
+
+    /// Builds the task payload for this template level, expanding `{date}`.
+    pub fn new_task(&self, date: &str) -> NewTask {
+        NewTask {
+            labels: self.labels.clone(),
+            priority: self.priority,
+            ..NewTask::new(&self.title.replace(DATE_PLACEHOLDER, date))
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// The Todoist account the API token belongs to.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub full_name: String,
+    pub email: String,
+}
+
+/// A person a shared project is shared with.
+#[derive(Clone, Deserialize)]
+pub struct Collaborator {
+    pub id: String,
+    pub name: String,
+}
+
+/// Completion stats for the account, as `tasks/completed/stats` reports them.
+#[derive(Clone, Default, Deserialize)]
+pub struct ProductivityStats {
+    #[serde(default)]
+    pub goals: Goals,
+    /// Completions per day, most recent first.
+    #[serde(default)]
+    pub days_items: Vec<DayStats>,
+}
+
+/// Goals set in the user's Todoist productivity settings.
+#[derive(Clone, Default, Deserialize)]
+pub struct Goals {
+    /// Tasks to complete each day; 0 when no goal is set.
+    #[serde(default)]
+    pub daily_goal: usize,
+}
+
+/// Tasks completed on one day.
+#[derive(Clone, Deserialize)]
+pub struct DayStats {
+    pub date: NaiveDate,
+    pub total_completed: usize,
+}
+
+impl ProductivityStats {
+    /// Tasks completed on `date`, 0 for days the stats don't cover.
+    pub fn completed_on(&self, date: NaiveDate) -> usize {
+        self.days_items
+            .iter()
+            .find(|day| day.date == date)
+            .map_or(0, |day| day.total_completed)
+    }
+}
@@ -0,0 +1,26 @@
+use crate::utils::error::AppResult;
+use std::process::{Command, Stdio};
+
+/// Opens a URL in `$BROWSER` or the platform's default browser without waiting for it to close.
+pub fn open(url: &str) -> AppResult<()> {
+    let mut command = match std::env::var("BROWSER") {
+        Ok(browser) => Command::new(browser),
+        Err(_) if cfg!(target_os = "macos") => Command::new("open"),
+        Err(_) if cfg!(windows) => {
+            // `start` treats its first quoted argument as the window title
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        Err(_) => Command::new("xdg-open"),
+    };
+    // Browser output would scribble over the TUI
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Couldn't open {}: {}", url, e))?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use clap::ValueEnum;
+use std::io::IsTerminal;
+
+pub const BOLD: &str = "\x1b[1m";
+pub const RED: &str = "\x1b[31m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const BLUE: &str = "\x1b[34m";
+pub const CYAN: &str = "\x1b[36m";
+pub const RESET: &str = "\x1b[0m";
+
+/// When to color CLI output.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolves the choice for stdout, following the https://no-color.org convention in auto mode.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Gets the color of an API priority above normal: red for p1, yellow for p2 and blue for p3.
+pub fn priority_color(priority: u8) -> Option<&'static str> {
+    match priority {
+        4 => Some(RED),
+        3 => Some(YELLOW),
+        2 => Some(BLUE),
+        _ => None,
+    }
+}
+
+/// Gets the flag color the Todoist apps give an API priority above normal, for the TUI: red for
+/// p1, orange for p2 and blue for p3.
+pub fn priority_rgb(priority: u8) -> Option<Rgb> {
+    match priority {
+        4 => Some(Rgb(0xd1, 0x45, 0x3b)),
+        3 => Some(Rgb(0xeb, 0x89, 0x09)),
+        2 => Some(Rgb(0x24, 0x6f, 0xe0)),
+        _ => None,
+    }
+}
+
+/// Wraps `text` in an ANSI color, or returns it unchanged when coloring is off.
+pub fn paint(text: &str, color: &str, enabled: bool) -> String {
+    if enabled {
+        format!("{}{}{}", color, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+/// A 24-bit color.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// Todoist's palette in color ID order, 30 to 49, under the names API v1 and REST v2 send.
+const TODOIST_COLORS: [(&str, Rgb); 20] = [
+    ("berry_red", Rgb(0xb8, 0x25, 0x5f)),
+    ("red", Rgb(0xdb, 0x40, 0x35)),
+    ("orange", Rgb(0xff, 0x99, 0x33)),
+    ("yellow", Rgb(0xfa, 0xd0, 0x00)),
+    ("olive_green", Rgb(0xaf, 0xb8, 0x3b)),
+    ("lime_green", Rgb(0x7e, 0xcc, 0x49)),
+    ("green", Rgb(0x29, 0x94, 0x38)),
+    ("mint_green", Rgb(0x6a, 0xcc, 0xbc)),
+    ("teal", Rgb(0x15, 0x8f, 0xad)),
+    ("sky_blue", Rgb(0x14, 0xaa, 0xf5)),
+    ("light_blue", Rgb(0x96, 0xc3, 0xeb)),
+    ("blue", Rgb(0x40, 0x73, 0xff)),
+    ("grape", Rgb(0x88, 0x4d, 0xff)),
+    ("violet", Rgb(0xaf, 0x38, 0xeb)),
+    ("lavender", Rgb(0xeb, 0x96, 0xeb)),
+    ("magenta", Rgb(0xe0, 0x51, 0x94)),
+    ("salmon", Rgb(0xff, 0x8d, 0x85)),
+    ("charcoal", Rgb(0x80, 0x80, 0x80)),
+    ("grey", Rgb(0xb8, 0xb8, 0xb8)),
+    ("taupe", Rgb(0xcc, 0xac, 0x93)),
+];
+
+/// Resolves a Todoist color, by name such as `berry_red` or by the Sync API's numeric ID, to
+/// the value the official apps show. Unknown or empty colors resolve to `None`.
+pub fn todoist_color(color: &str) -> Option<Rgb> {
+    if let Ok(id) = color.parse::<usize>() {
+        return TODOIST_COLORS.get(id.checked_sub(30)?).map(|(_, rgb)| *rgb);
+    }
+    TODOIST_COLORS
+        .iter()
+        .find(|(name, _)| *name == color)
+        .map(|(_, rgb)| *rgb)
+}
+
+/// Returns true if the terminal advertises 24-bit color through `COLORTERM`.
+pub fn truecolor() -> bool {
+    std::env::var("COLORTERM").is_ok_and(|value| value == "truecolor" || value == "24bit")
+}
+
+impl Rgb {
+    /// The nearest color of the xterm 256-color cube.
+    pub fn xterm256(self) -> u8 {
+        let level = |c: u8| (c as u16 * 5 + 127) / 255;
+        (16 + 36 * level(self.0) + 6 * level(self.1) + level(self.2)) as u8
+    }
+
+    /// ANSI escape setting this as the foreground color, falling back to the 256-color
+    /// palette on terminals without 24-bit color.
+    pub fn ansi(self) -> String {
+        if truecolor() {
+            format!("\x1b[38;2;{};{};{}m", self.0, self.1, self.2)
+        } else {
+            format!("\x1b[38;5;{}m", self.xterm256())
+        }
+    }
+}
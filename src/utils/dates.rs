@@ -0,0 +1,249 @@
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+/// A due date parsed from natural language.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParsedDue {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl ParsedDue {
+    /// Formats the date with `date_format`, followed by the time for timed dues.
+    pub fn format(&self, date_format: &str) -> String {
+        match self {
+            ParsedDue::Date(date) => date.format(date_format).to_string(),
+            ParsedDue::DateTime(at) => format!("{} {}", at.format(date_format), at.format("%H:%M")),
+        }
+    }
+}
+
+/// Parses expressions like `tomorrow`, `next fri 3pm`, `in 3 days` or `may 4 at 9:30`
+/// relative to `today`. Returns `None` for anything it doesn't understand, such as
+/// recurring dues, so the caller can fall back to Todoist's own parser.
+pub fn parse_due(input: &str, today: NaiveDate) -> Option<ParsedDue> {
+    let input = input.trim().to_ascii_lowercase();
+    let mut words = input.split_whitespace().collect::<Vec<_>>();
+
+    // A trailing time, optionally introduced by "at"
+    let mut time = None;
+    if let Some(last) = words.last()
+        && let Some(parsed) = parse_time(last)
+    {
+        time = Some(parsed);
+        words.pop();
+        if words.last() == Some(&"at") {
+            words.pop();
+        }
+    } else if words.len() >= 2
+        && let Some(parsed) = parse_time(&words[words.len() - 2..].concat())
+    {
+        // e.g. "3 pm"
+        time = Some(parsed);
+        words.truncate(words.len() - 2);
+        if words.last() == Some(&"at") {
+            words.pop();
+        }
+    }
+
+    let date = if words.is_empty() {
+        time.map(|_| today)?
+    } else {
+        parse_date(&words, today)?
+    };
+    Some(match time {
+        Some(time) => ParsedDue::DateTime(date.and_time(time)),
+        None => ParsedDue::Date(date),
+    })
+}
+
+pub(crate) fn parse_date(words: &[&str], today: NaiveDate) -> Option<NaiveDate> {
+    match words {
+        ["today" | "tod"] => Some(today),
+        ["tomorrow" | "tom" | "tmr"] => today.succ_opt(),
+        ["next", "week"] => next_weekday(today, Weekday::Mon),
+        ["next", "month"] => today.checked_add_months(Months::new(1))?.with_day(1),
+        ["in", amount, unit] | [amount, unit, "from", "now"] => {
+            let amount = amount.parse::<u32>().ok()?;
+            match unit.trim_end_matches('s') {
+                "day" => today.checked_add_days(Days::new(amount.into())),
+                "week" => today.checked_add_days(Days::new(u64::from(amount) * 7)),
+                "month" => today.checked_add_months(Months::new(amount)),
+                _ => None,
+            }
+        }
+        [day] | ["next" | "this", day] if parse_weekday(day).is_some() => {
+            next_weekday(today, parse_weekday(day)?)
+        }
+        [date] => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+        [month, day] | [day, month] if parse_month(month).is_some() => {
+            let day = day.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+            month_day(today, parse_month(month)?, day.parse().ok()?)
+        }
+        _ => None,
+    }
+}
+
+/// The first given weekday strictly after `today`.
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> Option<NaiveDate> {
+    let ahead = (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    today.checked_add_days(Days::new(if ahead == 0 { 7 } else { ahead.into() }))
+}
+
+/// The next occurrence of a month and day, rolling over to next year once it has passed.
+fn month_day(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if date < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    } else {
+        Some(date)
+    }
+}
+
+pub(crate) fn parse_weekday(word: &str) -> Option<Weekday> {
+    // chrono accepts both short and full English names
+    word.parse().ok()
+}
+
+pub(crate) fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    if word.len() < 3 {
+        return None;
+    }
+    let position = MONTHS.iter().position(|m| word.starts_with(m))?;
+    Some(position as u32 + 1)
+}
+
+/// Parses `15:00`, `3pm`, `3:30pm`, `noon` and `midnight`.
+pub(crate) fn parse_time(word: &str) -> Option<NaiveTime> {
+    match word {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return Some(NaiveTime::MIN),
+        _ => {}
+    }
+    let (clock, offset) = if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (word, None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        // A bare number is only a time with am/pm, otherwise "may 4" would lose its day
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Describes a date relative to `today`, e.g. "today", "in 3 days" or "2 days overdue".
+pub fn relative_date(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        -1 => "1 day overdue".to_string(),
+        days if days < 0 => format!("{} days overdue", -days),
+        days => format!("in {} days", days),
+    }
+}
+
+/// Parses an age such as `30d`, `2w` or `12h`.
+pub fn parse_age(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let unit = input.chars().last()?;
+    let amount = input[..input.len() - unit.len_utf8()].parse::<i64>().ok()?;
+    match unit {
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 15).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> ParsedDue {
+        ParsedDue::Date(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> ParsedDue {
+        ParsedDue::DateTime(NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap())
+    }
+
+    #[test]
+    fn relative_days() {
+        assert_eq!(parse_due("today", today()), Some(date(2024, 5, 15)));
+        assert_eq!(parse_due("Tomorrow", today()), Some(date(2024, 5, 16)));
+        assert_eq!(parse_due("tmr", today()), Some(date(2024, 5, 16)));
+        assert_eq!(parse_due("in 3 days", today()), Some(date(2024, 5, 18)));
+        assert_eq!(parse_due("2 weeks from now", today()), Some(date(2024, 5, 29)));
+        assert_eq!(parse_due("in 1 month", today()), Some(date(2024, 6, 15)));
+        assert_eq!(parse_due("next week", today()), Some(date(2024, 5, 20)));
+        assert_eq!(parse_due("next month", today()), Some(date(2024, 6, 1)));
+    }
+
+    #[test]
+    fn weekdays_are_strictly_after_today() {
+        assert_eq!(parse_due("fri", today()), Some(date(2024, 5, 17)));
+        assert_eq!(parse_due("next friday", today()), Some(date(2024, 5, 17)));
+        assert_eq!(parse_due("wed", today()), Some(date(2024, 5, 22)));
+    }
+
+    #[test]
+    fn month_days_roll_over_to_next_year() {
+        assert_eq!(parse_due("may 20", today()), Some(date(2024, 5, 20)));
+        assert_eq!(parse_due("4th may", today()), Some(date(2025, 5, 4)));
+        assert_eq!(parse_due("2024-02-29", today()), Some(date(2024, 2, 29)));
+        assert_eq!(parse_due("feb 30", today()), None);
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parse_due("tomorrow 3pm", today()), Some(at(2024, 5, 16, 15, 0)));
+        assert_eq!(parse_due("next fri at 9:30", today()), Some(at(2024, 5, 17, 9, 30)));
+        assert_eq!(parse_due("may 4 at 3 pm", today()), Some(at(2025, 5, 4, 15, 0)));
+        assert_eq!(parse_due("noon", today()), Some(at(2024, 5, 15, 12, 0)));
+        assert_eq!(parse_due("12am", today()), Some(at(2024, 5, 15, 0, 0)));
+        assert_eq!(parse_due("today 13pm", today()), None);
+    }
+
+    #[test]
+    fn unknown_input() {
+        for input in ["", "every day", "someday", "in three days", "25:00"] {
+            assert_eq!(parse_due(input, today()), None, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn relative_date_wording() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        assert_eq!(relative_date(day(15), today()), "today");
+        assert_eq!(relative_date(day(16), today()), "tomorrow");
+        assert_eq!(relative_date(day(18), today()), "in 3 days");
+        assert_eq!(relative_date(day(14), today()), "1 day overdue");
+        assert_eq!(relative_date(day(12), today()), "3 days overdue");
+    }
+
+    #[test]
+    fn ages() {
+        assert_eq!(parse_age("12h"), Some(Duration::hours(12)));
+        assert_eq!(parse_age("30d"), Some(Duration::days(30)));
+        assert_eq!(parse_age("2w"), Some(Duration::weeks(2)));
+        assert_eq!(parse_age("2y"), None);
+        assert_eq!(parse_age("d"), None);
+    }
+}
@@ -0,0 +1,379 @@
+use crate::models::project::{self, Project};
+use crate::models::task::Task;
+use crate::utils::dates::{ParsedDue, parse_due};
+use crate::utils::error::AppResult;
+use chrono::{Days, Local, NaiveDate};
+
+/// A parsed Todoist filter query such as `(today | overdue) & #Work & !@waiting`.
+#[derive(Clone, Debug)]
+pub enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Term(Term),
+}
+
+/// A single filter condition.
+#[derive(Clone, Debug)]
+pub enum Term {
+    Overdue,
+    NoDate,
+    Recurring,
+    /// Due on this date.
+    On(NaiveDate),
+    /// Due within this many days, starting today.
+    Within(u64),
+    Before(NaiveDate),
+    After(NaiveDate),
+    /// Created before this date.
+    CreatedBefore(NaiveDate),
+    /// Created after this date.
+    CreatedAfter(NaiveDate),
+    /// Todoist priority as shown to users: p1 (urgent) to p4.
+    Priority(u8),
+    Project(String),
+    /// `##Project`: the project and its sub-projects.
+    ProjectTree(String),
+    Label(String),
+    NoLabels,
+    Subtask,
+    /// Assignees aren't synced, so this never matches.
+    Assigned,
+    Search(String),
+    All,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Or,
+    And,
+    Not,
+    Open,
+    Close,
+    Term(String),
+}
+
+impl Filter {
+    /// Parses a filter query, relative to `today` for date terms.
+    pub fn parse(query: &str, today: NaiveDate) -> AppResult<Self> {
+        let tokens = tokenize(query);
+        let mut pos = 0;
+        let filter = parse_or(&tokens, &mut pos, today)?;
+        if pos < tokens.len() {
+            return Err(anyhow::anyhow!(
+                "Unexpected {:?} in filter '{}'",
+                tokens[pos],
+                query
+            ));
+        }
+        Ok(filter)
+    }
+
+    /// Checks whether a task matches the filter; `projects` resolves `#Project` names.
+    pub fn matches(&self, task: &Task, projects: &[Project], today: NaiveDate) -> bool {
+        match self {
+            Filter::Or(a, b) => {
+                a.matches(task, projects, today) || b.matches(task, projects, today)
+            }
+            Filter::And(a, b) => {
+                a.matches(task, projects, today) && b.matches(task, projects, today)
+            }
+            Filter::Not(inner) => !inner.matches(task, projects, today),
+            Filter::Term(term) => term.matches(task, projects, today),
+        }
+    }
+}
+
+impl Term {
+    fn parse(text: &str, today: NaiveDate) -> AppResult<Self> {
+        let lower = text.to_ascii_lowercase();
+        let term = match lower.as_str() {
+            "overdue" | "od" => Term::Overdue,
+            "no date" | "no due date" => Term::NoDate,
+            "recurring" => Term::Recurring,
+            "no labels" => Term::NoLabels,
+            "subtask" => Term::Subtask,
+            "assigned" => Term::Assigned,
+            "all" | "view all" => Term::All,
+            "p1" | "p2" | "p3" | "p4" => Term::Priority(lower.as_bytes()[1] - b'0'),
+            _ => {
+                if let Some(name) = text.strip_prefix("##") {
+                    Term::ProjectTree(name.to_string())
+                } else if let Some(name) = text.strip_prefix('#') {
+                    Term::Project(name.to_string())
+                } else if let Some(label) = text.strip_prefix('@') {
+                    Term::Label(label.to_string())
+                } else if let Some(search) = lower.strip_prefix("search:") {
+                    Term::Search(search.trim().to_string())
+                } else if let Some(date) = lower.strip_prefix("due before:") {
+                    Term::Before(parse_date(date, today)?)
+                } else if let Some(date) = lower.strip_prefix("due after:") {
+                    Term::After(parse_date(date, today)?)
+                } else if let Some(date) = lower.strip_prefix("created before:") {
+                    Term::CreatedBefore(parse_date(date, today)?)
+                } else if let Some(date) = lower.strip_prefix("created after:") {
+                    Term::CreatedAfter(parse_date(date, today)?)
+                } else if let Some(days) = lower
+                    .trim_start_matches("next ")
+                    .strip_suffix(" days")
+                    .and_then(|d| d.parse().ok())
+                {
+                    Term::Within(days)
+                } else {
+                    Term::On(parse_date(&lower, today)?)
+                }
+            }
+        };
+        Ok(term)
+    }
+
+    fn matches(&self, task: &Task, projects: &[Project], today: NaiveDate) -> bool {
+        let due = task.due.as_ref();
+        // Parsed only by the terms that need them, as filters run over every task on each frame
+        let date = || due.and_then(|d| d.naive_date());
+        let created = || task.added().map(|at| at.with_timezone(&Local).date_naive());
+        match self {
+            Term::Overdue => task.is_overdue(today),
+            Term::NoDate => due.is_none(),
+            Term::Recurring => due.is_some_and(|d| d.is_recurring),
+            Term::On(day) => date() == Some(*day),
+            Term::Within(days) => date().is_some_and(|d| {
+                d >= today
+                    && today
+                        .checked_add_days(Days::new(*days))
+                        .is_some_and(|end| d < end)
+            }),
+            Term::Before(day) => date().is_some_and(|d| d < *day),
+            Term::After(day) => date().is_some_and(|d| d > *day),
+            Term::CreatedBefore(day) => created().is_some_and(|d| d < *day),
+            Term::CreatedAfter(day) => created().is_some_and(|d| d > *day),
+            // p1 is the API's priority 4
+            Term::Priority(p) => task.priority == 5 - p,
+            Term::Project(name) => projects
+                .iter()
+                .any(|p| p.id == task.project_id && p.name.eq_ignore_ascii_case(name)),
+            Term::ProjectTree(name) => projects.iter().any(|p| {
+                p.name.eq_ignore_ascii_case(name) && project::is_within(projects, &task.project_id, &p.id)
+            }),
+            Term::Label(label) => task.labels.iter().any(|l| l.eq_ignore_ascii_case(label)),
+            Term::NoLabels => task.labels.is_empty(),
+            Term::Subtask => task.parent_id.is_some(),
+            Term::Assigned => false,
+            Term::Search(text) => task.title.to_lowercase().contains(text.as_str()),
+            Term::All => true,
+        }
+    }
+}
+
+/// Parses a date term, also accepting Todoist's relative `-30 days` form.
+fn parse_date(text: &str, today: NaiveDate) -> AppResult<NaiveDate> {
+    if let Some(days) = text
+        .trim()
+        .strip_prefix('-')
+        .and_then(|rest| rest.trim_end_matches('s').strip_suffix(" day"))
+        .and_then(|days| days.trim().parse().ok())
+    {
+        return today
+            .checked_sub_days(Days::new(days))
+            .ok_or_else(|| anyhow::anyhow!("Date out of range in '{}'", text.trim()));
+    }
+    match parse_due(text, today) {
+        Some(ParsedDue::Date(date)) => Ok(date),
+        Some(ParsedDue::DateTime(datetime)) => Ok(datetime.date()),
+        None => Err(anyhow::anyhow!("Unknown filter term '{}'", text.trim())),
+    }
+}
+
+/// Splits a query into operators and trimmed terms; `,` is treated like `|`.
+fn tokenize(query: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut term = String::new();
+    for c in query.chars() {
+        let token = match c {
+            '|' | ',' => Token::Or,
+            '&' => Token::And,
+            '!' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            _ => {
+                term.push(c);
+                continue;
+            }
+        };
+        flush(&mut term, &mut tokens);
+        tokens.push(token);
+    }
+    flush(&mut term, &mut tokens);
+    tokens
+}
+
+fn flush(term: &mut String, tokens: &mut Vec<Token>) {
+    if !term.trim().is_empty() {
+        tokens.push(Token::Term(term.trim().to_string()));
+    }
+    term.clear();
+}
+
+fn parse_or(tokens: &[Token], pos: &mut usize, today: NaiveDate) -> AppResult<Filter> {
+    let mut filter = parse_and(tokens, pos, today)?;
+    while tokens.get(*pos) == Some(&Token::Or) {
+        *pos += 1;
+        filter = Filter::Or(Box::new(filter), Box::new(parse_and(tokens, pos, today)?));
+    }
+    Ok(filter)
+}
+
+fn parse_and(tokens: &[Token], pos: &mut usize, today: NaiveDate) -> AppResult<Filter> {
+    let mut filter = parse_unary(tokens, pos, today)?;
+    while tokens.get(*pos) == Some(&Token::And) {
+        *pos += 1;
+        filter = Filter::And(Box::new(filter), Box::new(parse_unary(tokens, pos, today)?));
+    }
+    Ok(filter)
+}
+
+fn parse_unary(tokens: &[Token], pos: &mut usize, today: NaiveDate) -> AppResult<Filter> {
+    let token = tokens
+        .get(*pos)
+        .ok_or_else(|| anyhow::anyhow!("Filter ended unexpectedly"))?;
+    *pos += 1;
+    match token {
+        Token::Not => Ok(Filter::Not(Box::new(parse_unary(tokens, pos, today)?))),
+        Token::Open => {
+            let filter = parse_or(tokens, pos, today)?;
+            if tokens.get(*pos) != Some(&Token::Close) {
+                return Err(anyhow::anyhow!("Missing ')' in filter"));
+            }
+            *pos += 1;
+            Ok(filter)
+        }
+        Token::Term(text) => Ok(Filter::Term(Term::parse(text, today)?)),
+        other => Err(anyhow::anyhow!("Unexpected {:?} in filter", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::task::NewTask;
+
+    /// A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 15).unwrap()
+    }
+
+    fn project(id: &str, name: &str, parent_id: Option<&str>) -> Project {
+        Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            is_archived: false,
+            is_favorite: false,
+            is_shared: false,
+            color: String::new(),
+            parent_id: parent_id.map(str::to_string),
+        }
+    }
+
+    fn projects() -> Vec<Project> {
+        vec![
+            project("1", "Work", None),
+            project("2", "Meetings", Some("1")),
+            project("3", "Home", None),
+        ]
+    }
+
+    /// A task in `project_id` with the given Todoist priority (p1 is 4), labels and due date.
+    fn task(project_id: &str, priority: u8, labels: &[&str], due: Option<&str>) -> Task {
+        NewTask {
+            project_id: Some(project_id.to_string()),
+            priority: Some(priority),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            due_date: due.map(str::to_string),
+            ..NewTask::new("Task")
+        }
+        .preview("abc")
+    }
+
+    fn matches(query: &str, task: &Task) -> bool {
+        Filter::parse(query, today()).unwrap().matches(task, &projects(), today())
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let filter = Filter::parse("p1 | p2 & @home", today()).unwrap();
+        assert!(matches!(&filter, Filter::Or(_, b) if matches!(**b, Filter::And(_, _))));
+        assert!(matches("p1 | p2 & @home", &task("3", 4, &[], None)));
+        assert!(!matches("p1 | p2 & @home", &task("3", 3, &[], None)));
+        assert!(matches("p1 | p2 & @home", &task("3", 3, &["home"], None)));
+        assert!(!matches("(p1 | p2) & @home", &task("3", 4, &[], None)));
+    }
+
+    #[test]
+    fn comma_is_or() {
+        assert!(matches("p1, p4", &task("3", 1, &[], None)));
+    }
+
+    #[test]
+    fn not_applies_to_the_next_term_or_group() {
+        let task = task("3", 4, &["waiting"], None);
+        assert!(!matches("!@waiting", &task));
+        assert!(matches("!@waiting | p1", &task));
+        assert!(!matches("!(@waiting | p1)", &task));
+        assert!(matches("!!@waiting", &task));
+    }
+
+    #[test]
+    fn project_matches_only_that_project() {
+        assert!(matches("#Work", &task("1", 1, &[], None)));
+        assert!(matches("#work", &task("1", 1, &[], None)));
+        assert!(!matches("#Work", &task("2", 1, &[], None)));
+    }
+
+    #[test]
+    fn project_tree_includes_sub_projects() {
+        assert!(matches("##Work", &task("1", 1, &[], None)));
+        assert!(matches("##Work", &task("2", 1, &[], None)));
+        assert!(!matches("##Work", &task("3", 1, &[], None)));
+    }
+
+    #[test]
+    fn date_keywords() {
+        let due_today = task("1", 1, &[], Some("2024-05-15"));
+        let due_tomorrow = task("1", 1, &[], Some("2024-05-16"));
+        let overdue = task("1", 1, &[], Some("2024-05-10"));
+        let undated = task("1", 1, &[], None);
+        assert!(matches("today", &due_today));
+        assert!(!matches("today", &due_tomorrow));
+        assert!(matches("tomorrow", &due_tomorrow));
+        assert!(matches("overdue", &overdue));
+        assert!(!matches("overdue", &due_today));
+        assert!(matches("no date", &undated));
+        assert!(!matches("no date", &due_today));
+        assert!(matches("2024-05-16", &due_tomorrow));
+    }
+
+    #[test]
+    fn day_ranges_start_today() {
+        assert!(matches("7 days", &task("1", 1, &[], Some("2024-05-15"))));
+        assert!(matches("next 7 days", &task("1", 1, &[], Some("2024-05-21"))));
+        assert!(!matches("7 days", &task("1", 1, &[], Some("2024-05-22"))));
+        assert!(!matches("7 days", &task("1", 1, &[], Some("2024-05-14"))));
+    }
+
+    #[test]
+    fn due_before_and_after() {
+        let due = task("1", 1, &[], Some("2024-05-18"));
+        assert!(matches("due before: may 20", &due));
+        assert!(!matches("due before: 2024-05-18", &due));
+        assert!(matches("due after: tomorrow", &due));
+        assert!(matches("due after: -3 days", &due));
+        assert!(!matches("due after: may 18", &due));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for query in ["", "(today", "today )", "today &", "& today", "!", "someday", "due before: soon"] {
+            assert!(Filter::parse(query, today()).is_err(), "{:?} should not parse", query);
+        }
+    }
+}
@@ -1 +1,9 @@
-pub mod error;
\ No newline at end of file
+pub mod browser;
+pub mod color;
+pub mod dates;
+pub mod error;
+pub mod filter;
+pub mod progress;
+pub mod random;
+pub mod recurrence;
+pub mod text;
\ No newline at end of file
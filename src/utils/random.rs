@@ -0,0 +1,7 @@
+/// Picks a random index below `len`, or `None` when `len` is zero.
+///
+/// Draws on v4 UUIDs, which are already randomly generated, rather than pulling in a crate for
+/// the odd random choice.
+pub fn index(len: usize) -> Option<usize> {
+    (len > 0).then(|| (uuid::Uuid::new_v4().as_u128() % len as u128) as usize)
+}
@@ -0,0 +1,277 @@
+//! Recurring due strings such as `every 2nd wed` or `every other fri at 9am`, parsed closely
+//! enough to Todoist's rules to preview their next dates before a task is saved with one.
+
+use crate::utils::dates::{ParsedDue, parse_date, parse_month, parse_time, parse_weekday};
+use chrono::{Datelike, Days, NaiveDate, NaiveTime, Weekday};
+
+/// How far ahead occurrences are looked for, so rules that rarely match still end.
+const HORIZON_YEARS: u32 = 50;
+
+/// A recurring schedule parsed from a due string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recurrence {
+    rule: Rule,
+    time: Option<NaiveTime>,
+    /// First day the schedule may fall on: the `starting` date, or today.
+    start: NaiveDate,
+    /// Last day the schedule may fall on, from `until` or `ending`.
+    until: Option<NaiveDate>,
+    /// Whether it's an `every!` schedule, counted from each completion instead of the due date.
+    pub from_completion: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Rule {
+    /// Every `n` days.
+    Days(u32),
+    /// The given weekdays of every `n`th week.
+    Weeks(u32, Vec<Weekday>),
+    /// The given days of every `n`th month.
+    Months(u32, Vec<MonthDay>),
+    /// A month and day every `n` years.
+    Years(u32, u32, u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MonthDay {
+    /// A day of the month, moved to the last day in shorter months.
+    Day(u32),
+    LastDay,
+    /// The `n`th given weekday of the month, or the last one when `n` is 0.
+    Weekday(u32, Weekday),
+}
+
+/// Parses a recurring due string relative to `today`. Returns `None` for one-off dates and for
+/// schedules it doesn't understand, which Todoist may still accept.
+pub fn parse_recurrence(input: &str, today: NaiveDate) -> Option<Recurrence> {
+    let input = input.trim().to_ascii_lowercase().replace(',', " ");
+    let mut words = input.split_whitespace().filter(|w| *w != "and").collect::<Vec<_>>();
+
+    // Trailing `until <date>` or `ending <date>`, then `starting <date>`
+    let mut until = None;
+    if let Some(i) = words.iter().position(|w| *w == "until" || *w == "ending") {
+        until = Some(parse_date(&words[i + 1..], today)?);
+        words.truncate(i);
+    }
+    let mut start = today;
+    if let Some(i) = words.iter().position(|w| *w == "starting" || *w == "from") {
+        start = parse_date(&words[i + 1..], today)?;
+        words.truncate(i);
+    }
+
+    // A time after the schedule, optionally introduced by "at"
+    let mut time = None;
+    if let Some(i) = words.iter().position(|w| *w == "at") {
+        time = Some(parse_time(&words[i + 1..].concat())?);
+        words.truncate(i);
+    } else if let Some(parsed) = words.last().and_then(|w| parse_time(w)) {
+        time = Some(parsed);
+        words.pop();
+    }
+
+    let (every, rest) = words.split_first()?;
+    let (from_completion, rest) = match *every {
+        "every" | "ev" => (false, rest),
+        "every!" | "ev!" => (true, rest),
+        "daily" => (false, &["day"][..]),
+        "weekly" => (false, &["week"][..]),
+        "monthly" => (false, &["month"][..]),
+        "yearly" | "annually" => (false, &["year"][..]),
+        _ => return None,
+    };
+    let rule = parse_rule(rest, start)?;
+    Some(Recurrence { rule, time, start, until, from_completion })
+}
+
+/// Parses what follows `every`.
+fn parse_rule(words: &[&str], start: NaiveDate) -> Option<Rule> {
+    let (interval, unit) = match words {
+        ["other", rest @ ..] => (2, rest),
+        [n, rest @ ..] if rest.len() == 1 && n.parse::<u32>().is_ok_and(|n| n > 0) => {
+            (n.parse().ok()?, rest)
+        }
+        _ => (1, words),
+    };
+    match unit {
+        [] | ["day" | "days"] => return Some(Rule::Days(interval)),
+        ["week" | "weeks"] => return Some(Rule::Weeks(interval, vec![start.weekday()])),
+        ["month" | "months"] => return Some(Rule::Months(interval, vec![MonthDay::Day(start.day())])),
+        ["year" | "years"] => return Some(Rule::Years(interval, start.month(), start.day())),
+        ["weekday" | "workday"] => {
+            let days = vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+            return Some(Rule::Weeks(interval, days));
+        }
+        ["weekend"] => return Some(Rule::Weeks(interval, vec![Weekday::Sat, Weekday::Sun])),
+        [month, day] | [day, month] if parse_month(month).is_some() => {
+            return Some(Rule::Years(interval, parse_month(month)?, ordinal(day)?));
+        }
+        _ => {}
+    }
+    if let Some(days) = unit.iter().map(|w| parse_weekday(w)).collect::<Option<Vec<_>>>() {
+        return Some(Rule::Weeks(interval, days));
+    }
+    // Days of the month, e.g. `15th`, `1st 15th`, `last day`, `2nd wed` or `last fri`
+    let mut days = Vec::new();
+    let mut rest = unit;
+    while let [first, tail @ ..] = rest {
+        let nth = if *first == "last" { Some(0) } else { ordinal(first) };
+        match tail.first().map(|w| (*w, parse_weekday(w))) {
+            Some(("day", _)) if *first == "last" => {
+                days.push(MonthDay::LastDay);
+                rest = &tail[1..];
+            }
+            Some((_, Some(weekday))) if nth.is_some_and(|n| n <= 5) => {
+                days.push(MonthDay::Weekday(nth?, weekday));
+                rest = &tail[1..];
+            }
+            _ => {
+                days.push(MonthDay::Day(nth.filter(|n| (1..=31).contains(n))?));
+                rest = tail;
+            }
+        }
+    }
+    Some(Rule::Months(interval, days))
+}
+
+/// Parses `15`, `15th`, `2nd` or `second`.
+fn ordinal(word: &str) -> Option<u32> {
+    const WORDS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+    if let Some(i) = WORDS.iter().position(|w| *w == word) {
+        return Some(i as u32 + 1);
+    }
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if digits.len() != word.len() && !["st", "nd", "rd", "th"].contains(&&word[digits.len()..]) {
+        return None;
+    }
+    digits.parse().ok().filter(|&n| n > 0)
+}
+
+impl Recurrence {
+    /// The next `count` dates on or after the start, fewer when the schedule ends first.
+    pub fn occurrences(&self, count: usize) -> Vec<ParsedDue> {
+        let horizon = self.start.checked_add_months(chrono::Months::new(12 * HORIZON_YEARS));
+        let last = match (self.until, horizon) {
+            (Some(until), Some(horizon)) => until.min(horizon),
+            (until, horizon) => until.or(horizon).unwrap_or(NaiveDate::MAX),
+        };
+        self.start
+            .iter_days()
+            .take_while(|date| *date <= last)
+            .filter(|date| self.falls_on(*date))
+            .take(count)
+            .map(|date| match self.time {
+                Some(time) => ParsedDue::DateTime(date.and_time(time)),
+                None => ParsedDue::Date(date),
+            })
+            .collect()
+    }
+
+    fn falls_on(&self, date: NaiveDate) -> bool {
+        let months = |date: NaiveDate| date.year() * 12 + date.month0() as i32;
+        match &self.rule {
+            Rule::Days(n) => (date - self.start).num_days() % i64::from(*n) == 0,
+            Rule::Weeks(n, days) => {
+                let monday = |date: NaiveDate| date - Days::new(date.weekday().num_days_from_monday().into());
+                let weeks = (monday(date) - monday(self.start)).num_weeks();
+                weeks % i64::from(*n) == 0 && days.contains(&date.weekday())
+            }
+            Rule::Months(n, days) => {
+                (months(date) - months(self.start)) % *n as i32 == 0
+                    && days.iter().any(|day| day.matches(date))
+            }
+            Rule::Years(n, month, day) => {
+                (date.year() - self.start.year()) % *n as i32 == 0
+                    && date.month() == *month
+                    && MonthDay::Day(*day).matches(date)
+            }
+        }
+    }
+}
+
+impl MonthDay {
+    fn matches(self, date: NaiveDate) -> bool {
+        let month_length = date
+            .with_day(1)
+            .and_then(|first| first.checked_add_months(chrono::Months::new(1)))
+            .map_or(31, |next| (next - Days::new(1)).day());
+        match self {
+            MonthDay::Day(day) => date.day() == day.min(month_length),
+            MonthDay::LastDay => date.day() == month_length,
+            MonthDay::Weekday(0, weekday) => date.weekday() == weekday && date.day() + 7 > month_length,
+            MonthDay::Weekday(n, weekday) => date.weekday() == weekday && (date.day() - 1) / 7 + 1 == n,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 5, 15).unwrap()
+    }
+
+    /// The next `count` dates of `input` as `YYYY-MM-DD` strings, with the time when there is one.
+    fn next(input: &str, count: usize) -> Vec<String> {
+        parse_recurrence(input, today())
+            .unwrap_or_else(|| panic!("{:?} should parse", input))
+            .occurrences(count)
+            .iter()
+            .map(|due| due.format("%Y-%m-%d"))
+            .collect()
+    }
+
+    #[test]
+    fn days_and_weeks() {
+        assert_eq!(next("every day", 2), ["2024-05-15", "2024-05-16"]);
+        assert_eq!(next("every 3 days", 3), ["2024-05-15", "2024-05-18", "2024-05-21"]);
+        assert_eq!(next("weekly", 2), ["2024-05-15", "2024-05-22"]);
+        assert_eq!(next("every mon, fri", 3), ["2024-05-17", "2024-05-20", "2024-05-24"]);
+        assert_eq!(next("every other fri", 2), ["2024-05-17", "2024-05-31"]);
+        assert_eq!(next("every weekend", 2), ["2024-05-18", "2024-05-19"]);
+        assert_eq!(next("every weekday", 3), ["2024-05-15", "2024-05-16", "2024-05-17"]);
+    }
+
+    #[test]
+    fn days_of_the_month() {
+        assert_eq!(next("every 1st 15th", 3), ["2024-05-15", "2024-06-01", "2024-06-15"]);
+        assert_eq!(next("every last day", 2), ["2024-05-31", "2024-06-30"]);
+        assert_eq!(next("every 2nd wed", 2), ["2024-06-12", "2024-07-10"]);
+        assert_eq!(next("every last fri", 2), ["2024-05-31", "2024-06-28"]);
+        // Shorter months fall back to their last day
+        assert_eq!(next("every 31st", 2), ["2024-05-31", "2024-06-30"]);
+    }
+
+    #[test]
+    fn years() {
+        assert_eq!(next("every jan 3", 2), ["2025-01-03", "2026-01-03"]);
+        assert_eq!(next("yearly", 2), ["2024-05-15", "2025-05-15"]);
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(next("every other fri at 9am", 1), ["2024-05-17 09:00"]);
+        assert_eq!(next("every day 18:30", 1), ["2024-05-15 18:30"]);
+    }
+
+    #[test]
+    fn start_and_end() {
+        assert_eq!(next("every day starting may 20", 1), ["2024-05-20"]);
+        assert_eq!(next("every day until may 16", 5), ["2024-05-15", "2024-05-16"]);
+        assert!(next("every jan 3 until dec 1", 1).is_empty());
+    }
+
+    #[test]
+    fn from_completion() {
+        assert!(parse_recurrence("every! 3 days", today()).unwrap().from_completion);
+        assert!(!parse_recurrence("every 3 days", today()).unwrap().from_completion);
+    }
+
+    #[test]
+    fn not_recurring() {
+        for input in ["tomorrow", "every 0 days", "every 32nd", "every blue moon", "every day at 25:00"] {
+            assert_eq!(parse_recurrence(input, today()), None, "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Lowercases text and collapses everything but letters and digits into single spaces.
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Scores how alike two titles are, from 0.0 to 1.0, ignoring case and punctuation.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a = normalize(a).chars().collect::<Vec<_>>();
+    let b = normalize(b).chars().collect::<Vec<_>>();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / longest as f64
+}
+
+/// Gets how many terminal columns text takes; CJK characters and most emoji take two.
+pub fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Truncates to at most `max_width` terminal columns, ending with an ellipsis when shortened.
+pub fn truncate(text: &str, max_width: usize) -> String {
+    if width(text) <= max_width {
+        return text.to_string();
+    }
+    // The ellipsis takes one column
+    let budget = max_width.saturating_sub(1);
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if used + c_width > budget {
+            break;
+        }
+        out.push(c);
+        used += c_width;
+    }
+    out.push('…');
+    out
+}
+
+/// Pads text with spaces to `target` terminal columns; `format!` pads by characters, which
+/// misaligns wide ones.
+pub fn pad(text: &str, target: usize) -> String {
+    format!("{}{}", text, " ".repeat(target.saturating_sub(width(text))))
+}
+
+/// Wraps text into lines of at most `max_width` terminal columns, breaking at spaces and
+/// splitting words longer than a line. Line breaks in the text are kept.
+pub fn wrap(text: &str, max_width: usize) -> Vec<String> {
+    let max_width = max_width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut used = 0;
+        for word in paragraph.split_whitespace() {
+            let word_width = width(word);
+            if used > 0 && used + 1 + word_width <= max_width {
+                line.push(' ');
+                line.push_str(word);
+                used += 1 + word_width;
+                continue;
+            }
+            if used > 0 {
+                lines.push(std::mem::take(&mut line));
+                used = 0;
+            }
+            for c in word.chars() {
+                let c_width = c.width().unwrap_or(0);
+                if used > 0 && used + c_width > max_width {
+                    lines.push(std::mem::take(&mut line));
+                    used = 0;
+                }
+                line.push(c);
+                used += c_width;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Formats a count with thousands separators, e.g. "1,240".
+pub fn thousands(count: usize) -> String {
+    let digits = count.to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Finds the shortest prefix length, at least `min`, that tells all the given IDs apart.
+pub fn unique_prefix_len<'a>(ids: impl IntoIterator<Item = &'a str>, min: usize) -> usize {
+    let mut ids = ids.into_iter().collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.windows(2)
+        .map(|pair| {
+            let common = pair[0]
+                .chars()
+                .zip(pair[1].chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            common + 1
+        })
+        .fold(min, usize::max)
+}
@@ -0,0 +1,27 @@
+use crate::controller::app::App;
+use crate::utils::color;
+use ratatui::style::Color;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders};
+
+pub mod project_list;
+
+/// The terminal color closest to a Todoist color that the terminal can show.
+pub fn terminal_color(rgb: color::Rgb) -> Color {
+    if color::truecolor() {
+        Color::Rgb(rgb.0, rgb.1, rgb.2)
+    } else {
+        Color::Indexed(rgb.xterm256())
+    }
+}
+
+/// A titled pane: bordered, or with `--plain-ui` just the title line so screen readers don't
+/// read out box-drawing characters.
+pub fn pane<'a>(app: &App, title: impl Into<Line<'a>>) -> Block<'a> {
+    let block = Block::default().title(title);
+    if app.plain_ui() {
+        block
+    } else {
+        block.borders(Borders::ALL)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::controller::app::{App, SidebarRow};
+use crate::utils::color;
+use crate::utils::text::width as text_width;
+use crate::views::{pane, terminal_color};
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem};
+use ratatui::Frame;
+
+/// Narrowest the project sidebar gets, so its title fits.
+const MIN_WIDTH: u16 = 20;
+
+/// Widest the project sidebar grows, so long project names don't squeeze the list.
+const MAX_WIDTH: u16 = 28;
+
+/// Width of the project sidebar: its longest entry, at least enough for the title and up to
+/// `MAX_WIDTH`, or 0 to leave it out while there are no projects.
+pub fn width(app: &App) -> u16 {
+    let rows = app.sidebar_rows();
+    if rows.len() <= 1 {
+        return 0;
+    }
+    let longest = rows
+        .iter()
+        .map(|row| match row {
+            SidebarRow::AllTasks => text_width("All tasks"),
+            SidebarRow::Heading(heading) => text_width(heading),
+            SidebarRow::Project(depth, p) => depth * 2 + text_width(&p.name) + 2,
+            SidebarRow::Label(l) => text_width(&l.name) + 3,
+            SidebarRow::Filter(f) => text_width(&f.name) + 2,
+        })
+        .max()
+        .unwrap_or(0);
+    // Borders and the selection prefix
+    (longest as u16 + 4).clamp(MIN_WIDTH, MAX_WIDTH)
+}
+
+/// A dot in a Todoist color, left out in plain UI and for colors it doesn't know.
+fn dot(todoist_color: &str, plain: bool) -> Option<Span<'static>> {
+    let rgb = color::todoist_color(todoist_color).filter(|_| !plain)?;
+    Some(Span::styled("● ", Style::default().fg(terminal_color(rgb))))
+}
+
+/// Renders the project sidebar: "All tasks", the favorites, the active projects as a tree and,
+/// when shown, the archived ones, with a dot in each project's color. The title shows whether
+/// `j`/`k` move through it.
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let focused = app.sidebar_focused();
+    let plain = app.plain_ui();
+    let rows = app.sidebar_rows();
+    let selected = app.sidebar_selected();
+    // Scrolled so the selected entry stays in view
+    let height = area.height.saturating_sub(2).max(1) as usize;
+    let items = rows
+        .iter()
+        .enumerate()
+        .skip((selected + 1).saturating_sub(height))
+        .map(|(i, row)| {
+            let prefix = if i == selected { "> " } else { "  " };
+            let mut spans = vec![Span::raw(prefix)];
+            match row {
+                SidebarRow::AllTasks => spans.push(Span::raw("All tasks")),
+                SidebarRow::Heading(heading) if plain => spans.push(Span::raw(*heading)),
+                SidebarRow::Heading(heading) => {
+                    spans.push(Span::styled(*heading, Style::default().add_modifier(Modifier::DIM)))
+                }
+                SidebarRow::Project(depth, p) => {
+                    spans.push(Span::raw("  ".repeat(*depth)));
+                    spans.extend(dot(&p.color, plain));
+                    spans.push(Span::raw(p.name.as_str()));
+                }
+                SidebarRow::Label(l) => {
+                    spans.extend(dot(&l.color, plain));
+                    spans.push(Span::raw(format!("@{}", l.name)));
+                }
+                SidebarRow::Filter(f) => {
+                    spans.extend(dot(&f.color, plain));
+                    spans.push(Span::raw(f.name.as_str()));
+                }
+            }
+            let item = ListItem::new(Line::from(spans));
+            if i == selected && focused {
+                item.style(Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                item
+            }
+        })
+        .collect::<Vec<_>>();
+    let title = if focused { "Projects (l: list)" } else { "Projects (h)" };
+    f.render_widget(List::new(items).block(pane(app, title)), area);
+}